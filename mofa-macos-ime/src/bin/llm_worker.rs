@@ -0,0 +1,20 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+
+/// LLM 子进程：由输入法通过 stdin/stdout 行协议驱动，崩溃不影响主进程。
+fn main() -> Result<()> {
+    let mut args = std::env::args().skip(1);
+    let mut model_path: Option<PathBuf> = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--model" => model_path = args.next().map(PathBuf::from),
+            other => bail!("未知参数: {other}"),
+        }
+    }
+
+    let Some(model_path) = model_path else {
+        bail!("用法: mofa-llm-worker --model <模型路径>");
+    };
+    mofa_input::llm::worker::serve(&model_path)
+}
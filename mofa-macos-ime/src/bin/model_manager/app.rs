@@ -381,11 +381,64 @@ impl eframe::App for ModelManagerApp {
             }
             ui.add_space(8.0);
 
+            let old_trigger = self.config.trigger_mode;
+            let old_toggle_silence = self.config.toggle_silence_ms;
+            let old_toggle_max = self.config.toggle_max_secs;
+            let old_pre_roll = self.config.pre_roll;
+            let old_pre_roll_ms = self.config.pre_roll_ms;
+            let old_max_record = self.config.max_record_secs;
             let old_output = self.config.output_mode;
             let old_llm = self.config.llm_model;
+            let old_llm_backend = self.config.llm_backend;
             let old_asr = self.config.asr_model;
+            let old_asr_language = self.config.asr_language;
+            let old_asr_beam = self.config.asr_beam_size;
+            let old_asr_threads = self.config.asr_threads;
+            let old_asr_prompt = self.config.asr_prompt.clone();
+            let old_asr_translate = self.config.asr_translate;
+            let old_asr_temperature_inc = self.config.asr_temperature_inc;
+            let old_asr_no_speech = self.config.asr_no_speech_thold;
+            let old_output_script = self.config.output_script;
+            let old_refine_policy = self.config.refine_policy;
             let old_show_orb = self.config.show_floating_orb;
             let mut setting_changed = false;
+            ui.horizontal(|ui| {
+                ui.label("触发方式:");
+                egui::ComboBox::from_id_source("trigger_mode_choice")
+                    .selected_text(self.config.trigger_mode.label())
+                    .show_ui(ui, |ui| {
+                        for mode in TriggerModeCfg::all() {
+                            ui.selectable_value(&mut self.config.trigger_mode, mode, mode.label());
+                        }
+                    });
+            });
+            if self.config.trigger_mode == TriggerModeCfg::Toggle {
+                ui.horizontal(|ui| {
+                    ui.label("停顿结束(毫秒):");
+                    ui.add(egui::Slider::new(
+                        &mut self.config.toggle_silence_ms,
+                        500..=10_000,
+                    ));
+                    ui.label("最长(秒):");
+                    ui.add(egui::Slider::new(&mut self.config.toggle_max_secs, 10..=600));
+                });
+            }
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.config.pre_roll, "预录")
+                    .on_hover_text(
+                        "麦克风常开，把按下热键前的一小段并入录音，避免吞掉第一个字；\
+                         空闲音频只在内存中滚动保留并随时丢弃，开启时托盘标题带 ●",
+                    );
+                if self.config.pre_roll {
+                    ui.label("预录(毫秒):");
+                    ui.add(egui::Slider::new(&mut self.config.pre_roll_ms, 100..=1000));
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("最长录音(秒):");
+                ui.add(egui::Slider::new(&mut self.config.max_record_secs, 30..=3600))
+                    .on_hover_text("超过后自动结束录音，避免忘记松开时内存无限增长");
+            });
             ui.horizontal(|ui| {
                 ui.label("发送内容:");
                 egui::ComboBox::from_id_source("send_output_mode")
@@ -417,33 +470,95 @@ impl eframe::App for ModelManagerApp {
                         }
                     });
             });
+            ui.horizontal(|ui| {
+                ui.label("LLM 运行方式:");
+                egui::ComboBox::from_id_source("llm_backend_choice")
+                    .selected_text(self.config.llm_backend.label())
+                    .show_ui(ui, |ui| {
+                        for backend in LlmBackendCfg::all() {
+                            ui.selectable_value(
+                                &mut self.config.llm_backend,
+                                backend,
+                                backend.label(),
+                            );
+                        }
+                    });
+            });
             ui.horizontal(|ui| {
                 ui.label("ASR 模型:");
                 egui::ComboBox::from_id_source("asr_model_choice")
                     .selected_text(self.config.asr_model.label())
                     .show_ui(ui, |ui| {
-                        ui.selectable_value(&mut self.config.asr_model, AsrChoice::Auto, "自动");
-                        ui.selectable_value(
-                            &mut self.config.asr_model,
-                            AsrChoice::Tiny,
-                            AsrChoice::Tiny.label(),
-                        );
-                        ui.selectable_value(
-                            &mut self.config.asr_model,
-                            AsrChoice::Base,
-                            AsrChoice::Base.label(),
-                        );
-                        ui.selectable_value(
-                            &mut self.config.asr_model,
-                            AsrChoice::Small,
-                            AsrChoice::Small.label(),
-                        );
-                        ui.selectable_value(
-                            &mut self.config.asr_model,
-                            AsrChoice::Medium,
-                            AsrChoice::Medium.label(),
-                        );
+                        for choice in AsrChoice::all() {
+                            ui.selectable_value(&mut self.config.asr_model, choice, choice.label());
+                        }
+                    });
+            });
+            if self.config.asr_model == AsrChoice::ParaformerSmall {
+                ui.small("Paraformer 需手动放置 model.onnx / tokens.txt / am.mvn 至 ~/.mofa/models/funasr-small，并安装 ONNX Runtime。");
+            }
+
+            ui.horizontal(|ui| {
+                ui.label("识别语言:");
+                egui::ComboBox::from_id_source("asr_language_choice")
+                    .selected_text(self.config.asr_language.label())
+                    .show_ui(ui, |ui| {
+                        for lang in AsrLanguageCfg::all() {
+                            ui.selectable_value(&mut self.config.asr_language, lang, lang.label());
+                        }
                     });
+                ui.label("Beam 宽度:");
+                ui.add(egui::Slider::new(&mut self.config.asr_beam_size, 1..=8));
+            });
+            ui.collapsing("Whisper 高级参数", |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("线程数:");
+                    ui.add(egui::Slider::new(&mut self.config.asr_threads, 0..=16))
+                        .on_hover_text("0 为自动");
+                    ui.checkbox(&mut self.config.asr_translate, "翻译为英文");
+                });
+                ui.horizontal(|ui| {
+                    ui.label("初始提示:");
+                    ui.text_edit_singleline(&mut self.config.asr_prompt)
+                        .on_hover_text("引导用词和标点风格，例如“以下是普通话的句子，使用简体中文。”");
+                });
+                ui.horizontal(|ui| {
+                    ui.label("温度回退步长:");
+                    ui.add(egui::Slider::new(&mut self.config.asr_temperature_inc, 0.0..=1.0))
+                        .on_hover_text("解码失败时逐步提高温度重试，0 关闭回退");
+                    ui.label("无语音阈值:");
+                    ui.add(egui::Slider::new(&mut self.config.asr_no_speech_thold, 0.0..=1.0));
+                });
+            });
+            ui.horizontal(|ui| {
+                ui.label("中文字形:");
+                egui::ComboBox::from_id_source("output_script_choice")
+                    .selected_text(self.config.output_script.label())
+                    .show_ui(ui, |ui| {
+                        for script in OutputScriptCfg::all() {
+                            ui.selectable_value(
+                                &mut self.config.output_script,
+                                script,
+                                script.label(),
+                            );
+                        }
+                    });
+            });
+            ui.collapsing("按语言润色策略", |ui| {
+                for (idx, (key, label)) in REFINE_LANGUAGES.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{label}:"));
+                        let action = &mut self.config.refine_policy.actions[idx];
+                        egui::ComboBox::from_id_source(format!("refine_policy_{key}"))
+                            .selected_text(action.label())
+                            .show_ui(ui, |ui| {
+                                for choice in RefineActionCfg::all() {
+                                    ui.selectable_value(action, choice, choice.label());
+                                }
+                            });
+                    });
+                }
+                ui.small("自定义提示词读取 ~/.mofa/refine-prompt-<语言>.txt，用 {text} 标记识别文本位置。");
             });
 
             ui.horizontal(|ui| {
@@ -452,11 +567,82 @@ impl eframe::App for ModelManagerApp {
                     self.config.show_floating_orb = show_orb;
                     setting_changed = true;
                 }
+                let mut streaming = self.config.asr_streaming;
+                if ui.checkbox(&mut streaming, "录音时实时识别").changed() {
+                    self.config.asr_streaming = streaming;
+                    setting_changed = true;
+                }
+                let mut filter = self.config.asr_filter;
+                if ui.checkbox(&mut filter, "过滤幻听文本").changed() {
+                    self.config.asr_filter = filter;
+                    setting_changed = true;
+                }
+                let mut itn = self.config.itn;
+                if ui.checkbox(&mut itn, "数字规整").changed() {
+                    self.config.itn = itn;
+                    setting_changed = true;
+                }
+                let mut cleanup = self.config.asr_cleanup;
+                if ui
+                    .checkbox(&mut cleanup, "原文清理")
+                    .on_hover_text("ASR 原文模式下去除口语词、重复词，并按停顿补标点")
+                    .changed()
+                {
+                    self.config.asr_cleanup = cleanup;
+                    setting_changed = true;
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("音频预处理:");
+                let mut highpass = self.config.audio_highpass;
+                if ui
+                    .checkbox(&mut highpass, "高通滤波")
+                    .on_hover_text("去除直流偏移和低频嗡声")
+                    .changed()
+                {
+                    self.config.audio_highpass = highpass;
+                    setting_changed = true;
+                }
+                let mut denoise = self.config.audio_denoise;
+                if ui
+                    .checkbox(&mut denoise, "降噪")
+                    .on_hover_text("以录音开头的静音为噪声样本，压低风扇等稳态噪声")
+                    .changed()
+                {
+                    self.config.audio_denoise = denoise;
+                    setting_changed = true;
+                }
+                let mut agc = self.config.audio_agc;
+                if ui
+                    .checkbox(&mut agc, "自动增益")
+                    .on_hover_text("把音量拉到统一水平，并限制峰值避免削波")
+                    .changed()
+                {
+                    self.config.audio_agc = agc;
+                    setting_changed = true;
+                }
             });
 
-            if old_output != self.config.output_mode
+            if old_trigger != self.config.trigger_mode
+                || old_toggle_silence != self.config.toggle_silence_ms
+                || old_toggle_max != self.config.toggle_max_secs
+                || old_pre_roll != self.config.pre_roll
+                || old_pre_roll_ms != self.config.pre_roll_ms
+                || old_max_record != self.config.max_record_secs
+                || old_output != self.config.output_mode
                 || old_llm != self.config.llm_model
+                || old_llm_backend != self.config.llm_backend
                 || old_asr != self.config.asr_model
+                || old_asr_language != self.config.asr_language
+                || old_asr_beam != self.config.asr_beam_size
+                || old_asr_threads != self.config.asr_threads
+                || old_asr_prompt != self.config.asr_prompt
+                || old_asr_translate != self.config.asr_translate
+                || old_asr_temperature_inc != self.config.asr_temperature_inc
+                || old_asr_no_speech != self.config.asr_no_speech_thold
+                || old_output_script != self.config.output_script
+                || old_refine_policy != self.config.refine_policy
                 || old_show_orb != self.config.show_floating_orb
             {
                 setting_changed = true;
@@ -186,72 +186,160 @@ impl LlmModel {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum AsrModel {
     WhisperSmall,
+    WhisperSmallQ8,
+    WhisperSmallQ5,
     WhisperBase,
+    WhisperBaseQ8,
+    WhisperBaseQ5,
     WhisperTiny,
+    WhisperTinyQ8,
+    WhisperTinyQ5,
     WhisperMedium,
+    WhisperMediumQ8,
+    WhisperMediumQ5,
+    WhisperTurbo,
+    WhisperTurboQ8,
+    WhisperTurboQ5,
 }
 
 impl AsrModel {
-    fn all() -> [Self; 4] {
+    fn all() -> [Self; 15] {
         [
             Self::WhisperSmall,
+            Self::WhisperSmallQ8,
+            Self::WhisperSmallQ5,
             Self::WhisperBase,
+            Self::WhisperBaseQ8,
+            Self::WhisperBaseQ5,
             Self::WhisperTiny,
+            Self::WhisperTinyQ8,
+            Self::WhisperTinyQ5,
             Self::WhisperMedium,
+            Self::WhisperMediumQ8,
+            Self::WhisperMediumQ5,
+            Self::WhisperTurbo,
+            Self::WhisperTurboQ8,
+            Self::WhisperTurboQ5,
         ]
     }
 
     fn id(self) -> &'static str {
         match self {
-            Self::WhisperTiny => "asr:ggml-tiny.bin",
-            Self::WhisperBase => "asr:ggml-base.bin",
             Self::WhisperSmall => "asr:ggml-small.bin",
+            Self::WhisperSmallQ8 => "asr:ggml-small-q8_0.bin",
+            Self::WhisperSmallQ5 => "asr:ggml-small-q5_1.bin",
+            Self::WhisperBase => "asr:ggml-base.bin",
+            Self::WhisperBaseQ8 => "asr:ggml-base-q8_0.bin",
+            Self::WhisperBaseQ5 => "asr:ggml-base-q5_1.bin",
+            Self::WhisperTiny => "asr:ggml-tiny.bin",
+            Self::WhisperTinyQ8 => "asr:ggml-tiny-q8_0.bin",
+            Self::WhisperTinyQ5 => "asr:ggml-tiny-q5_1.bin",
             Self::WhisperMedium => "asr:ggml-medium.bin",
+            Self::WhisperMediumQ8 => "asr:ggml-medium-q8_0.bin",
+            Self::WhisperMediumQ5 => "asr:ggml-medium-q5_0.bin",
+            Self::WhisperTurbo => "asr:ggml-large-v3-turbo.bin",
+            Self::WhisperTurboQ8 => "asr:ggml-large-v3-turbo-q8_0.bin",
+            Self::WhisperTurboQ5 => "asr:ggml-large-v3-turbo-q5_0.bin",
         }
     }
 
     fn file_name(self) -> &'static str {
         match self {
-            Self::WhisperTiny => "ggml-tiny.bin",
-            Self::WhisperBase => "ggml-base.bin",
             Self::WhisperSmall => "ggml-small.bin",
+            Self::WhisperSmallQ8 => "ggml-small-q8_0.bin",
+            Self::WhisperSmallQ5 => "ggml-small-q5_1.bin",
+            Self::WhisperBase => "ggml-base.bin",
+            Self::WhisperBaseQ8 => "ggml-base-q8_0.bin",
+            Self::WhisperBaseQ5 => "ggml-base-q5_1.bin",
+            Self::WhisperTiny => "ggml-tiny.bin",
+            Self::WhisperTinyQ8 => "ggml-tiny-q8_0.bin",
+            Self::WhisperTinyQ5 => "ggml-tiny-q5_1.bin",
             Self::WhisperMedium => "ggml-medium.bin",
+            Self::WhisperMediumQ8 => "ggml-medium-q8_0.bin",
+            Self::WhisperMediumQ5 => "ggml-medium-q5_0.bin",
+            Self::WhisperTurbo => "ggml-large-v3-turbo.bin",
+            Self::WhisperTurboQ8 => "ggml-large-v3-turbo-q8_0.bin",
+            Self::WhisperTurboQ5 => "ggml-large-v3-turbo-q5_0.bin",
         }
     }
 
     fn name(self) -> &'static str {
         match self {
-            Self::WhisperTiny => "Whisper Tiny",
-            Self::WhisperBase => "Whisper Base",
             Self::WhisperSmall => "Whisper Small",
+            Self::WhisperSmallQ8 => "Whisper Small Q8",
+            Self::WhisperSmallQ5 => "Whisper Small Q5",
+            Self::WhisperBase => "Whisper Base",
+            Self::WhisperBaseQ8 => "Whisper Base Q8",
+            Self::WhisperBaseQ5 => "Whisper Base Q5",
+            Self::WhisperTiny => "Whisper Tiny",
+            Self::WhisperTinyQ8 => "Whisper Tiny Q8",
+            Self::WhisperTinyQ5 => "Whisper Tiny Q5",
             Self::WhisperMedium => "Whisper Medium",
+            Self::WhisperMediumQ8 => "Whisper Medium Q8",
+            Self::WhisperMediumQ5 => "Whisper Medium Q5",
+            Self::WhisperTurbo => "Whisper Large-v3 Turbo",
+            Self::WhisperTurboQ8 => "Whisper Turbo Q8",
+            Self::WhisperTurboQ5 => "Whisper Turbo Q5",
         }
     }
 
     fn desc(self) -> &'static str {
         match self {
-            Self::WhisperTiny => "最快，精度较低",
-            Self::WhisperBase => "速度与精度平衡",
             Self::WhisperSmall => "当前主流程默认",
+            Self::WhisperSmallQ8 => "Small 8-bit 量化，精度几乎无损",
+            Self::WhisperSmallQ5 => "Small 5-bit 量化，体积约 2/5",
+            Self::WhisperBase => "速度与精度平衡",
+            Self::WhisperBaseQ8 => "Base 8-bit 量化",
+            Self::WhisperBaseQ5 => "Base 5-bit 量化，极省空间",
+            Self::WhisperTiny => "最快，精度较低",
+            Self::WhisperTinyQ8 => "Tiny 8-bit 量化",
+            Self::WhisperTinyQ5 => "Tiny 5-bit 量化，最小体积",
             Self::WhisperMedium => "精度更高，体积大",
+            Self::WhisperMediumQ8 => "Medium 8-bit 量化，精度接近原版",
+            Self::WhisperMediumQ5 => "Medium 5-bit 量化",
+            Self::WhisperTurbo => "large-v3 精简解码器，质量最佳，需较大内存",
+            Self::WhisperTurboQ8 => "Turbo 8-bit 量化，质量接近原版",
+            Self::WhisperTurboQ5 => "Turbo 5-bit 量化，同体积下质量最佳，推荐",
         }
     }
 
     fn size_mb(self) -> u64 {
         match self {
-            Self::WhisperTiny => 72,
-            Self::WhisperBase => 142,
             Self::WhisperSmall => 466,
+            Self::WhisperSmallQ8 => 252,
+            Self::WhisperSmallQ5 => 181,
+            Self::WhisperBase => 142,
+            Self::WhisperBaseQ8 => 78,
+            Self::WhisperBaseQ5 => 57,
+            Self::WhisperTiny => 72,
+            Self::WhisperTinyQ8 => 42,
+            Self::WhisperTinyQ5 => 31,
             Self::WhisperMedium => 1500,
+            Self::WhisperMediumQ8 => 785,
+            Self::WhisperMediumQ5 => 514,
+            Self::WhisperTurbo => 1620,
+            Self::WhisperTurboQ8 => 834,
+            Self::WhisperTurboQ5 => 547,
         }
     }
 
     fn url(self) -> &'static str {
         match self {
-            Self::WhisperTiny => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-tiny.bin",
-            Self::WhisperBase => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base.bin",
             Self::WhisperSmall => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-small.bin",
+            Self::WhisperSmallQ8 => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-small-q8_0.bin",
+            Self::WhisperSmallQ5 => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-small-q5_1.bin",
+            Self::WhisperBase => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base.bin",
+            Self::WhisperBaseQ8 => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base-q8_0.bin",
+            Self::WhisperBaseQ5 => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base-q5_1.bin",
+            Self::WhisperTiny => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-tiny.bin",
+            Self::WhisperTinyQ8 => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-tiny-q8_0.bin",
+            Self::WhisperTinyQ5 => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-tiny-q5_1.bin",
             Self::WhisperMedium => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-medium.bin",
+            Self::WhisperMediumQ8 => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-medium-q8_0.bin",
+            Self::WhisperMediumQ5 => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-medium-q5_0.bin",
+            Self::WhisperTurbo => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-large-v3-turbo.bin",
+            Self::WhisperTurboQ8 => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-large-v3-turbo-q8_0.bin",
+            Self::WhisperTurboQ5 => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-large-v3-turbo-q5_0.bin",
         }
     }
 }
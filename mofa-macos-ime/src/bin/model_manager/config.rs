@@ -268,30 +268,112 @@ impl LlmChoice {
 enum AsrChoice {
     Auto,
     Tiny,
+    TinyQ5,
+    TinyQ8,
     Base,
+    BaseQ5,
+    BaseQ8,
     Small,
+    SmallQ5,
+    SmallQ8,
     Medium,
+    MediumQ5,
+    MediumQ8,
+    Turbo,
+    TurboQ5,
+    TurboQ8,
+    ParaformerSmall,
 }
 
 impl AsrChoice {
+    fn all() -> [Self; 17] {
+        [
+            Self::Auto,
+            Self::Tiny,
+            Self::TinyQ5,
+            Self::TinyQ8,
+            Self::Base,
+            Self::BaseQ5,
+            Self::BaseQ8,
+            Self::Small,
+            Self::SmallQ5,
+            Self::SmallQ8,
+            Self::Medium,
+            Self::MediumQ5,
+            Self::MediumQ8,
+            Self::Turbo,
+            Self::TurboQ5,
+            Self::TurboQ8,
+            Self::ParaformerSmall,
+        ]
+    }
+
+    /// Accepts `backend:model` (e.g. `funasr:funasr-small`) or a bare model name
     fn from_token(s: &str) -> Option<Self> {
-        match s.trim().to_ascii_lowercase().as_str() {
-            "auto" => Some(Self::Auto),
-            "ggml-tiny.bin" | "tiny" => Some(Self::Tiny),
-            "ggml-base.bin" | "base" => Some(Self::Base),
-            "ggml-small.bin" | "small" => Some(Self::Small),
-            "ggml-medium.bin" | "medium" => Some(Self::Medium),
-            _ => None,
+        let s = s.trim().to_ascii_lowercase();
+        let (backend, model) = match s.split_once(':') {
+            Some((backend, model)) => (Some(backend.trim()), model.trim()),
+            None => (None, s.as_str()),
+        };
+        let choice = match model {
+            "auto" => Self::Auto,
+            "ggml-tiny.bin" | "tiny" => Self::Tiny,
+            "ggml-tiny-q5_1.bin" | "tiny-q5" | "tiny-q5_1" => Self::TinyQ5,
+            "ggml-tiny-q8_0.bin" | "tiny-q8" | "tiny-q8_0" => Self::TinyQ8,
+            "ggml-base.bin" | "base" => Self::Base,
+            "ggml-base-q5_1.bin" | "base-q5" | "base-q5_1" => Self::BaseQ5,
+            "ggml-base-q8_0.bin" | "base-q8" | "base-q8_0" => Self::BaseQ8,
+            "ggml-small.bin" | "small" => Self::Small,
+            "ggml-small-q5_1.bin" | "small-q5" | "small-q5_1" => Self::SmallQ5,
+            "ggml-small-q8_0.bin" | "small-q8" | "small-q8_0" => Self::SmallQ8,
+            "ggml-medium.bin" | "medium" => Self::Medium,
+            "ggml-medium-q5_0.bin" | "medium-q5" | "medium-q5_0" => Self::MediumQ5,
+            "ggml-medium-q8_0.bin" | "medium-q8" | "medium-q8_0" => Self::MediumQ8,
+            "ggml-large-v3-turbo.bin" | "turbo" | "large-v3-turbo" => Self::Turbo,
+            "ggml-large-v3-turbo-q5_0.bin" | "turbo-q5" | "large-v3-turbo-q5_0" => Self::TurboQ5,
+            "ggml-large-v3-turbo-q8_0.bin" | "turbo-q8" | "large-v3-turbo-q8_0" => Self::TurboQ8,
+            "funasr-small" | "paraformer-small" => Self::ParaformerSmall,
+            _ => return None,
+        };
+        match backend {
+            Some(b) if choice != Self::Auto && b != choice.backend_name() => None,
+            _ => Some(choice),
         }
     }
 
-    fn token(self) -> &'static str {
+    fn backend_name(self) -> &'static str {
+        match self {
+            Self::ParaformerSmall => "funasr",
+            _ => "whisper",
+        }
+    }
+
+    fn token(self) -> String {
+        match self {
+            Self::Auto => "auto".to_string(),
+            _ => format!("{}:{}", self.backend_name(), self.model_name()),
+        }
+    }
+
+    fn model_name(self) -> &'static str {
         match self {
             Self::Auto => "auto",
             Self::Tiny => "ggml-tiny.bin",
+            Self::TinyQ5 => "ggml-tiny-q5_1.bin",
+            Self::TinyQ8 => "ggml-tiny-q8_0.bin",
             Self::Base => "ggml-base.bin",
+            Self::BaseQ5 => "ggml-base-q5_1.bin",
+            Self::BaseQ8 => "ggml-base-q8_0.bin",
             Self::Small => "ggml-small.bin",
+            Self::SmallQ5 => "ggml-small-q5_1.bin",
+            Self::SmallQ8 => "ggml-small-q8_0.bin",
             Self::Medium => "ggml-medium.bin",
+            Self::MediumQ5 => "ggml-medium-q5_0.bin",
+            Self::MediumQ8 => "ggml-medium-q8_0.bin",
+            Self::Turbo => "ggml-large-v3-turbo.bin",
+            Self::TurboQ5 => "ggml-large-v3-turbo-q5_0.bin",
+            Self::TurboQ8 => "ggml-large-v3-turbo-q8_0.bin",
+            Self::ParaformerSmall => "funasr-small",
         }
     }
 
@@ -299,19 +381,269 @@ impl AsrChoice {
         match self {
             Self::Auto => "自动",
             Self::Tiny => "Whisper Tiny",
+            Self::TinyQ5 => "Whisper Tiny Q5",
+            Self::TinyQ8 => "Whisper Tiny Q8",
             Self::Base => "Whisper Base",
+            Self::BaseQ5 => "Whisper Base Q5",
+            Self::BaseQ8 => "Whisper Base Q8",
             Self::Small => "Whisper Small",
+            Self::SmallQ5 => "Whisper Small Q5",
+            Self::SmallQ8 => "Whisper Small Q8",
             Self::Medium => "Whisper Medium",
+            Self::MediumQ5 => "Whisper Medium Q5",
+            Self::MediumQ8 => "Whisper Medium Q8",
+            Self::Turbo => "Whisper Large-v3 Turbo",
+            Self::TurboQ5 => "Whisper Turbo Q5",
+            Self::TurboQ8 => "Whisper Turbo Q8",
+            Self::ParaformerSmall => "Paraformer Small",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TriggerModeCfg {
+    Hold,
+    Toggle,
+}
+
+impl TriggerModeCfg {
+    fn all() -> [Self; 2] {
+        [Self::Hold, Self::Toggle]
+    }
+
+    fn from_token(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "hold" => Some(Self::Hold),
+            "toggle" => Some(Self::Toggle),
+            _ => None,
+        }
+    }
+
+    fn token(self) -> &'static str {
+        match self {
+            Self::Hold => "hold",
+            Self::Toggle => "toggle",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Hold => "按住说话",
+            Self::Toggle => "单击开始（停顿或再按结束）",
         }
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum LlmBackendCfg {
+    InProcess,
+    Worker,
+}
+
+impl LlmBackendCfg {
+    fn all() -> [Self; 2] {
+        [Self::InProcess, Self::Worker]
+    }
+
+    fn from_token(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "inprocess" | "in-process" => Some(Self::InProcess),
+            "worker" | "subprocess" => Some(Self::Worker),
+            _ => None,
+        }
+    }
+
+    fn token(self) -> &'static str {
+        match self {
+            Self::InProcess => "inprocess",
+            Self::Worker => "worker",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::InProcess => "进程内",
+            Self::Worker => "独立进程（崩溃自动重启）",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum AsrLanguageCfg {
+    Auto,
+    Chinese,
+    English,
+}
+
+impl AsrLanguageCfg {
+    fn all() -> [Self; 3] {
+        [Self::Auto, Self::Chinese, Self::English]
+    }
+
+    fn from_token(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "auto" => Some(Self::Auto),
+            "zh" | "chinese" => Some(Self::Chinese),
+            "en" | "english" => Some(Self::English),
+            _ => None,
+        }
+    }
+
+    fn token(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Chinese => "zh",
+            Self::English => "en",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Auto => "自动",
+            Self::Chinese => "仅中文",
+            Self::English => "仅英文",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputScriptCfg {
+    Keep,
+    Simplified,
+    Traditional,
+}
+
+impl OutputScriptCfg {
+    fn all() -> [Self; 3] {
+        [Self::Keep, Self::Simplified, Self::Traditional]
+    }
+
+    fn from_token(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "keep" | "auto" => Some(Self::Keep),
+            "simplified" | "zh-hans" => Some(Self::Simplified),
+            "traditional" | "zh-hant" => Some(Self::Traditional),
+            _ => None,
+        }
+    }
+
+    fn token(self) -> &'static str {
+        match self {
+            Self::Keep => "keep",
+            Self::Simplified => "simplified",
+            Self::Traditional => "traditional",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Keep => "保持原样",
+            Self::Simplified => "简体",
+            Self::Traditional => "繁体",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RefineActionCfg {
+    Refine,
+    Skip,
+    Prompt,
+}
+
+impl RefineActionCfg {
+    fn all() -> [Self; 3] {
+        [Self::Refine, Self::Skip, Self::Prompt]
+    }
+
+    fn from_token(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "refine" | "llm" => Some(Self::Refine),
+            "skip" | "asr" => Some(Self::Skip),
+            "prompt" | "custom" => Some(Self::Prompt),
+            _ => None,
+        }
+    }
+
+    fn token(self) -> &'static str {
+        match self {
+            Self::Refine => "refine",
+            Self::Skip => "skip",
+            Self::Prompt => "prompt",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Refine => "LLM 润色",
+            Self::Skip => "直出原文",
+            Self::Prompt => "自定义提示词",
+        }
+    }
+}
+
+/// Config keys (`refine_<key>`) and labels of per-language refine policies
+const REFINE_LANGUAGES: [(&str, &str); 5] = [
+    ("zh", "中文"),
+    ("en", "英文"),
+    ("ja", "日文"),
+    ("ko", "韩文"),
+    ("other", "其他"),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct RefinePolicyCfg {
+    /// Indexed like `REFINE_LANGUAGES`
+    actions: [RefineActionCfg; 5],
+}
+
+impl Default for RefinePolicyCfg {
+    fn default() -> Self {
+        let mut actions = [RefineActionCfg::Skip; 5];
+        actions[0] = RefineActionCfg::Refine;
+        Self { actions }
+    }
+}
+
+impl RefinePolicyCfg {
+    fn slot_mut(&mut self, key: &str) -> Option<&mut RefineActionCfg> {
+        let idx = REFINE_LANGUAGES.iter().position(|(k, _)| *k == key)?;
+        Some(&mut self.actions[idx])
+    }
+}
+
+#[derive(Clone, Debug)]
 struct AppConfig {
     hotkey: HotkeySpec,
+    trigger_mode: TriggerModeCfg,
+    toggle_silence_ms: u32,
+    toggle_max_secs: u32,
     output_mode: OutputModeCfg,
     llm_model: LlmChoice,
+    llm_backend: LlmBackendCfg,
     asr_model: AsrChoice,
+    asr_language: AsrLanguageCfg,
+    asr_beam_size: u8,
+    /// 解码线程数，0 为自动
+    asr_threads: u8,
+    /// Whisper 初始提示，引导用词和标点风格
+    asr_prompt: String,
+    asr_translate: bool,
+    /// 解码失败时的温度递增，0 关闭回退
+    asr_temperature_inc: f32,
+    asr_no_speech_thold: f32,
+    asr_streaming: bool,
+    asr_filter: bool,
+    output_script: OutputScriptCfg,
+    itn: bool,
+    asr_cleanup: bool,
+    audio_highpass: bool,
+    audio_denoise: bool,
+    audio_agc: bool,
+    pre_roll: bool,
+    pre_roll_ms: u32,
+    max_record_secs: u32,
+    refine_policy: RefinePolicyCfg,
     show_floating_orb: bool,
 }
 
@@ -319,9 +651,32 @@ impl Default for AppConfig {
     fn default() -> Self {
         Self {
             hotkey: HotkeySpec::fn_key(),
+            trigger_mode: TriggerModeCfg::Hold,
+            toggle_silence_ms: 1500,
+            toggle_max_secs: 120,
             output_mode: OutputModeCfg::Llm,
             llm_model: LlmChoice::Auto,
+            llm_backend: LlmBackendCfg::InProcess,
             asr_model: AsrChoice::Auto,
+            asr_language: AsrLanguageCfg::Auto,
+            asr_beam_size: 1,
+            asr_threads: 0,
+            asr_prompt: String::new(),
+            asr_translate: false,
+            asr_temperature_inc: 0.2,
+            asr_no_speech_thold: 0.6,
+            asr_streaming: false,
+            asr_filter: true,
+            output_script: OutputScriptCfg::Keep,
+            itn: true,
+            asr_cleanup: true,
+            audio_highpass: true,
+            audio_denoise: false,
+            audio_agc: true,
+            pre_roll: false,
+            pre_roll_ms: 300,
+            max_record_secs: 600,
+            refine_policy: RefinePolicyCfg::default(),
             show_floating_orb: true,
         }
     }
@@ -484,6 +839,18 @@ fn load_app_config() -> AppConfig {
             if let Some(spec) = HotkeySpec::parse(v) {
                 cfg.hotkey = spec;
             }
+        } else if let Some(v) = line.strip_prefix("trigger_mode=") {
+            if let Some(mode) = TriggerModeCfg::from_token(v) {
+                cfg.trigger_mode = mode;
+            }
+        } else if let Some(v) = line.strip_prefix("toggle_silence_ms=") {
+            if let Ok(ms) = v.trim().parse::<u32>() {
+                cfg.toggle_silence_ms = ms.clamp(500, 10_000);
+            }
+        } else if let Some(v) = line.strip_prefix("toggle_max_secs=") {
+            if let Ok(secs) = v.trim().parse::<u32>() {
+                cfg.toggle_max_secs = secs.clamp(10, 600);
+            }
         } else if let Some(v) = line.strip_prefix("output_mode=") {
             if let Some(mode) = OutputModeCfg::from_token(v) {
                 cfg.output_mode = mode;
@@ -492,12 +859,75 @@ fn load_app_config() -> AppConfig {
             if let Some(choice) = LlmChoice::from_token(v) {
                 cfg.llm_model = choice;
             }
+        } else if let Some(v) = line.strip_prefix("llm_backend=") {
+            if let Some(backend) = LlmBackendCfg::from_token(v) {
+                cfg.llm_backend = backend;
+            }
         } else if let Some(v) = line.strip_prefix("asr_model=") {
             if let Some(choice) = AsrChoice::from_token(v) {
                 cfg.asr_model = choice;
             }
+        } else if let Some(v) = line.strip_prefix("asr_language=") {
+            if let Some(lang) = AsrLanguageCfg::from_token(v) {
+                cfg.asr_language = lang;
+            }
+        } else if let Some(v) = line.strip_prefix("asr_beam_size=") {
+            if let Ok(n) = v.trim().parse::<u8>() {
+                cfg.asr_beam_size = n.clamp(1, 8);
+            }
+        } else if let Some(v) = line.strip_prefix("asr_threads=") {
+            if let Ok(n) = v.trim().parse::<u8>() {
+                cfg.asr_threads = n.min(16);
+            }
+        } else if let Some(v) = line.strip_prefix("asr_prompt=") {
+            cfg.asr_prompt = v.trim().to_string();
+        } else if let Some(v) = line.strip_prefix("asr_translate=") {
+            cfg.asr_translate = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("asr_temperature_inc=") {
+            if let Ok(t) = v.trim().parse::<f32>() {
+                cfg.asr_temperature_inc = t.clamp(0.0, 1.0);
+            }
+        } else if let Some(v) = line.strip_prefix("asr_no_speech_thold=") {
+            if let Ok(t) = v.trim().parse::<f32>() {
+                cfg.asr_no_speech_thold = t.clamp(0.0, 1.0);
+            }
+        } else if let Some(v) = line.strip_prefix("asr_streaming=") {
+            cfg.asr_streaming = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("asr_filter=") {
+            cfg.asr_filter = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("output_script=") {
+            if let Some(script) = OutputScriptCfg::from_token(v) {
+                cfg.output_script = script;
+            }
+        } else if let Some(v) = line.strip_prefix("itn=") {
+            cfg.itn = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("asr_cleanup=") {
+            cfg.asr_cleanup = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("audio_highpass=") {
+            cfg.audio_highpass = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("audio_denoise=") {
+            cfg.audio_denoise = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("audio_agc=") {
+            cfg.audio_agc = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("pre_roll=") {
+            cfg.pre_roll = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("pre_roll_ms=") {
+            if let Ok(ms) = v.trim().parse::<u32>() {
+                cfg.pre_roll_ms = ms.clamp(100, 1000);
+            }
+        } else if let Some(v) = line.strip_prefix("max_record_secs=") {
+            if let Ok(secs) = v.trim().parse::<u32>() {
+                cfg.max_record_secs = secs.clamp(30, 3600);
+            }
         } else if let Some(v) = line.strip_prefix("show_floating_orb=") {
             cfg.show_floating_orb = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some((lang, v)) = line.strip_prefix("refine_").and_then(|r| r.split_once('=')) {
+            if let (Some(slot), Some(action)) = (
+                cfg.refine_policy.slot_mut(lang.trim()),
+                RefineActionCfg::from_token(v),
+            ) {
+                *slot = action;
+            }
         }
     }
 
@@ -514,13 +944,39 @@ fn save_app_config(cfg: &AppConfig) -> Result<()> {
         Ok(content) => content.lines().map(|line| line.to_string()).collect(),
         Err(_) => Vec::new(),
     };
-    let pairs: Vec<(&str, String)> = vec![
+    let mut pairs: Vec<(&str, String)> = vec![
         ("hotkey", cfg.hotkey.token()),
+        ("trigger_mode", cfg.trigger_mode.token().to_string()),
+        ("toggle_silence_ms", cfg.toggle_silence_ms.to_string()),
+        ("toggle_max_secs", cfg.toggle_max_secs.to_string()),
         ("output_mode", cfg.output_mode.token().to_string()),
         ("llm_model", cfg.llm_model.token().to_string()),
-        ("asr_model", cfg.asr_model.token().to_string()),
+        ("llm_backend", cfg.llm_backend.token().to_string()),
+        ("asr_model", cfg.asr_model.token()),
+        ("asr_language", cfg.asr_language.token().to_string()),
+        ("asr_beam_size", cfg.asr_beam_size.to_string()),
+        ("asr_threads", cfg.asr_threads.to_string()),
+        ("asr_prompt", cfg.asr_prompt.clone()),
+        ("asr_translate", cfg.asr_translate.to_string()),
+        ("asr_temperature_inc", cfg.asr_temperature_inc.to_string()),
+        ("asr_no_speech_thold", cfg.asr_no_speech_thold.to_string()),
+        ("asr_streaming", cfg.asr_streaming.to_string()),
+        ("asr_filter", cfg.asr_filter.to_string()),
+        ("output_script", cfg.output_script.token().to_string()),
+        ("itn", cfg.itn.to_string()),
+        ("asr_cleanup", cfg.asr_cleanup.to_string()),
+        ("audio_highpass", cfg.audio_highpass.to_string()),
+        ("audio_denoise", cfg.audio_denoise.to_string()),
+        ("audio_agc", cfg.audio_agc.to_string()),
+        ("pre_roll", cfg.pre_roll.to_string()),
+        ("pre_roll_ms", cfg.pre_roll_ms.to_string()),
+        ("max_record_secs", cfg.max_record_secs.to_string()),
         ("show_floating_orb", cfg.show_floating_orb.to_string()),
     ];
+    let refine_keys = ["refine_zh", "refine_en", "refine_ja", "refine_ko", "refine_other"];
+    for (key, action) in refine_keys.into_iter().zip(cfg.refine_policy.actions) {
+        pairs.push((key, action.token().to_string()));
+    }
 
     for (key, value) in pairs {
         let wanted = format!("{key}={value}");
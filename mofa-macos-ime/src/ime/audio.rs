@@ -1,21 +1,59 @@
 struct RecordingTicker {
     stop: Arc<AtomicBool>,
-    join: Option<std::thread::JoinHandle<()>>,
+    join: Option<std::thread::JoinHandle<Option<mofa_input::asr::StreamingTranscriber>>>,
 }
 
 impl RecordingTicker {
-    fn start(samples: Arc<Mutex<Vec<f32>>>, sample_rate: u32, overlay: OverlayHandle) -> Self {
+    fn start(
+        samples: Arc<Mutex<Vec<f32>>>,
+        sample_rate: u32,
+        overlay: OverlayHandle,
+        streamer: Option<mofa_input::asr::StreamingTranscriber>,
+    ) -> Self {
         let stop = Arc::new(AtomicBool::new(false));
         let stop_flag = Arc::clone(&stop);
 
         let join = std::thread::spawn(move || {
+            let mut streamer = streamer;
+            let mut partial = String::new();
+            // 预览用短滤波器；每次只重采样新录到的部分，累积成 16k 缓冲
+            let resampler = mofa_input::audio::Resampler::new(
+                sample_rate,
+                16_000,
+                mofa_input::audio::ResampleQuality::Fast,
+            );
+            if let Err(e) = &resampler {
+                eprintln!("[mofa-ime] 实时识别失败: {e}");
+                streamer = None;
+            }
+            let mut audio_16k = Vec::new();
             while !stop_flag.load(Ordering::SeqCst) {
-                let len = samples.lock().map(|buf| buf.len()).unwrap_or(0);
-                let secs = len as f32 / sample_rate.max(1) as f32;
+                if let (Some(s), Ok(resampler)) = (streamer.as_mut(), &resampler) {
+                    if let Ok(buf) = samples.lock() {
+                        let fresh = resampler.process_tail(&buf, audio_16k.len());
+                        audio_16k.extend(fresh);
+                    }
+                    match s.push(&audio_16k) {
+                        Ok(Some(event)) => partial = event.text(),
+                        Ok(None) => {}
+                        Err(e) => {
+                            eprintln!("[mofa-ime] 实时识别失败: {e}");
+                            streamer = None;
+                        }
+                    }
+                }
+
                 overlay.set_status("录音中");
-                overlay.set_preview(&format!("正在听写 {:.1}s", secs));
+                if partial.is_empty() {
+                    let len = samples.lock().map(|buf| buf.len()).unwrap_or(0);
+                    let secs = len as f32 / sample_rate.max(1) as f32;
+                    overlay.set_preview(&format!("正在听写 {:.1}s", secs));
+                } else {
+                    overlay.set_preview(&partial);
+                }
                 std::thread::sleep(Duration::from_millis(180));
             }
+            streamer
         });
 
         Self {
@@ -24,167 +62,139 @@ impl RecordingTicker {
         }
     }
 
-    fn stop(mut self) {
+    /// 停止计时，返回实时识别状态（如有）供松开后只解码尾部
+    fn stop(mut self) -> Option<mofa_input::asr::StreamingTranscriber> {
         self.stop.store(true, Ordering::SeqCst);
-        if let Some(join) = self.join.take() {
-            let _ = join.join();
-        }
+        self.join.take().and_then(|join| join.join().ok().flatten())
     }
 }
 
-struct ActiveRecorder {
-    stream: cpal::Stream,
+/// 免按住模式：判断录音是否该自动结束
+struct ToggleWatch {
     samples: Arc<Mutex<Vec<f32>>>,
     sample_rate: u32,
+    consumed: usize,
+    started: std::time::Instant,
+    max: Duration,
+    endpointer: mofa_input::audio::Endpointer,
 }
 
-impl ActiveRecorder {
-    fn start() -> Result<Self> {
-        let host = cpal::default_host();
-        let device = host
-            .default_input_device()
-            .ok_or_else(|| anyhow!("未找到麦克风设备"))?;
-
-        let cfg = device.default_input_config()?;
-        let sample_rate = cfg.sample_rate().0;
-        let channels = cfg.channels() as usize;
-        let samples = Arc::new(Mutex::new(Vec::<f32>::new()));
-
-        let stream = match cfg.sample_format() {
-            cpal::SampleFormat::F32 => {
-                let samples_buf = Arc::clone(&samples);
-                device.build_input_stream(
-                    &cfg.clone().into(),
-                    move |data: &[f32], _| append_mono_f32(&samples_buf, data, channels),
-                    move |err| eprintln!("[mofa-ime] 音频流错误: {err}"),
-                    None,
-                )?
-            }
-            cpal::SampleFormat::I16 => {
-                let samples_buf = Arc::clone(&samples);
-                device.build_input_stream(
-                    &cfg.clone().into(),
-                    move |data: &[i16], _| append_mono_i16(&samples_buf, data, channels),
-                    move |err| eprintln!("[mofa-ime] 音频流错误: {err}"),
-                    None,
-                )?
-            }
-            cpal::SampleFormat::U16 => {
-                let samples_buf = Arc::clone(&samples);
-                device.build_input_stream(
-                    &cfg.clone().into(),
-                    move |data: &[u16], _| append_mono_u16(&samples_buf, data, channels),
-                    move |err| eprintln!("[mofa-ime] 音频流错误: {err}"),
-                    None,
-                )?
-            }
-            other => bail!("不支持的采样格式: {other:?}"),
-        };
-
-        stream.play()?;
-
-        Ok(Self {
-            stream,
-            samples,
-            sample_rate,
-        })
-    }
-
-    fn sample_buffer(&self) -> Arc<Mutex<Vec<f32>>> {
-        Arc::clone(&self.samples)
-    }
-
-    fn sample_rate(&self) -> u32 {
-        self.sample_rate
+impl ToggleWatch {
+    fn new(recorder: &Microphone, cfg: &AppConfig) -> Self {
+        Self {
+            samples: recorder.sample_buffer(),
+            sample_rate: recorder.sample_rate(),
+            consumed: 0,
+            started: std::time::Instant::now(),
+            max: Duration::from_secs(cfg.toggle_max_secs as u64),
+            endpointer: mofa_input::audio::Endpointer::new(cfg.toggle_silence_ms),
+        }
     }
 
-    fn stop(self) -> Result<Vec<f32>> {
-        // drop stream first to stop capture
-        drop(self.stream);
-
-        // Give CoreAudio a short breath to flush callbacks.
-        std::thread::sleep(Duration::from_millis(40));
-
-        let raw = self
+    /// 应结束时返回原因
+    fn poll(&mut self) -> Option<&'static str> {
+        if self.started.elapsed() >= self.max {
+            return Some("达到最长录音时长");
+        }
+        // 只取新录到的部分，避免每次重采样整段缓冲
+        let fresh = self
             .samples
             .lock()
-            .map_err(|_| anyhow!("音频缓存锁失败"))?
-            .clone();
-
-        if raw.is_empty() {
-            bail!("录音为空");
+            .map(|buf| buf.get(self.consumed..).unwrap_or_default().to_vec())
+            .unwrap_or_default();
+        self.consumed += fresh.len();
+        let quality = mofa_input::audio::ResampleQuality::Fast;
+        if let Ok(audio) = resample_to_16k(&fresh, self.sample_rate, quality) {
+            self.endpointer.push(&audio);
         }
-
-        Ok(resample_to_16k(&raw, self.sample_rate))
+        self.endpointer.is_done().then_some("检测到说话结束")
     }
 }
 
-fn append_mono_f32(buf: &Arc<Mutex<Vec<f32>>>, data: &[f32], channels: usize) {
-    if channels == 0 {
-        return;
+/// 麦克风；开启预录时在两次录音之间保持采集，只在内存里保留最近一小段
+struct Microphone {
+    recorder: mofa_input::audio::Recorder,
+}
+
+impl Microphone {
+    fn new() -> Self {
+        Self {
+            recorder: mofa_input::audio::Recorder::new(),
+        }
     }
-    if let Ok(mut dst) = buf.lock() {
-        if channels == 1 {
-            dst.extend_from_slice(data);
-            return;
+
+    /// 按配置开关预录、设置最长录音；录音中不切换，返回预录状态是否变化
+    fn apply_config(&mut self, cfg: &AppConfig) -> bool {
+        if self.recorder.is_recording() {
+            return false;
         }
-        for frame in data.chunks(channels) {
-            let sum: f32 = frame.iter().copied().sum();
-            dst.push(sum / channels as f32);
+        self.recorder
+            .set_max_duration(Some(Duration::from_secs(cfg.max_record_secs as u64)));
+        let was_warm = self.recorder.is_warm();
+        if cfg.pre_roll {
+            if let Err(e) = self.recorder.warm_up(cfg.pre_roll_ms) {
+                eprintln!("[mofa-ime] 预录启动失败: {e}");
+                self.recorder.cool_down();
+            }
+        } else {
+            self.recorder.cool_down();
         }
+        was_warm != self.recorder.is_warm()
     }
-}
 
-fn append_mono_i16(buf: &Arc<Mutex<Vec<f32>>>, data: &[i16], channels: usize) {
-    if channels == 0 {
-        return;
+    fn is_warm(&self) -> bool {
+        self.recorder.is_warm()
     }
-    if let Ok(mut dst) = buf.lock() {
-        for frame in data.chunks(channels) {
-            let mut sum = 0.0f32;
-            for s in frame {
-                sum += *s as f32 / i16::MAX as f32;
-            }
-            dst.push(sum / frame.len() as f32);
-        }
+
+    fn is_recording(&self) -> bool {
+        self.recorder.is_recording()
     }
-}
 
-fn append_mono_u16(buf: &Arc<Mutex<Vec<f32>>>, data: &[u16], channels: usize) {
-    if channels == 0 {
-        return;
+    fn limit_reached(&self) -> bool {
+        self.recorder.limit_reached()
     }
-    if let Ok(mut dst) = buf.lock() {
-        for frame in data.chunks(channels) {
-            let mut sum = 0.0f32;
-            for s in frame {
-                sum += (*s as f32 / u16::MAX as f32) * 2.0 - 1.0;
-            }
-            dst.push(sum / frame.len() as f32);
-        }
+
+    fn start(&mut self) -> Result<()> {
+        self.recorder.start()
     }
-}
 
-fn resample_to_16k(samples: &[f32], from_rate: u32) -> Vec<f32> {
-    const TARGET: u32 = 16_000;
-    if from_rate == TARGET || samples.is_empty() {
-        return samples.to_vec();
+    fn sample_buffer(&self) -> Arc<Mutex<Vec<f32>>> {
+        self.recorder.sample_buffer()
     }
 
-    let ratio = TARGET as f64 / from_rate as f64;
-    let new_len = (samples.len() as f64 * ratio) as usize;
-    let mut out = Vec::with_capacity(new_len);
+    fn sample_rate(&self) -> u32 {
+        self.recorder.sample_rate()
+    }
 
-    for i in 0..new_len {
-        let src_pos = i as f64 / ratio;
-        let i0 = src_pos.floor() as usize;
-        let i1 = (i0 + 1).min(samples.len() - 1);
-        let frac = src_pos - i0 as f64;
+    fn stop(&mut self) -> Result<Vec<f32>> {
+        let samples = self.recorder.stop()?;
+        let stats = self.recorder.stats();
+        if stats != mofa_input::audio::CaptureStats::default() {
+            eprintln!(
+                "[mofa-ime] 录音丢失样本: 缓冲溢出 {}，超时截断 {}",
+                stats.overflowed, stats.truncated
+            );
+        }
+        if samples.is_empty() {
+            bail!("录音为空");
+        }
+        Ok(samples)
+    }
+}
 
-        let y0 = samples[i0] as f64;
-        let y1 = samples[i1] as f64;
-        out.push((y0 + (y1 - y0) * frac) as f32);
+fn dsp_config(cfg: &AppConfig) -> mofa_input::audio::DspConfig {
+    mofa_input::audio::DspConfig {
+        high_pass: cfg.audio_highpass,
+        noise_suppression: cfg.audio_denoise,
+        agc: cfg.audio_agc,
+        ..Default::default()
     }
+}
 
-    out
+fn resample_to_16k(
+    samples: &[f32],
+    from_rate: u32,
+    quality: mofa_input::audio::ResampleQuality,
+) -> Result<Vec<f32>> {
+    mofa_input::audio::resample(samples, from_rate, 16_000, quality)
 }
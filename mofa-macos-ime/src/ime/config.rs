@@ -108,6 +108,23 @@ impl OutputMode {
     }
 }
 
+/// 按住说话，或单击开始、静音/超时/再次单击结束
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TriggerMode {
+    Hold,
+    Toggle,
+}
+
+impl TriggerMode {
+    fn from_token(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "hold" => Some(Self::Hold),
+            "toggle" => Some(Self::Toggle),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum LlmModelChoice {
     Auto,
@@ -230,34 +247,99 @@ impl LlmModelChoice {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum LlmBackendChoice {
+    InProcess,
+    Worker,
+}
+
+impl LlmBackendChoice {
+    fn from_token(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "inprocess" | "in-process" => Some(Self::InProcess),
+            "worker" | "subprocess" => Some(Self::Worker),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::InProcess => "进程内",
+            Self::Worker => "独立进程",
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum AsrModelChoice {
     Auto,
     Tiny,
+    TinyQ5,
+    TinyQ8,
     Base,
+    BaseQ5,
+    BaseQ8,
     Small,
+    SmallQ5,
+    SmallQ8,
     Medium,
+    MediumQ5,
+    MediumQ8,
+    Turbo,
+    TurboQ5,
+    TurboQ8,
+    ParaformerSmall,
 }
 
 impl AsrModelChoice {
+    /// Accepts `backend:model` (e.g. `funasr:funasr-small`) or a bare model name
     fn from_token(s: &str) -> Option<Self> {
-        match s.trim().to_ascii_lowercase().as_str() {
-            "auto" => Some(Self::Auto),
-            "ggml-tiny.bin" | "tiny" => Some(Self::Tiny),
-            "ggml-base.bin" | "base" => Some(Self::Base),
-            "ggml-small.bin" | "small" => Some(Self::Small),
-            "ggml-medium.bin" | "medium" => Some(Self::Medium),
-            _ => None,
+        let s = s.trim().to_ascii_lowercase();
+        let (backend, model) = match s.split_once(':') {
+            Some((backend, model)) => (Some(backend.trim()), model.trim()),
+            None => (None, s.as_str()),
+        };
+        let choice = match model {
+            "auto" => Self::Auto,
+            "ggml-tiny.bin" | "tiny" => Self::Tiny,
+            "ggml-tiny-q5_1.bin" | "tiny-q5" | "tiny-q5_1" => Self::TinyQ5,
+            "ggml-tiny-q8_0.bin" | "tiny-q8" | "tiny-q8_0" => Self::TinyQ8,
+            "ggml-base.bin" | "base" => Self::Base,
+            "ggml-base-q5_1.bin" | "base-q5" | "base-q5_1" => Self::BaseQ5,
+            "ggml-base-q8_0.bin" | "base-q8" | "base-q8_0" => Self::BaseQ8,
+            "ggml-small.bin" | "small" => Self::Small,
+            "ggml-small-q5_1.bin" | "small-q5" | "small-q5_1" => Self::SmallQ5,
+            "ggml-small-q8_0.bin" | "small-q8" | "small-q8_0" => Self::SmallQ8,
+            "ggml-medium.bin" | "medium" => Self::Medium,
+            "ggml-medium-q5_0.bin" | "medium-q5" | "medium-q5_0" => Self::MediumQ5,
+            "ggml-medium-q8_0.bin" | "medium-q8" | "medium-q8_0" => Self::MediumQ8,
+            "ggml-large-v3-turbo.bin" | "turbo" | "large-v3-turbo" => Self::Turbo,
+            "ggml-large-v3-turbo-q5_0.bin" | "turbo-q5" | "large-v3-turbo-q5_0" => Self::TurboQ5,
+            "ggml-large-v3-turbo-q8_0.bin" | "turbo-q8" | "large-v3-turbo-q8_0" => Self::TurboQ8,
+            "funasr-small" | "paraformer-small" => Self::ParaformerSmall,
+            _ => return None,
+        };
+        match backend {
+            Some(b) if choice != Self::Auto && b != choice.backend_name() => None,
+            _ => Some(choice),
         }
     }
 
-    fn token(self) -> &'static str {
+    fn backend(self) -> mofa_input::asr::AsrBackendKind {
         match self {
-            Self::Auto => "auto",
-            Self::Tiny => "ggml-tiny.bin",
-            Self::Base => "ggml-base.bin",
-            Self::Small => "ggml-small.bin",
-            Self::Medium => "ggml-medium.bin",
+            Self::ParaformerSmall => mofa_input::asr::AsrBackendKind::FunAsr,
+            _ => mofa_input::asr::AsrBackendKind::Whisper,
+        }
+    }
+
+    fn backend_name(self) -> &'static str {
+        self.backend().name()
+    }
+
+    fn token(self) -> String {
+        match self.file_name() {
+            Some(file_name) => format!("{}:{}", self.backend_name(), file_name),
+            None => "auto".to_string(),
         }
     }
 
@@ -265,9 +347,21 @@ impl AsrModelChoice {
         match self {
             Self::Auto => None,
             Self::Tiny => Some("ggml-tiny.bin"),
+            Self::TinyQ5 => Some("ggml-tiny-q5_1.bin"),
+            Self::TinyQ8 => Some("ggml-tiny-q8_0.bin"),
             Self::Base => Some("ggml-base.bin"),
+            Self::BaseQ5 => Some("ggml-base-q5_1.bin"),
+            Self::BaseQ8 => Some("ggml-base-q8_0.bin"),
             Self::Small => Some("ggml-small.bin"),
+            Self::SmallQ5 => Some("ggml-small-q5_1.bin"),
+            Self::SmallQ8 => Some("ggml-small-q8_0.bin"),
             Self::Medium => Some("ggml-medium.bin"),
+            Self::MediumQ5 => Some("ggml-medium-q5_0.bin"),
+            Self::MediumQ8 => Some("ggml-medium-q8_0.bin"),
+            Self::Turbo => Some("ggml-large-v3-turbo.bin"),
+            Self::TurboQ5 => Some("ggml-large-v3-turbo-q5_0.bin"),
+            Self::TurboQ8 => Some("ggml-large-v3-turbo-q8_0.bin"),
+            Self::ParaformerSmall => Some("funasr-small"),
         }
     }
 
@@ -275,19 +369,204 @@ impl AsrModelChoice {
         match self {
             Self::Auto => "自动",
             Self::Tiny => "Whisper Tiny",
+            Self::TinyQ5 => "Whisper Tiny Q5",
+            Self::TinyQ8 => "Whisper Tiny Q8",
             Self::Base => "Whisper Base",
+            Self::BaseQ5 => "Whisper Base Q5",
+            Self::BaseQ8 => "Whisper Base Q8",
             Self::Small => "Whisper Small",
+            Self::SmallQ5 => "Whisper Small Q5",
+            Self::SmallQ8 => "Whisper Small Q8",
             Self::Medium => "Whisper Medium",
+            Self::MediumQ5 => "Whisper Medium Q5",
+            Self::MediumQ8 => "Whisper Medium Q8",
+            Self::Turbo => "Whisper Large-v3 Turbo",
+            Self::TurboQ5 => "Whisper Turbo Q5",
+            Self::TurboQ8 => "Whisper Turbo Q8",
+            Self::ParaformerSmall => "Paraformer Small",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum AsrLanguageChoice {
+    Auto,
+    Chinese,
+    English,
+}
+
+impl AsrLanguageChoice {
+    fn from_token(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "auto" => Some(Self::Auto),
+            "zh" | "chinese" => Some(Self::Chinese),
+            "en" | "english" => Some(Self::English),
+            _ => None,
+        }
+    }
+
+    fn token(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Chinese => "zh",
+            Self::English => "en",
+        }
+    }
+
+    fn language_code(self) -> Option<&'static str> {
+        match self {
+            Self::Auto => None,
+            other => Some(other.token()),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Auto => "自动",
+            Self::Chinese => "仅中文",
+            Self::English => "仅英文",
         }
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputScriptChoice {
+    Keep,
+    Simplified,
+    Traditional,
+}
+
+impl OutputScriptChoice {
+    fn from_token(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "keep" | "auto" => Some(Self::Keep),
+            "simplified" | "zh-hans" => Some(Self::Simplified),
+            "traditional" | "zh-hant" => Some(Self::Traditional),
+            _ => None,
+        }
+    }
+
+    fn token(self) -> &'static str {
+        match self {
+            Self::Keep => "keep",
+            Self::Simplified => "simplified",
+            Self::Traditional => "traditional",
+        }
+    }
+
+    fn script(self) -> Option<mofa_input::text::Script> {
+        match self {
+            Self::Keep => None,
+            Self::Simplified => Some(mofa_input::text::Script::Simplified),
+            Self::Traditional => Some(mofa_input::text::Script::Traditional),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Keep => "保持原样",
+            Self::Simplified => "简体",
+            Self::Traditional => "繁体",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RefineAction {
+    Refine,
+    Skip,
+    Prompt,
+}
+
+impl RefineAction {
+    fn from_token(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "refine" | "llm" => Some(Self::Refine),
+            "skip" | "asr" => Some(Self::Skip),
+            "prompt" | "custom" => Some(Self::Prompt),
+            _ => None,
+        }
+    }
+}
+
+/// Refine action per detected language
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct RefinePolicy {
+    zh: RefineAction,
+    en: RefineAction,
+    ja: RefineAction,
+    ko: RefineAction,
+    other: RefineAction,
+}
+
+impl Default for RefinePolicy {
+    fn default() -> Self {
+        // 润色提示词面向中文；其他语言默认直出，避免被改写或翻译
+        Self {
+            zh: RefineAction::Refine,
+            en: RefineAction::Skip,
+            ja: RefineAction::Skip,
+            ko: RefineAction::Skip,
+            other: RefineAction::Skip,
+        }
+    }
+}
+
+impl RefinePolicy {
+    fn action(self, language: &str) -> RefineAction {
+        match language {
+            "zh" | "yue" => self.zh,
+            "en" => self.en,
+            "ja" => self.ja,
+            "ko" => self.ko,
+            _ => self.other,
+        }
+    }
+
+    fn slot_mut(&mut self, key: &str) -> Option<&mut RefineAction> {
+        match key {
+            "zh" => Some(&mut self.zh),
+            "en" => Some(&mut self.en),
+            "ja" => Some(&mut self.ja),
+            "ko" => Some(&mut self.ko),
+            "other" => Some(&mut self.other),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
 struct AppConfig {
     hotkey: HotkeySpec,
+    trigger_mode: TriggerMode,
+    toggle_silence_ms: u32,
+    toggle_max_secs: u32,
     output_mode: OutputMode,
     llm_model: LlmModelChoice,
+    llm_backend: LlmBackendChoice,
     asr_model: AsrModelChoice,
+    asr_language: AsrLanguageChoice,
+    asr_beam_size: u8,
+    /// 解码线程数，0 为自动
+    asr_threads: u8,
+    /// Whisper 初始提示，引导用词和标点风格
+    asr_prompt: String,
+    asr_translate: bool,
+    /// 解码失败时的温度递增，0 关闭回退
+    asr_temperature_inc: f32,
+    asr_no_speech_thold: f32,
+    asr_streaming: bool,
+    asr_filter: bool,
+    output_script: OutputScriptChoice,
+    itn: bool,
+    asr_cleanup: bool,
+    audio_highpass: bool,
+    audio_denoise: bool,
+    audio_agc: bool,
+    pre_roll: bool,
+    pre_roll_ms: u32,
+    max_record_secs: u32,
+    refine_policy: RefinePolicy,
     show_floating_orb: bool,
 }
 
@@ -295,9 +574,32 @@ impl Default for AppConfig {
     fn default() -> Self {
         Self {
             hotkey: HotkeySpec::fn_key(),
+            trigger_mode: TriggerMode::Hold,
+            toggle_silence_ms: 1500,
+            toggle_max_secs: 120,
             output_mode: OutputMode::Llm,
             llm_model: LlmModelChoice::Auto,
+            llm_backend: LlmBackendChoice::InProcess,
             asr_model: AsrModelChoice::Auto,
+            asr_language: AsrLanguageChoice::Auto,
+            asr_beam_size: 1,
+            asr_threads: 0,
+            asr_prompt: String::new(),
+            asr_translate: false,
+            asr_temperature_inc: 0.2,
+            asr_no_speech_thold: 0.6,
+            asr_streaming: false,
+            asr_filter: true,
+            output_script: OutputScriptChoice::Keep,
+            itn: true,
+            asr_cleanup: true,
+            audio_highpass: true,
+            audio_denoise: false,
+            audio_agc: true,
+            pre_roll: false,
+            pre_roll_ms: 300,
+            max_record_secs: 600,
+            refine_policy: RefinePolicy::default(),
             show_floating_orb: true,
         }
     }
@@ -392,6 +694,18 @@ fn load_app_config() -> AppConfig {
             if let Some(spec) = HotkeySpec::parse(v) {
                 cfg.hotkey = spec;
             }
+        } else if let Some(v) = line.strip_prefix("trigger_mode=") {
+            if let Some(mode) = TriggerMode::from_token(v) {
+                cfg.trigger_mode = mode;
+            }
+        } else if let Some(v) = line.strip_prefix("toggle_silence_ms=") {
+            if let Ok(ms) = v.trim().parse::<u32>() {
+                cfg.toggle_silence_ms = ms.clamp(500, 10_000);
+            }
+        } else if let Some(v) = line.strip_prefix("toggle_max_secs=") {
+            if let Ok(secs) = v.trim().parse::<u32>() {
+                cfg.toggle_max_secs = secs.clamp(10, 600);
+            }
         } else if let Some(v) = line.strip_prefix("output_mode=") {
             if let Some(mode) = OutputMode::from_token(v) {
                 cfg.output_mode = mode;
@@ -400,12 +714,75 @@ fn load_app_config() -> AppConfig {
             if let Some(choice) = LlmModelChoice::from_token(v) {
                 cfg.llm_model = choice;
             }
+        } else if let Some(v) = line.strip_prefix("llm_backend=") {
+            if let Some(backend) = LlmBackendChoice::from_token(v) {
+                cfg.llm_backend = backend;
+            }
         } else if let Some(v) = line.strip_prefix("asr_model=") {
             if let Some(choice) = AsrModelChoice::from_token(v) {
                 cfg.asr_model = choice;
             }
+        } else if let Some(v) = line.strip_prefix("asr_language=") {
+            if let Some(lang) = AsrLanguageChoice::from_token(v) {
+                cfg.asr_language = lang;
+            }
+        } else if let Some(v) = line.strip_prefix("asr_beam_size=") {
+            if let Ok(n) = v.trim().parse::<u8>() {
+                cfg.asr_beam_size = n.clamp(1, 8);
+            }
+        } else if let Some(v) = line.strip_prefix("asr_threads=") {
+            if let Ok(n) = v.trim().parse::<u8>() {
+                cfg.asr_threads = n.min(16);
+            }
+        } else if let Some(v) = line.strip_prefix("asr_prompt=") {
+            cfg.asr_prompt = v.trim().to_string();
+        } else if let Some(v) = line.strip_prefix("asr_translate=") {
+            cfg.asr_translate = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("asr_temperature_inc=") {
+            if let Ok(t) = v.trim().parse::<f32>() {
+                cfg.asr_temperature_inc = t.clamp(0.0, 1.0);
+            }
+        } else if let Some(v) = line.strip_prefix("asr_no_speech_thold=") {
+            if let Ok(t) = v.trim().parse::<f32>() {
+                cfg.asr_no_speech_thold = t.clamp(0.0, 1.0);
+            }
+        } else if let Some(v) = line.strip_prefix("asr_streaming=") {
+            cfg.asr_streaming = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("asr_filter=") {
+            cfg.asr_filter = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("output_script=") {
+            if let Some(script) = OutputScriptChoice::from_token(v) {
+                cfg.output_script = script;
+            }
+        } else if let Some(v) = line.strip_prefix("itn=") {
+            cfg.itn = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("asr_cleanup=") {
+            cfg.asr_cleanup = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("audio_highpass=") {
+            cfg.audio_highpass = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("audio_denoise=") {
+            cfg.audio_denoise = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("audio_agc=") {
+            cfg.audio_agc = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("pre_roll=") {
+            cfg.pre_roll = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("pre_roll_ms=") {
+            if let Ok(ms) = v.trim().parse::<u32>() {
+                cfg.pre_roll_ms = ms.clamp(100, 1000);
+            }
+        } else if let Some(v) = line.strip_prefix("max_record_secs=") {
+            if let Ok(secs) = v.trim().parse::<u32>() {
+                cfg.max_record_secs = secs.clamp(30, 3600);
+            }
         } else if let Some(v) = line.strip_prefix("show_floating_orb=") {
             cfg.show_floating_orb = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some((lang, v)) = line.strip_prefix("refine_").and_then(|r| r.split_once('=')) {
+            if let (Some(slot), Some(action)) = (
+                cfg.refine_policy.slot_mut(lang.trim()),
+                RefineAction::from_token(v),
+            ) {
+                *slot = action;
+            }
         }
     }
 
@@ -1,19 +1,76 @@
+/// LLM used for refining, either in-process or hosted by `mofa-llm-worker`.
+#[derive(Clone)]
+enum RefineLlm {
+    InProcess(mofa_input::llm::ChatSession),
+    Worker(mofa_input::llm::WorkerChatSession),
+}
+
+impl RefineLlm {
+    fn load(path: &Path, backend: LlmBackendChoice) -> Result<Self> {
+        match backend {
+            LlmBackendChoice::InProcess => {
+                Ok(Self::InProcess(mofa_input::llm::ChatSession::new(path)?))
+            }
+            LlmBackendChoice::Worker => {
+                let exe = llm_worker_exe().ok_or_else(|| anyhow!("未找到 mofa-llm-worker"))?;
+                let cfg = mofa_input::llm::WorkerConfig::new(&exe, path);
+                let chat = mofa_input::llm::WorkerChatSession::spawn(cfg)?;
+                Ok(Self::Worker(chat))
+            }
+        }
+    }
+
+    fn clear(&self) {
+        match self {
+            Self::InProcess(chat) => chat.clear(),
+            Self::Worker(chat) => chat.clear(),
+        }
+    }
+
+    fn send(&self, message: &str, max_tokens: i32, temperature: f32) -> Result<String> {
+        match self {
+            Self::InProcess(chat) => chat.send(message, max_tokens, temperature),
+            Self::Worker(chat) => chat.send(message, max_tokens, temperature),
+        }
+    }
+}
+
+fn llm_worker_exe() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let worker = exe.parent()?.join("mofa-llm-worker");
+    worker.exists().then_some(worker)
+}
+
+fn whisper_params_for(cfg: &AppConfig) -> mofa_input::asr::WhisperParams {
+    mofa_input::asr::WhisperParams {
+        language: cfg.asr_language.language_code().map(str::to_string),
+        beam_size: cfg.asr_beam_size as usize,
+        n_threads: (cfg.asr_threads > 0).then_some(cfg.asr_threads as usize),
+        initial_prompt: (!cfg.asr_prompt.is_empty()).then(|| cfg.asr_prompt.clone()),
+        translate: cfg.asr_translate,
+        temperature_inc: cfg.asr_temperature_inc,
+        no_speech_thold: cfg.asr_no_speech_thold,
+        ..Default::default()
+    }
+}
+
 fn refresh_models(
     model_base: &Path,
-    cfg: AppConfig,
+    cfg: &AppConfig,
     asr: &mut Option<mofa_input::asr::AsrSession>,
-    asr_loaded_path: &mut Option<PathBuf>,
-    llm: &mut Option<mofa_input::llm::ChatSession>,
-    llm_loaded_path: &mut Option<PathBuf>,
+    asr_loaded: &mut Option<(mofa_input::asr::AsrBackendKind, PathBuf)>,
+    llm: &mut Option<RefineLlm>,
+    llm_loaded: &mut Option<(PathBuf, LlmBackendChoice)>,
     monitor: MonitorHandle,
 ) {
+    let whisper_params = whisper_params_for(cfg);
     let desired_asr = choose_asr_model(model_base, cfg.asr_model);
-    if desired_asr != *asr_loaded_path {
+    if desired_asr != *asr_loaded {
         *asr = None;
-        *asr_loaded_path = desired_asr.clone();
+        *asr_loaded = desired_asr.clone();
 
-        if let Some(path) = desired_asr {
-            match mofa_input::asr::AsrSession::new(&path) {
+        if let Some((kind, path)) = desired_asr {
+            match mofa_input::asr::AsrSession::load(kind, &path, whisper_params.clone()) {
                 Ok(s) => {
                     *asr = Some(s);
                     if cfg.asr_model != AsrModelChoice::Auto {
@@ -28,24 +85,37 @@ fn refresh_models(
         } else {
             monitor.set_hint("未发现可用 ASR 模型");
         }
+    } else if let Some(session) = asr.as_ref() {
+        if session.params() != whisper_params {
+            session.set_params(whisper_params);
+            monitor.set_hint(&format!("ASR 语言: {}", cfg.asr_language.label()));
+        }
+    }
+    if let Some(session) = asr.as_ref() {
+        session.set_filter(hallucination_filter(cfg));
     }
 
-    let desired_llm = choose_llm_model(model_base, cfg.llm_model);
-    if desired_llm != *llm_loaded_path {
+    let desired_llm =
+        choose_llm_model(model_base, cfg.llm_model).map(|path| (path, cfg.llm_backend));
+    if desired_llm != *llm_loaded {
         *llm = None;
-        *llm_loaded_path = desired_llm.clone();
+        *llm_loaded = desired_llm.clone();
 
-        if let Some(path) = desired_llm {
-            match mofa_input::llm::ChatSession::new(&path) {
+        if let Some((path, backend)) = desired_llm {
+            match RefineLlm::load(&path, backend) {
                 Ok(s) => {
                     *llm = Some(s);
                     if cfg.llm_model != LlmModelChoice::Auto {
                         monitor.set_hint(&format!("LLM 已切换: {}", cfg.llm_model.label()));
+                    } else if backend == LlmBackendChoice::Worker {
+                        monitor.set_hint(&format!("LLM 运行方式: {}", backend.label()));
                     }
                 }
                 Err(e) => {
                     eprintln!("[mofa-ime] LLM 加载失败 {:?}: {e}", path);
                     monitor.set_hint("LLM 加载失败");
+                    // 下次录音结束时重试，例如 worker 首次启动失败
+                    *llm_loaded = None;
                 }
             }
         } else {
@@ -54,6 +124,24 @@ fn refresh_models(
     }
 }
 
+/// 按配置切换预录，并在托盘标出麦克风常开
+fn apply_mic_config(
+    mic: &mut Microphone,
+    cfg: &AppConfig,
+    status: StatusHandle,
+    monitor: MonitorHandle,
+) {
+    if mic.apply_config(cfg) {
+        status.set_mic_warm(mic.is_warm());
+        status.set(TrayState::Idle);
+    }
+    if mic.is_warm() {
+        monitor.set_mic(&format!("常开，预录 {}ms（仅存内存）", cfg.pre_roll_ms));
+    } else {
+        monitor.set_mic("仅录音时开启");
+    }
+}
+
 fn spawn_pipeline_worker(
     rx: Receiver<HotkeySignal>,
     status: StatusHandle,
@@ -68,9 +156,9 @@ fn spawn_pipeline_worker(
         let model_base = model_base_dir();
 
         let mut asr: Option<mofa_input::asr::AsrSession> = None;
-        let mut asr_loaded_path: Option<PathBuf> = None;
-        let mut llm: Option<mofa_input::llm::ChatSession> = None;
-        let mut llm_loaded_path: Option<PathBuf> = None;
+        let mut asr_loaded: Option<(mofa_input::asr::AsrBackendKind, PathBuf)> = None;
+        let mut llm: Option<RefineLlm> = None;
+        let mut llm_loaded: Option<(PathBuf, LlmBackendChoice)> = None;
 
         monitor.set_state("就绪");
         monitor.set_asr("-");
@@ -80,21 +168,38 @@ fn spawn_pipeline_worker(
         let startup_cfg = load_app_config();
         refresh_models(
             &model_base,
-            startup_cfg,
+            &startup_cfg,
             &mut asr,
-            &mut asr_loaded_path,
+            &mut asr_loaded,
             &mut llm,
-            &mut llm_loaded_path,
+            &mut llm_loaded,
             monitor,
         );
 
-        let mut recorder: Option<ActiveRecorder> = None;
+        // 有 Silero 模型时用 Silero，否则用能量检测
+        let vad = mofa_input::audio::Vad::auto();
+        let mut mic = Microphone::new();
+        apply_mic_config(&mut mic, &startup_cfg, status, monitor);
+        let mut mic_checked = std::time::Instant::now();
         let mut recording_ticker: Option<RecordingTicker> = None;
+        // 免按住模式录音中为 Some
+        let mut toggle_watch: Option<ToggleWatch> = None;
+        // 免按住模式下热键的松开不结束录音
+        let mut ignore_up = false;
         let mut history_visible = false;
 
         loop {
             // Check for hotkey signal (blocking with timeout)
             let sig = match rx.recv_timeout(Duration::from_millis(100)) {
+                Ok(HotkeySignal::Up) if ignore_up => {
+                    ignore_up = false;
+                    continue;
+                }
+                // 免按住模式：再次按下热键结束录音
+                Ok(HotkeySignal::Down) if toggle_watch.is_some() => {
+                    ignore_up = true;
+                    HotkeySignal::Up
+                }
                 Ok(s) => s,
                 Err(mpsc::RecvTimeoutError::Timeout) => {
                     // Check orb click events during timeout
@@ -111,7 +216,23 @@ fn spawn_pipeline_worker(
                             }
                         }
                     }
-                    continue;
+                    // 空闲时每秒按配置开关预录
+                    if !mic.is_recording() && mic_checked.elapsed() >= Duration::from_secs(1) {
+                        apply_mic_config(&mut mic, &load_app_config(), status, monitor);
+                        mic_checked = std::time::Instant::now();
+                    }
+                    let limit = mic.limit_reached().then_some("达到最长录音时长");
+                    match limit.or_else(|| toggle_watch.as_mut().and_then(|watch| watch.poll())) {
+                        Some(reason) => {
+                            // 按住模式下热键仍按着，之后的松开不再结束录音
+                            if toggle_watch.is_none() {
+                                ignore_up = true;
+                            }
+                            eprintln!("[mofa-ime] 自动结束录音: {reason}");
+                            HotkeySignal::Up
+                        }
+                        None => continue,
+                    }
                 }
                 Err(mpsc::RecvTimeoutError::Disconnected) => break,
             };
@@ -132,19 +253,40 @@ fn spawn_pipeline_worker(
 
             match sig {
                 HotkeySignal::Down => {
-                    if recorder.is_none() {
-                        match ActiveRecorder::start() {
-                            Ok(r) => {
+                    if !mic.is_recording() {
+                        match mic.start() {
+                            Ok(()) => {
+                                let start_cfg = load_app_config();
+                                if start_cfg.trigger_mode == TriggerMode::Toggle {
+                                    toggle_watch = Some(ToggleWatch::new(&mic, &start_cfg));
+                                    ignore_up = true;
+                                }
+                                let streamer = if start_cfg.asr_streaming {
+                                    // 与结束时的整段识别使用同一套预处理
+                                    let dsp = dsp_config(&start_cfg);
+                                    asr.clone()
+                                        .filter(|s| s.capabilities().timestamps)
+                                        .map(|s| {
+                                            mofa_input::asr::StreamingTranscriber::new(s)
+                                                .with_dsp(dsp)
+                                        })
+                                } else {
+                                    None
+                                };
                                 let ticker = RecordingTicker::start(
-                                    r.sample_buffer(),
-                                    r.sample_rate(),
+                                    mic.sample_buffer(),
+                                    mic.sample_rate(),
                                     overlay,
+                                    streamer,
                                 );
                                 recording_ticker = Some(ticker);
-                                recorder = Some(r);
                                 status.set(TrayState::Recording);
                                 monitor.set_state("录音中");
-                                monitor.set_hint("-");
+                                monitor.set_hint(if toggle_watch.is_some() {
+                                    "停顿或再按热键结束"
+                                } else {
+                                    "-"
+                                });
                                 overlay.show_recording();
                             }
                             Err(e) => {
@@ -160,31 +302,30 @@ fn spawn_pipeline_worker(
                     }
                 }
                 HotkeySignal::Up => {
-                    if let Some(ticker) = recording_ticker.take() {
-                        ticker.stop();
-                    }
+                    toggle_watch = None;
+                    let mut streamer = recording_ticker.take().and_then(|ticker| ticker.stop());
 
                     let app_cfg = load_app_config();
                     refresh_models(
                         &model_base,
-                        app_cfg,
+                        &app_cfg,
                         &mut asr,
-                        &mut asr_loaded_path,
+                        &mut asr_loaded,
                         &mut llm,
-                        &mut llm_loaded_path,
+                        &mut llm_loaded,
                         monitor,
                     );
 
-                    let Some(r) = recorder.take() else {
+                    if !mic.is_recording() {
                         overlay.hide();
                         continue;
-                    };
+                    }
 
                     status.set(TrayState::Processing);
                     monitor.set_state("识别中");
                     overlay.show_transcribing();
 
-                    let samples = match r.stop() {
+                    let samples = match mic.stop() {
                         Ok(s) => s,
                         Err(e) => {
                             eprintln!("[mofa-ime] 录音结束失败: {e}");
@@ -209,7 +350,18 @@ fn spawn_pipeline_worker(
                         continue;
                     }
 
-                    if audio_rms(&samples) < SILENCE_RMS_THRESHOLD {
+                    // 降噪需要开头的静音做噪声样本，所以在裁剪前处理整段
+                    let raw = samples;
+                    let samples = mofa_input::audio::preprocess(&raw, &dsp_config(&app_cfg));
+
+                    let speech_span = match vad.speech_span(&samples) {
+                        Ok(span) => span,
+                        Err(e) => {
+                            eprintln!("[mofa-ime] VAD 失败，按整段识别: {e}");
+                            Some(0..samples.len())
+                        }
+                    };
+                    let Some(speech_span) = speech_span else {
                         status.set(TrayState::Idle);
                         monitor.set_state("无语音");
                         monitor.set_hint("检测到静音");
@@ -217,7 +369,16 @@ fn spawn_pipeline_worker(
                         std::thread::sleep(Duration::from_millis(760));
                         overlay.fade_out_quick();
                         continue;
-                    }
+                    };
+                    // 去掉首尾静音；实时识别的时间轴从缓冲开头算起，开头的静音改为跳过。
+                    // 实时识别自行预处理，与中途各轮一致，所以交给它原始录音
+                    let samples = match streamer.as_mut() {
+                        Some(streamer) => {
+                            streamer.skip_to(speech_span.start);
+                            raw[..speech_span.end].to_vec()
+                        }
+                        None => samples[speech_span].to_vec(),
+                    };
 
                     let Some(asr_session) = asr.as_ref() else {
                         eprintln!("[mofa-ime] ASR 未加载，跳过");
@@ -230,11 +391,27 @@ fn spawn_pipeline_worker(
                         continue;
                     };
 
-                    let asr_preview = Arc::new(Mutex::new(String::new()));
-                    let asr_preview_cb = Arc::clone(&asr_preview);
-                    let overlay_cb = overlay;
-                    let raw_text =
-                        match asr_session.transcribe_with_progress(&samples, move |seg| {
+                    // 规则清理仅用于 ASR 原文输出，LLM 润色自行处理口语词
+                    let cleanup = if app_cfg.output_mode == OutputMode::Asr {
+                        transcript_cleanup(&app_cfg)
+                    } else {
+                        None
+                    };
+                    let transcribed = if let Some(mut streamer) = streamer {
+                        // 已提交的前缀无需重算，只解码未稳定的尾部；标点与整段识别一致
+                        streamer.finish_transcription(&samples).map(|result| {
+                            match cleanup.as_ref() {
+                                Some(cleanup) => {
+                                    (cleanup.punctuate(&result.segments), result.language)
+                                }
+                                None => (result.text, result.language),
+                            }
+                        })
+                    } else {
+                        let asr_preview = Arc::new(Mutex::new(String::new()));
+                        let asr_preview_cb = Arc::clone(&asr_preview);
+                        let overlay_cb = overlay;
+                        let on_segment = move |seg: &str| {
                             let seg = seg.trim();
                             if seg.is_empty() {
                                 return;
@@ -247,24 +424,57 @@ fn spawn_pipeline_worker(
                                 acc.push_str(seg);
                                 overlay_cb.set_preview(acc.as_str());
                             }
-                        }) {
-                            Ok(t) => t.trim().to_string(),
-                            Err(e) => {
-                                eprintln!("[mofa-ime] ASR 失败: {e}");
-                                status.set(TrayState::Error);
-                                monitor.set_state("ASR 失败");
-                                monitor.set_hint("语音识别失败");
-                                overlay.show_error("语音识别失败");
-                                std::thread::sleep(Duration::from_millis(900));
-                                overlay.fade_out_quick();
-                                continue;
-                            }
                         };
+                        match cleanup.as_ref() {
+                            // 按分段时间戳在停顿处补标点
+                            Some(cleanup) => asr_session
+                                .transcribe_segments_with_progress(&samples, on_segment)
+                                .map(|result| {
+                                    (cleanup.punctuate(&result.segments), result.language)
+                                }),
+                            None => asr_session
+                                .transcribe_with_progress(&samples, on_segment)
+                                .map(|result| (result.text, result.language)),
+                        }
+                    };
+                    let (raw_text, detected_language) = match transcribed {
+                        Ok((t, language)) => (t.trim().to_string(), language),
+                        Err(e) => {
+                            eprintln!("[mofa-ime] ASR 失败: {e}");
+                            status.set(TrayState::Error);
+                            monitor.set_state("ASR 失败");
+                            monitor.set_hint("语音识别失败");
+                            overlay.show_error("语音识别失败");
+                            std::thread::sleep(Duration::from_millis(900));
+                            overlay.fade_out_quick();
+                            continue;
+                        }
+                    };
                     let raw_text = normalize_transcript(&raw_text);
+                    let raw_text = apply_output_script(&raw_text, app_cfg.output_script);
+                    let raw_text = match cleanup.as_ref() {
+                        Some(cleanup) => {
+                            trim_added_terminal_period(&raw_text, &cleanup.clean(&raw_text))
+                        }
+                        None => raw_text,
+                    };
+                    let raw_text = if app_cfg.itn {
+                        mofa_input::text::itn::inverse_normalize(&raw_text)
+                    } else {
+                        raw_text
+                    };
                     monitor.set_asr(&raw_text);
-                    if !raw_text.is_empty() {
-                        overlay.set_preview(&raw_text);
+                    if raw_text.is_empty() {
+                        // 幻听过滤后无剩余文本，视为无语音
+                        status.set(TrayState::Idle);
+                        monitor.set_state("无语音");
+                        monitor.set_hint("识别结果已被过滤");
+                        overlay.show_error("未识别到有效内容");
+                        std::thread::sleep(Duration::from_millis(760));
+                        overlay.fade_out_quick();
+                        continue;
                     }
+                    overlay.set_preview(&raw_text);
 
                     // 注：不再对ASR原文进行过滤，直接送入LLM或输出
                     // LLM将负责过滤和润色工作
@@ -275,22 +485,39 @@ fn spawn_pipeline_worker(
                     let mut mode_text = app_cfg.output_mode.label();
                     if app_cfg.output_mode == OutputMode::Llm {
                         overlay.show_refining();
-                        if should_skip_llm_refine(&raw_text) {
+                        let language = transcript_language(detected_language, &raw_text);
+                        let action = app_cfg.refine_policy.action(language);
+                        if action == RefineAction::Skip {
                             mode_text = "ASR 原文";
-                            monitor.set_hint("英文段落直出 ASR 原文");
+                            monitor.set_hint(&format!("语言 {language}：按策略直出 ASR 原文"));
                         } else if let Some(chat) = llm.as_ref() {
-                            let prompt = build_refine_prompt(&raw_text);
+                            let prompt = match action {
+                                RefineAction::Prompt => custom_refine_prompt(language, &raw_text)
+                                    .unwrap_or_else(|| build_refine_prompt(&raw_text)),
+                                _ => build_refine_prompt(&raw_text),
+                            };
                             chat.clear();
-                            let llm_out = chat.send(&prompt, 384, 0.1).unwrap_or(raw_text.clone());
-                            let llm_out = normalize_transcript(&llm_out);
-                            let llm_out = trim_added_terminal_period(&raw_text, &llm_out);
-                            if !llm_out.is_empty() {
-                                final_text = llm_out;
-                            } else {
-                                // LLM输出为空，回退到ASR原文
-                                mode_text = "ASR 原文";
-                                monitor.set_hint("LLM 输出为空，回退 ASR 原文");
-                                // final_text 保持为 raw_text.clone()
+                            match chat.send(&prompt, 384, 0.1) {
+                                Ok(llm_out) => {
+                                    let llm_out = normalize_transcript(&llm_out);
+                                    let llm_out =
+                                        apply_output_script(&llm_out, app_cfg.output_script);
+                                    let llm_out = trim_added_terminal_period(&raw_text, &llm_out);
+                                    if !llm_out.is_empty() {
+                                        final_text = llm_out;
+                                    } else {
+                                        // LLM输出为空，回退到ASR原文
+                                        mode_text = "ASR 原文";
+                                        monitor.set_hint("LLM 输出为空，回退 ASR 原文");
+                                        // final_text 保持为 raw_text.clone()
+                                    }
+                                }
+                                Err(e) => {
+                                    // LLM进程崩溃或重启中，回退到ASR原文
+                                    eprintln!("[mofa-ime] LLM 润色失败: {e}");
+                                    mode_text = "ASR 原文";
+                                    monitor.set_hint("LLM 暂不可用，使用 ASR 原文");
+                                }
                             }
                         } else {
                             // LLM未加载，使用ASR原文
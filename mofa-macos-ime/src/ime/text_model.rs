@@ -68,26 +68,92 @@ fn choose_llm_model_auto(base: &Path) -> Option<PathBuf> {
         .find(|p| p.exists())
 }
 
-fn choose_asr_model(base: &Path, choice: AsrModelChoice) -> Option<PathBuf> {
+fn choose_asr_model(
+    base: &Path,
+    choice: AsrModelChoice,
+) -> Option<(mofa_input::asr::AsrBackendKind, PathBuf)> {
     if let Some(file_name) = choice.file_name() {
         let selected = base.join(file_name);
         if selected.exists() {
-            return Some(selected);
+            return Some((choice.backend(), selected));
         }
     }
     choose_asr_model_auto(base)
 }
 
-fn choose_asr_model_auto(base: &Path) -> Option<PathBuf> {
-    [
-        "ggml-small.bin",
-        "ggml-base.bin",
-        "ggml-tiny.bin",
-        "ggml-medium.bin",
-    ]
-    .into_iter()
-    .map(|name| base.join(name))
-    .find(|p| p.exists())
+fn choose_asr_model_auto(base: &Path) -> Option<(mofa_input::asr::AsrBackendKind, PathBuf)> {
+    let mem_gb = total_memory_gb().unwrap_or(32);
+
+    // large-v3-turbo beats Small at every size, but has to fit next to the LLM
+    let preferred: &[AsrModelChoice] = if mem_gb >= 32 {
+        &[
+            AsrModelChoice::Turbo,
+            AsrModelChoice::TurboQ8,
+            AsrModelChoice::TurboQ5,
+        ]
+    } else if mem_gb >= 16 {
+        &[AsrModelChoice::TurboQ8, AsrModelChoice::TurboQ5]
+    } else if mem_gb >= 8 {
+        &[AsrModelChoice::TurboQ5]
+    } else {
+        &[]
+    };
+
+    let mut candidates = preferred.to_vec();
+    candidates.extend([
+        AsrModelChoice::Small,
+        AsrModelChoice::SmallQ8,
+        AsrModelChoice::SmallQ5,
+        AsrModelChoice::Base,
+        AsrModelChoice::BaseQ8,
+        AsrModelChoice::BaseQ5,
+        AsrModelChoice::Tiny,
+        AsrModelChoice::TinyQ8,
+        AsrModelChoice::TinyQ5,
+        AsrModelChoice::Medium,
+        AsrModelChoice::MediumQ8,
+        AsrModelChoice::MediumQ5,
+        AsrModelChoice::TurboQ5,
+        AsrModelChoice::TurboQ8,
+        AsrModelChoice::Turbo,
+        AsrModelChoice::ParaformerSmall,
+    ]);
+
+    candidates
+        .into_iter()
+        .filter_map(|choice| Some((choice.backend(), base.join(choice.file_name()?))))
+        .find(|(_, p)| p.exists())
+}
+
+/// Whisper 幻听过滤：内置黑名单 + `~/.mofa/asr-blocklist.txt`（每行一个短语）
+fn hallucination_filter(cfg: &AppConfig) -> Option<mofa_input::asr::HallucinationFilter> {
+    if !cfg.asr_filter {
+        return None;
+    }
+    let mut filter = mofa_input::asr::HallucinationFilter::default();
+    if let Some(path) = dirs::home_dir().map(|h| h.join(".mofa/asr-blocklist.txt")) {
+        if path.exists() {
+            if let Err(e) = filter.load_blocklist(&path) {
+                eprintln!("[mofa-ime] 黑名单读取失败 {:?}: {e}", path);
+            }
+        }
+    }
+    Some(filter)
+}
+
+fn transcript_cleanup(cfg: &AppConfig) -> Option<mofa_input::text::cleanup::Cleanup> {
+    if !cfg.asr_cleanup {
+        return None;
+    }
+    let mut cleanup = mofa_input::text::cleanup::Cleanup::default();
+    if let Some(path) = dirs::home_dir().map(|h| h.join(".mofa/asr-fillers.txt")) {
+        if path.exists() {
+            if let Err(e) = cleanup.load_fillers(&path) {
+                eprintln!("[mofa-ime] 口语词表读取失败 {:?}: {e}", path);
+            }
+        }
+    }
+    Some(cleanup)
 }
 
 fn normalize_transcript(text: &str) -> String {
@@ -107,19 +173,12 @@ fn normalize_transcript(text: &str) -> String {
     out.trim().to_string()
 }
 
-fn audio_rms(samples: &[f32]) -> f32 {
-    if samples.is_empty() {
-        return 0.0;
+/// Convert Chinese text to the configured script (Whisper often mixes in Traditional)
+fn apply_output_script(text: &str, choice: OutputScriptChoice) -> String {
+    match choice.script() {
+        Some(script) => mofa_input::text::script::convert(text, script),
+        None => text.to_string(),
     }
-    let mean_square = samples
-        .iter()
-        .map(|v| {
-            let x = *v as f64;
-            x * x
-        })
-        .sum::<f64>()
-        / samples.len() as f64;
-    mean_square.sqrt() as f32
 }
 
 fn build_refine_prompt(raw_text: &str) -> String {
@@ -141,29 +200,79 @@ fn build_refine_prompt(raw_text: &str) -> String {
     )
 }
 
-fn should_skip_llm_refine(raw_text: &str) -> bool {
-    let t = raw_text.trim();
-    if t.is_empty() {
-        return true;
+/// Minimum detection probability for trusting the recognizer's language
+const LANGUAGE_MIN_PROB: f32 = 0.5;
+
+/// Language used for refine routing: the recognizer's detection when it is
+/// confident, otherwise a guess from the writing system
+fn transcript_language(
+    detected: Option<mofa_input::asr::DetectedLanguage>,
+    raw_text: &str,
+) -> &'static str {
+    match detected {
+        Some(lang) if lang.probability >= LANGUAGE_MIN_PROB => lang.code,
+        _ => guess_language(raw_text),
     }
+}
 
-    // Skip LLM for full English paragraphs/sentences to avoid unwanted rewriting.
-    let mut english_letters = 0usize;
-    let mut cjk_chars = 0usize;
-    for ch in t.chars() {
-        if ch.is_ascii_alphabetic() {
-            english_letters += 1;
-        } else if ('\u{4E00}'..='\u{9FFF}').contains(&ch) {
-            cjk_chars += 1;
+fn guess_language(text: &str) -> &'static str {
+    let mut han = 0usize;
+    let mut kana = 0usize;
+    let mut hangul = 0usize;
+    let mut ascii_letters = 0usize;
+    let mut other_letters = 0usize;
+    for ch in text.chars() {
+        match ch {
+            '\u{3040}'..='\u{30FF}' | '\u{31F0}'..='\u{31FF}' => kana += 1,
+            '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}' => {
+                hangul += 1
+            }
+            '\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}' | '\u{F900}'..='\u{FAFF}' => han += 1,
+            c if c.is_ascii_alphabetic() => ascii_letters += 1,
+            c if c.is_alphabetic() => other_letters += 1,
+            _ => {}
         }
     }
-    let total_lang = english_letters + cjk_chars;
-    if total_lang == 0 {
-        return false;
+
+    // Japanese mixes kanji with kana; any real share of kana marks it
+    if kana > 0 && kana * 10 >= kana + han {
+        return "ja";
+    }
+    if hangul > 0 && hangul >= han {
+        return "ko";
     }
+    let latin = ascii_letters + other_letters;
+    if han > 0 {
+        // English letters are counted per letter, Han per word-like character
+        let latin_ratio = latin as f32 / (latin + han) as f32;
+        if latin < 16 || latin_ratio < 0.9 {
+            return "zh";
+        }
+    }
+    if latin == 0 {
+        return "zh";
+    }
+    if other_letters * 20 > latin {
+        "other"
+    } else {
+        "en"
+    }
+}
 
-    let english_ratio = english_letters as f32 / total_lang as f32;
-    english_letters >= 16 && english_ratio >= 0.9
+/// User prompt for `language` from `~/.mofa/refine-prompt-<language>.txt`;
+/// `{text}` marks where the transcript goes, otherwise it is appended
+fn custom_refine_prompt(language: &str, raw_text: &str) -> Option<String> {
+    let path = dirs::home_dir()?.join(format!(".mofa/refine-prompt-{language}.txt"));
+    let template = fs::read_to_string(&path).ok()?;
+    let template = template.trim();
+    if template.is_empty() {
+        return None;
+    }
+    Some(if template.contains("{text}") {
+        template.replace("{text}", raw_text)
+    } else {
+        format!("{template}\n\n{raw_text}")
+    })
 }
 
 fn has_terminal_punctuation(text: &str) -> bool {
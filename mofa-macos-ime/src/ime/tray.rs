@@ -29,6 +29,9 @@ impl TrayState {
     }
 }
 
+// 预录开启时麦克风常开，托盘标题带圆点提示
+static MIC_WARM: AtomicBool = AtomicBool::new(false);
+
 #[derive(Clone, Copy)]
 struct StatusHandle {
     button_ptr: usize,
@@ -37,7 +40,10 @@ struct StatusHandle {
 impl StatusHandle {
     fn set(self, state: TrayState) {
         let button_ptr = self.button_ptr;
-        let title = state.title().to_string();
+        let mut title = state.title().to_string();
+        if MIC_WARM.load(Ordering::SeqCst) {
+            title.push_str(" ●");
+        }
         let symbol = state.symbol_name().to_string();
         Queue::main().exec_async(move || unsafe {
             let button = button_ptr as id;
@@ -47,6 +53,11 @@ impl StatusHandle {
             }
         });
     }
+
+    /// 记录麦克风是否常开，下次 `set` 时生效
+    fn set_mic_warm(self, warm: bool) {
+        MIC_WARM.store(warm, Ordering::SeqCst);
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -55,6 +66,7 @@ struct MonitorHandle {
     asr_item_ptr: usize,
     output_item_ptr: usize,
     hint_item_ptr: usize,
+    mic_item_ptr: usize,
 }
 
 impl MonitorHandle {
@@ -74,6 +86,10 @@ impl MonitorHandle {
         self.set_item(self.hint_item_ptr, "提示", text);
     }
 
+    fn set_mic(self, text: &str) {
+        self.set_item(self.mic_item_ptr, "麦克风", text);
+    }
+
     fn set_item(self, item_ptr: usize, label: &str, value: &str) {
         let title = format!("{label}: {}", truncate_middle(value, 64));
         Queue::main().exec_async(move || unsafe {
@@ -792,11 +808,13 @@ unsafe fn install_status_item(app: id) -> Result<(StatusHandle, MonitorHandle, i
     let asr_item = make_info_item("识别: -", menu_handler);
     let output_item = make_info_item("发送: -", menu_handler);
     let hint_item = make_info_item("提示: -", menu_handler);
+    let mic_item = make_info_item("麦克风: 仅录音时开启", menu_handler);
 
     menu.addItem_(state_item);
     menu.addItem_(asr_item);
     menu.addItem_(output_item);
     menu.addItem_(hint_item);
+    menu.addItem_(mic_item);
     menu.addItem_(NSMenuItem::separatorItem(nil));
 
     let settings_item = NSMenuItem::alloc(nil)
@@ -827,6 +845,7 @@ unsafe fn install_status_item(app: id) -> Result<(StatusHandle, MonitorHandle, i
             asr_item_ptr: asr_item as usize,
             output_item_ptr: output_item as usize,
             hint_item_ptr: hint_item as usize,
+            mic_item_ptr: mic_item as usize,
         },
         status_item,
         menu,
@@ -30,8 +30,6 @@ use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::{Arc, Mutex, OnceLock};
 use std::time::Duration;
 
-use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-
 #[cfg(not(target_os = "macos"))]
 fn main() {
     eprintln!("mofa-macos-ime 仅支持 macOS");
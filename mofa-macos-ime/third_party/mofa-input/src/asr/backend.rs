@@ -0,0 +1,164 @@
+//! Backend-agnostic ASR interface
+
+use std::path::Path;
+
+use super::engine::{WhisperEngine, WhisperParams};
+use super::funasr::FunAsrEngine;
+use super::transcript::Transcription;
+
+/// What a backend can do beyond plain transcription
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AsrCapabilities {
+    /// Segment/token timestamps via `transcribe_detailed`
+    pub timestamps: bool,
+    /// Incremental output while decoding
+    pub streaming: bool,
+    /// Honours a forced language in `WhisperParams`
+    pub language_selection: bool,
+    pub translate: bool,
+}
+
+/// Speech recognition backend (16kHz, mono, f32 input)
+///
+/// Transcription takes `&self` so one loaded model can serve concurrent calls.
+pub trait AsrBackend: Send + Sync {
+    /// Short backend name, e.g. "whisper"
+    fn name(&self) -> &'static str;
+
+    fn capabilities(&self) -> AsrCapabilities;
+
+    /// Supported language codes
+    fn languages(&self) -> &'static [&'static str];
+
+    fn transcribe(&self, samples: &[f32]) -> anyhow::Result<String>;
+
+    /// Transcribe, reporting each decoded piece of text as it is produced
+    fn transcribe_streaming(
+        &self,
+        samples: &[f32],
+        on_text: &mut dyn FnMut(&str),
+    ) -> anyhow::Result<Transcription> {
+        let text = self.transcribe(samples)?;
+        on_text(&text);
+        Ok(Transcription::from_text(text, None))
+    }
+
+    fn transcribe_detailed(&self, _samples: &[f32]) -> anyhow::Result<Transcription> {
+        Err(anyhow::anyhow!("{} backend does not provide timestamps", self.name()))
+    }
+
+    /// Apply decoding parameters; unsupported fields are ignored
+    fn set_params(&mut self, _params: &WhisperParams) {}
+}
+
+/// Backend families that can be loaded from a model path
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum AsrBackendKind {
+    /// whisper.cpp `ggml-*.bin` file
+    Whisper,
+    /// FunASR Paraformer directory with `model.onnx`
+    FunAsr,
+}
+
+impl AsrBackendKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AsrBackendKind::Whisper => "whisper",
+            AsrBackendKind::FunAsr => "funasr",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "whisper" => Some(AsrBackendKind::Whisper),
+            "funasr" | "paraformer" => Some(AsrBackendKind::FunAsr),
+            _ => None,
+        }
+    }
+
+    /// Guess the backend from a model path: directories are FunASR models
+    pub fn detect(model_path: &Path) -> Self {
+        if model_path.is_dir() {
+            AsrBackendKind::FunAsr
+        } else {
+            AsrBackendKind::Whisper
+        }
+    }
+
+    pub fn load(
+        &self,
+        model_path: &Path,
+        params: &WhisperParams,
+    ) -> anyhow::Result<Box<dyn AsrBackend>> {
+        let mut backend: Box<dyn AsrBackend> = match self {
+            AsrBackendKind::Whisper => Box::new(WhisperEngine::new(model_path)?),
+            AsrBackendKind::FunAsr => Box::new(FunAsrEngine::new(model_path)?),
+        };
+        backend.set_params(params);
+        Ok(backend)
+    }
+}
+
+/// Languages whisper.cpp can be forced to (subset exposed in the UI)
+const WHISPER_LANGUAGES: &[&str] = &[
+    "zh", "en", "ja", "ko", "yue", "de", "fr", "es", "ru", "it", "pt",
+];
+
+const FUNASR_LANGUAGES: &[&str] = &["zh", "en"];
+
+impl AsrBackend for WhisperEngine {
+    fn name(&self) -> &'static str {
+        "whisper"
+    }
+
+    fn capabilities(&self) -> AsrCapabilities {
+        AsrCapabilities {
+            timestamps: true,
+            streaming: true,
+            language_selection: true,
+            translate: true,
+        }
+    }
+
+    fn languages(&self) -> &'static [&'static str] {
+        WHISPER_LANGUAGES
+    }
+
+    fn transcribe(&self, samples: &[f32]) -> anyhow::Result<String> {
+        WhisperEngine::transcribe(self, samples)
+    }
+
+    fn transcribe_streaming(
+        &self,
+        samples: &[f32],
+        on_text: &mut dyn FnMut(&str),
+    ) -> anyhow::Result<Transcription> {
+        WhisperEngine::transcribe_with_progress(self, samples, on_text)
+    }
+
+    fn transcribe_detailed(&self, samples: &[f32]) -> anyhow::Result<Transcription> {
+        WhisperEngine::transcribe_detailed(self, samples)
+    }
+
+    fn set_params(&mut self, params: &WhisperParams) {
+        WhisperEngine::set_params(self, params.clone());
+    }
+}
+
+impl AsrBackend for FunAsrEngine {
+    fn name(&self) -> &'static str {
+        "funasr"
+    }
+
+    fn capabilities(&self) -> AsrCapabilities {
+        AsrCapabilities::default()
+    }
+
+    fn languages(&self) -> &'static [&'static str] {
+        FUNASR_LANGUAGES
+    }
+
+    fn transcribe(&self, samples: &[f32]) -> anyhow::Result<String> {
+        FunAsrEngine::transcribe(self, samples)
+    }
+}
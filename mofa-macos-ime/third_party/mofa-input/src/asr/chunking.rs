@@ -0,0 +1,529 @@
+//! Chunked transcription of long recordings
+//!
+//! Audio longer than one chunk is cut at the longest pause near the chunk
+//! limit, found with the energy VAD. When the speech has no usable pause the
+//! cut falls at the limit and the next chunk starts `overlap_ms` earlier; the
+//! words heard twice are removed by aligning the two transcripts (or by token
+//! timestamps when segments are available).
+
+use std::ops::Range;
+
+use super::transcript::{DetectedLanguage, Segment};
+use crate::audio::vad::EnergyDetector;
+
+const SAMPLE_RATE: usize = 16_000;
+/// 30 ms analysis frames
+const FRAME: usize = 480;
+/// Most words or CJK characters recognized per second of speech; bounds how
+/// much text the shared audio can produce
+const MAX_UNITS_PER_SEC: usize = 8;
+/// Units a shared run may stop short of the end of the previous transcript
+/// or start into the next, e.g. a word the cut split in half
+const EDGE_SLACK: usize = 3;
+
+/// Chunking configuration
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ChunkConfig {
+    /// Longest chunk sent to the recognizer (Whisper sees 30 s at a time)
+    pub max_chunk_ms: u32,
+    /// Pauses are only cut at once a chunk is at least this long
+    pub min_chunk_ms: u32,
+    /// Shortest silence accepted as a cut point
+    pub min_pause_ms: u32,
+    /// Audio shared by two chunks cut without a pause
+    pub overlap_ms: u32,
+}
+
+impl Default for ChunkConfig {
+    fn default() -> Self {
+        Self {
+            max_chunk_ms: 28_000,
+            min_chunk_ms: 15_000,
+            min_pause_ms: 300,
+            overlap_ms: 2_000,
+        }
+    }
+}
+
+/// A span of samples transcribed on its own
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Chunk {
+    pub start: usize,
+    pub end: usize,
+    /// Samples at the start shared with the previous chunk
+    pub overlap: usize,
+}
+
+impl Chunk {
+    pub fn start_ms(&self) -> i64 {
+        (self.start * 1000 / SAMPLE_RATE) as i64
+    }
+
+    /// Length of the audio shared with the previous chunk
+    pub fn overlap_ms(&self) -> u32 {
+        (self.overlap * 1000 / SAMPLE_RATE) as u32
+    }
+
+    /// Middle of the shared audio, where the previous chunk's text hands over
+    pub fn handover_ms(&self) -> i64 {
+        ((self.start + self.overlap / 2) * 1000 / SAMPLE_RATE) as i64
+    }
+}
+
+/// Split 16kHz audio into chunks; short audio is a single chunk
+pub fn plan_chunks(samples: &[f32], config: &ChunkConfig) -> Vec<Chunk> {
+    let max = ms_to_samples(config.max_chunk_ms).max(FRAME * 2);
+    let min = ms_to_samples(config.min_chunk_ms).min(max);
+    let overlap = ms_to_samples(config.overlap_ms).min(max / 2);
+    let min_pause = (ms_to_samples(config.min_pause_ms) / FRAME).max(1);
+    if samples.len() <= max {
+        return vec![Chunk {
+            start: 0,
+            end: samples.len(),
+            overlap: 0,
+        }];
+    }
+
+    let silent = silent_frames(samples);
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut shared = 0;
+    while samples.len() - start > max {
+        let limit = start + max;
+        let pause = longest_pause(&silent, (start + min) / FRAME, limit / FRAME, min_pause);
+        match pause {
+            Some(frames) => {
+                let cut = (frames.start + frames.end) / 2 * FRAME;
+                chunks.push(Chunk {
+                    start,
+                    end: cut,
+                    overlap: shared,
+                });
+                start = cut;
+                shared = 0;
+            }
+            None => {
+                chunks.push(Chunk {
+                    start,
+                    end: limit,
+                    overlap: shared,
+                });
+                start = limit - overlap;
+                shared = overlap;
+            }
+        }
+    }
+    chunks.push(Chunk {
+        start,
+        end: samples.len(),
+        overlap: shared,
+    });
+    chunks
+}
+
+/// Per-frame silence flags from the energy VAD
+fn silent_frames(samples: &[f32]) -> Vec<bool> {
+    let detector = EnergyDetector {
+        frame_len: FRAME,
+        ..Default::default()
+    };
+    detector
+        .speech_frames(samples)
+        .into_iter()
+        .map(|speech| !speech)
+        .collect()
+}
+
+/// Longest run of at least `min_len` silent frames within `from..to`; later
+/// runs win ties so chunks stay long
+fn longest_pause(silent: &[bool], from: usize, to: usize, min_len: usize) -> Option<Range<usize>> {
+    let to = to.min(silent.len());
+    let mut best: Option<Range<usize>> = None;
+    let mut run_start = None;
+    let flags = silent.get(from..to).unwrap_or_default();
+    for (i, is_silent) in flags.iter().copied().chain([false]).enumerate() {
+        let i = from + i;
+        match (is_silent, run_start) {
+            (true, None) => run_start = Some(i),
+            (false, Some(s)) => {
+                run_start = None;
+                let len = i - s;
+                if len >= min_len && best.as_ref().is_none_or(|b| len >= b.len()) {
+                    best = Some(s..i);
+                }
+            }
+            _ => {}
+        }
+    }
+    best
+}
+
+/// Append the next chunk's text to `merged`; when the chunks share
+/// `overlap_ms` of audio the words both transcripts have there are dropped.
+/// Returns the text appended.
+pub fn merge_text(merged: &mut String, next: &str, overlap_ms: u32) -> String {
+    let next = next.trim();
+    let cut = if overlap_ms > 0 {
+        let window = (overlap_ms as usize * MAX_UNITS_PER_SEC).div_ceil(1000) + EDGE_SLACK;
+        overlap_cut(merged, next, window)
+    } else {
+        None
+    };
+    let (keep, skip) = cut.unwrap_or((merged.len(), 0));
+    merged.truncate(keep);
+    let tail = next[skip..].trim_start();
+    let mut appended = String::new();
+    let needs_space = merged.trim_end().len() == merged.len()
+        && merged.ends_with(|c: char| c.is_ascii_alphanumeric() || c.is_ascii_punctuation())
+        && tail.starts_with(|c: char| c.is_ascii_alphanumeric());
+    if needs_space {
+        appended.push(' ');
+    }
+    appended.push_str(tail);
+    merged.push_str(&appended);
+    appended
+}
+
+/// Byte offsets `(keep, skip)`: keep `prev[..keep]` and continue with
+/// `next[skip..]`, cutting both at the start of their longest common run
+/// of words (or CJK characters) among the last and first `window` units.
+/// The run must reach the end of `prev` and the start of `next`, so a
+/// phrase said twice is not mistaken for the overlap.
+fn overlap_cut(prev: &str, next: &str, window: usize) -> Option<(usize, usize)> {
+    let prev_units = units(prev);
+    let next_units = units(next);
+    let tail = &prev_units[prev_units.len().saturating_sub(window)..];
+    let head = &next_units[..next_units.len().min(window)];
+
+    // Longest common substring over units, anchored at both edges
+    let mut best = (0, 0, 0);
+    let mut lengths = vec![0usize; head.len() + 1];
+    for (i, (_, a)) in tail.iter().enumerate() {
+        for j in (0..head.len()).rev() {
+            lengths[j + 1] = if *a == head[j].1 { lengths[j] + 1 } else { 0 };
+            let anchored =
+                tail.len() - (i + 1) <= EDGE_SLACK && j + 1 - lengths[j + 1] <= EDGE_SLACK;
+            if anchored && lengths[j + 1] > best.0 {
+                best = (
+                    lengths[j + 1],
+                    i + 1 - lengths[j + 1],
+                    j + 1 - lengths[j + 1],
+                );
+            }
+        }
+    }
+    let (len, i, j) = best;
+    let matched_chars: usize = tail[i..i + len]
+        .iter()
+        .map(|(_, u)| u.chars().count())
+        .sum();
+    if len < 2 || matched_chars < 3 {
+        return None;
+    }
+    Some((tail[i].0.start, head[j].0.start))
+}
+
+/// Words and CJK characters with their byte ranges, lowercased
+fn units(text: &str) -> Vec<(Range<usize>, String)> {
+    let mut units = Vec::new();
+    let mut word: Option<usize> = None;
+    for (i, c) in text.char_indices() {
+        let wordlike = c.is_alphanumeric() && !is_cjk(c);
+        if let Some(start) = word {
+            if wordlike || c == '\'' {
+                continue;
+            }
+            units.push((start..i, text[start..i].to_lowercase()));
+            word = None;
+        }
+        if is_cjk(c) {
+            units.push((i..i + c.len_utf8(), c.to_string()));
+        } else if wordlike {
+            word = Some(i);
+        }
+    }
+    if let Some(start) = word {
+        units.push((start..text.len(), text[start..].to_lowercase()));
+    }
+    units
+}
+
+/// Language of most of the audio from per-chunk detections and chunk
+/// lengths; its probability is the length-weighted mean over those chunks
+pub fn merge_languages(detected: &[(DetectedLanguage, usize)]) -> Option<DetectedLanguage> {
+    let weight = |code: &str| -> usize {
+        detected
+            .iter()
+            .filter(|(l, _)| l.code == code)
+            .map(|(_, len)| len)
+            .sum()
+    };
+    let (best, _) = detected.iter().max_by_key(|(l, _)| weight(l.code))?;
+    let total = weight(best.code).max(1) as f32;
+    let probability = detected
+        .iter()
+        .filter(|(l, _)| l.code == best.code)
+        .map(|(l, len)| l.probability * *len as f32)
+        .sum::<f32>()
+        / total;
+    Some(DetectedLanguage {
+        code: best.code,
+        probability,
+    })
+}
+
+/// Shift segment and token times by `offset_ms`
+pub fn offset_segments(segments: &mut [Segment], offset_ms: i64) {
+    for seg in segments {
+        seg.start_ms += offset_ms;
+        seg.end_ms += offset_ms;
+        for token in &mut seg.tokens {
+            token.t0 += offset_ms;
+            token.t1 += offset_ms;
+        }
+    }
+}
+
+/// Append the next chunk's segments (already on the global timeline); tokens
+/// before `handover_ms` belong to the previous chunk, the rest to the next.
+/// Returns how many segments were appended.
+pub fn merge_segments(merged: &mut Vec<Segment>, next: Vec<Segment>, handover_ms: i64) -> usize {
+    merged.retain_mut(|seg| keep_tokens(seg, |t0| t0 < handover_ms));
+    let before = merged.len();
+    merged.extend(
+        next.into_iter()
+            .filter_map(|mut seg| keep_tokens(&mut seg, |t0| t0 >= handover_ms).then_some(seg)),
+    );
+    merged.len() - before
+}
+
+/// Drop tokens failing `keep`; false if nothing is left. Segments without
+/// tokens are judged by their start time.
+fn keep_tokens(seg: &mut Segment, keep: impl Fn(i64) -> bool) -> bool {
+    if seg.tokens.is_empty() {
+        return keep(seg.start_ms);
+    }
+    let before = seg.tokens.len();
+    seg.tokens.retain(|t| keep(t.t0));
+    if seg.tokens.len() != before {
+        let (Some(first), Some(last)) = (seg.tokens.first(), seg.tokens.last()) else {
+            return false;
+        };
+        seg.start_ms = first.t0;
+        seg.end_ms = last.t1;
+        seg.text = seg.tokens.iter().map(|t| t.text.as_str()).collect();
+    }
+    !seg.tokens.is_empty()
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c, '\u{3040}'..='\u{30FF}' | '\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}' | '\u{AC00}'..='\u{D7AF}' | '\u{F900}'..='\u{FAFF}')
+}
+
+fn ms_to_samples(ms: u32) -> usize {
+    ms as usize * SAMPLE_RATE / 1000
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asr::transcript::Token;
+
+    /// Speech-level tone for `secs` seconds, or silence at amplitude 0
+    fn audio(secs: f32, amplitude: f32) -> Vec<f32> {
+        (0..(secs * SAMPLE_RATE as f32) as usize)
+            .map(|i| amplitude * (i as f32 * 0.12).sin())
+            .collect()
+    }
+
+    fn config() -> ChunkConfig {
+        ChunkConfig {
+            max_chunk_ms: 10_000,
+            min_chunk_ms: 5_000,
+            min_pause_ms: 300,
+            overlap_ms: 1_000,
+        }
+    }
+
+    #[test]
+    fn short_audio_is_one_chunk() {
+        let samples = audio(8.0, 0.1);
+        let chunks = plan_chunks(&samples, &config());
+        assert_eq!(
+            chunks,
+            vec![Chunk {
+                start: 0,
+                end: samples.len(),
+                overlap: 0,
+            }]
+        );
+    }
+
+    #[test]
+    fn cuts_snap_to_pauses() {
+        let samples = [audio(7.2, 0.1), audio(0.6, 0.0), audio(7.2, 0.1)].concat();
+        let chunks = plan_chunks(&samples, &config());
+        assert_eq!(chunks.len(), 2);
+        // Middle of the pause, with nothing shared
+        let cut = chunks[0].end;
+        assert!((7 * SAMPLE_RATE..8 * SAMPLE_RATE).contains(&cut), "{cut}");
+        assert_eq!(cut % FRAME, 0);
+        assert_eq!(chunks[1].start, cut);
+        assert_eq!(chunks[1].overlap, 0);
+        assert_eq!(chunks[1].end, samples.len());
+    }
+
+    #[test]
+    fn pauses_before_min_chunk_are_ignored() {
+        let samples = [audio(2.0, 0.1), audio(0.6, 0.0), audio(15.0, 0.1)].concat();
+        let chunks = plan_chunks(&samples, &config());
+        assert_eq!(chunks[0].end, 10 * SAMPLE_RATE);
+        assert_eq!(chunks[1].overlap, SAMPLE_RATE);
+    }
+
+    #[test]
+    fn speech_without_pauses_is_cut_at_the_limit_with_overlap() {
+        let samples = audio(25.0, 0.1);
+        let chunks = plan_chunks(&samples, &config());
+        let secs = |s: usize| s as f32 / SAMPLE_RATE as f32;
+        let spans: Vec<(f32, f32)> = chunks
+            .iter()
+            .map(|c| (secs(c.start), secs(c.end)))
+            .collect();
+        assert_eq!(spans, vec![(0.0, 10.0), (9.0, 19.0), (18.0, 25.0)]);
+        assert_eq!(chunks[0].overlap, 0);
+        assert!(chunks[1..].iter().all(|c| c.overlap_ms() == 1000));
+        assert_eq!(chunks[1].handover_ms(), 9500);
+        assert!(chunks.iter().all(|c| c.end - c.start <= 10 * SAMPLE_RATE));
+    }
+
+    #[test]
+    fn merge_text_drops_the_shared_words() {
+        let mut merged = "今天我们讨论一下项目的进度安排".to_string();
+        let appended = merge_text(&mut merged, "进度安排和下周的计划", 2000);
+        assert_eq!(merged, "今天我们讨论一下项目的进度安排和下周的计划");
+        assert_eq!(appended, "进度安排和下周的计划");
+
+        let mut merged = "We should ship the release on Friday".to_string();
+        merge_text(&mut merged, "on friday after the review.", 2000);
+        assert_eq!(
+            merged,
+            "We should ship the release on friday after the review."
+        );
+    }
+
+    #[test]
+    fn merge_text_appends_without_overlap() {
+        let mut merged = "first part.".to_string();
+        assert_eq!(merge_text(&mut merged, " Second part", 0), " Second part");
+        assert_eq!(merged, "first part. Second part");
+
+        // Shared words without shared audio are both kept
+        let mut merged = "我们开会".to_string();
+        merge_text(&mut merged, "我们开会吧", 0);
+        assert_eq!(merged, "我们开会我们开会吧");
+
+        // Shared audio but nothing in common
+        let mut merged = "你好世界".to_string();
+        merge_text(&mut merged, "再见朋友", 2000);
+        assert_eq!(merged, "你好世界再见朋友");
+    }
+
+    #[test]
+    fn merge_text_ignores_repeats_outside_the_overlap() {
+        // Said early in the previous chunk, far before the shared audio
+        let prev = "我们的目标是提高效率，为此需要更多的人手来完成这个非常重要的任务";
+        let mut merged = prev.to_string();
+        merge_text(&mut merged, "我们的目标是在下个月完成", 2000);
+        assert_eq!(merged, format!("{prev}我们的目标是在下个月完成"));
+
+        // Within the window but not at the end of the previous text
+        let prev = "项目的进度安排很紧张我们要加快";
+        let mut merged = prev.to_string();
+        merge_text(&mut merged, "进度安排明天再说", 2000);
+        assert_eq!(merged, format!("{prev}进度安排明天再说"));
+    }
+
+    fn token(text: &str, t0: i64) -> Token {
+        Token {
+            text: text.to_string(),
+            p: 0.9,
+            t0,
+            t1: t0 + 100,
+        }
+    }
+
+    fn segment(tokens: Vec<Token>) -> Segment {
+        Segment {
+            start_ms: tokens[0].t0,
+            end_ms: tokens[tokens.len() - 1].t1,
+            text: tokens.iter().map(|t| t.text.as_str()).collect(),
+            avg_logprob: -0.2,
+            no_speech_prob: 0.1,
+            tokens,
+        }
+    }
+
+    #[test]
+    fn merge_segments_hands_over_at_the_middle_of_the_overlap() {
+        let mut merged = vec![
+            segment(vec![token("你好", 0), token("世界", 200)]),
+            segment(vec![
+                token("今天", 9_000),
+                token("天气", 9_400),
+                token("不错", 9_600),
+            ]),
+        ];
+        let next = vec![
+            segment(vec![
+                token("今天", 9_100),
+                token("天气", 9_500),
+                token("不错", 9_700),
+            ]),
+            segment(vec![token("出去", 10_500)]),
+        ];
+        assert_eq!(merge_segments(&mut merged, next, 9_500), 2);
+        let texts: Vec<&str> = merged.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, ["你好世界", "今天天气", "天气不错", "出去"]);
+        assert_eq!((merged[1].start_ms, merged[1].end_ms), (9_000, 9_500));
+        assert_eq!((merged[2].start_ms, merged[2].end_ms), (9_500, 9_800));
+    }
+
+    #[test]
+    fn merge_segments_without_tokens_uses_start_times() {
+        let mut bare = segment(vec![token("x", 0)]);
+        bare.tokens.clear();
+        let mut merged = vec![bare.clone()];
+        let mut late = bare.clone();
+        late.start_ms = 5_000;
+        assert_eq!(merge_segments(&mut merged, vec![bare, late], 1_000), 1);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[1].start_ms, 5_000);
+    }
+
+    #[test]
+    fn offsets_move_segments_and_tokens() {
+        let mut segments = vec![segment(vec![token("a", 0), token("b", 300)])];
+        offset_segments(&mut segments, 1_000);
+        assert_eq!((segments[0].start_ms, segments[0].end_ms), (1_000, 1_400));
+        assert_eq!(segments[0].tokens[1].t0, 1_300);
+    }
+
+    #[test]
+    fn merged_language_is_weighted_by_length() {
+        let zh = |probability| DetectedLanguage {
+            code: "zh",
+            probability,
+        };
+        let en = DetectedLanguage {
+            code: "en",
+            probability: 0.5,
+        };
+        let merged = merge_languages(&[(zh(0.8), 100), (en, 150), (zh(0.6), 100)]).unwrap();
+        assert_eq!(merged.code, "zh");
+        assert!((merged.probability - 0.7).abs() < 1e-6);
+
+        assert_eq!(merge_languages(&[(en, 10)]), Some(en));
+        assert_eq!(merge_languages(&[]), None);
+    }
+}
@@ -1,14 +1,179 @@
 //! Whisper engine implementation
 
+use std::ops::{Deref, DerefMut};
 use std::path::Path;
-use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+use std::sync::{Condvar, Mutex};
+
+use super::transcript::{DetectedLanguage, Segment, Token, Transcription};
+use whisper_rs::{
+    FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperState,
+};
+
+/// Whisper decoding parameters
+#[derive(Clone, Debug, PartialEq)]
+pub struct WhisperParams {
+    /// Forced language code (e.g. "zh", "en"); `None` auto-detects
+    pub language: Option<String>,
+    /// Beam width; 1 uses greedy decoding
+    pub beam_size: usize,
+    /// Decoder threads; `None` uses whisper.cpp's default
+    pub n_threads: Option<usize>,
+    /// Prompt text to bias vocabulary and punctuation style
+    pub initial_prompt: Option<String>,
+    /// Translate the transcript to English
+    pub translate: bool,
+    /// Initial sampling temperature
+    pub temperature: f32,
+    /// Temperature increment on decode failure; 0 disables fallback
+    pub temperature_inc: f32,
+    /// Segments above this no-speech probability are treated as silence
+    pub no_speech_thold: f32,
+    /// Transcriptions one model can run at once; each needs its own decoder
+    /// state (KV cache and compute buffers), which is kept for reuse
+    pub parallelism: usize,
+}
+
+impl Default for WhisperParams {
+    fn default() -> Self {
+        Self {
+            language: None, // Auto-detect language (supports Chinese-English mixed)
+            beam_size: 1,
+            n_threads: None,
+            initial_prompt: None,
+            translate: false,
+            temperature: 0.0,
+            temperature_inc: 0.2,
+            no_speech_thold: 0.6,
+            parallelism: 1,
+        }
+    }
+}
+
+impl WhisperParams {
+    /// Parameters pinned to a single language
+    pub fn with_language(language: &str) -> Self {
+        Self {
+            language: Some(language.to_string()),
+            ..Self::default()
+        }
+    }
+}
+
+/// Decoder states created on demand up to a limit and kept between calls
+struct StatePool {
+    slots: Mutex<PoolSlots>,
+    released: Condvar,
+}
+
+struct PoolSlots {
+    idle: Vec<WhisperState>,
+    /// States in existence, idle or in use
+    created: usize,
+    limit: usize,
+}
+
+impl StatePool {
+    fn new(limit: usize) -> Self {
+        Self {
+            slots: Mutex::new(PoolSlots {
+                idle: Vec::new(),
+                created: 0,
+                limit: limit.max(1),
+            }),
+            released: Condvar::new(),
+        }
+    }
+
+    fn set_limit(&self, limit: usize) {
+        let mut slots = self.slots.lock().unwrap();
+        slots.limit = limit.max(1);
+        while slots.created > slots.limit && slots.idle.pop().is_some() {
+            slots.created -= 1;
+        }
+        self.released.notify_all();
+    }
+
+    /// Take an idle state, create one if under the limit, or wait for one
+    fn acquire<'a>(&'a self, context: &WhisperContext) -> anyhow::Result<PooledState<'a>> {
+        let mut slots = self.slots.lock().unwrap();
+        loop {
+            if let Some(state) = slots.idle.pop() {
+                return Ok(PooledState {
+                    pool: self,
+                    state: Some(state),
+                });
+            }
+            if slots.created < slots.limit {
+                slots.created += 1;
+                drop(slots);
+                return match context.create_state() {
+                    Ok(state) => Ok(PooledState {
+                        pool: self,
+                        state: Some(state),
+                    }),
+                    Err(e) => {
+                        self.slots.lock().unwrap().created -= 1;
+                        self.released.notify_one();
+                        Err(e.into())
+                    }
+                };
+            }
+            slots = self.released.wait(slots).unwrap();
+        }
+    }
+
+    fn release(&self, state: WhisperState) {
+        let mut slots = self.slots.lock().unwrap();
+        if slots.created > slots.limit {
+            // The limit shrank while this state was in use
+            slots.created -= 1;
+        } else {
+            slots.idle.push(state);
+        }
+        self.released.notify_one();
+    }
+}
+
+/// A state borrowed from the pool; returned when dropped
+struct PooledState<'a> {
+    pool: &'a StatePool,
+    state: Option<WhisperState>,
+}
+
+impl Deref for PooledState<'_> {
+    type Target = WhisperState;
+
+    fn deref(&self) -> &WhisperState {
+        self.state.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for PooledState<'_> {
+    fn deref_mut(&mut self) -> &mut WhisperState {
+        self.state.as_mut().unwrap()
+    }
+}
+
+impl Drop for PooledState<'_> {
+    fn drop(&mut self) {
+        if let Some(state) = self.state.take() {
+            self.pool.release(state);
+        }
+    }
+}
 
 pub struct WhisperEngine {
     context: WhisperContext,
+    params: WhisperParams,
+    states: StatePool,
 }
 
 impl WhisperEngine {
     pub fn new(model_path: &Path) -> anyhow::Result<Self> {
+        Self::with_params(model_path, WhisperParams::default())
+    }
+
+    pub fn with_params(model_path: &Path, params: WhisperParams) -> anyhow::Result<Self> {
         if !model_path.exists() {
             return Err(anyhow::anyhow!("Model file not found: {:?}", model_path));
         }
@@ -20,64 +185,115 @@ impl WhisperEngine {
         )
         .map_err(|e| anyhow::anyhow!("Failed to load model: {:?}", e))?;
 
-        Ok(Self { context })
+        Ok(Self {
+            context,
+            states: StatePool::new(params.parallelism),
+            params,
+        })
     }
 
-    /// Transcribe audio samples (16kHz, mono, f32)
-    pub fn transcribe(&self, samples: &[f32]) -> anyhow::Result<String> {
-        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    pub fn params(&self) -> &WhisperParams {
+        &self.params
+    }
+
+    pub fn set_params(&mut self, params: WhisperParams) {
+        self.states.set_limit(params.parallelism);
+        self.params = params;
+    }
+
+    fn n_threads(&self) -> usize {
+        self.params.n_threads.unwrap_or_else(|| {
+            std::thread::available_parallelism()
+                .map(|n| n.get().min(4))
+                .unwrap_or(4)
+        })
+    }
+
+    /// Detect the language (unless forced) and decode into a pooled state
+    fn run(
+        &self,
+        samples: &[f32],
+        token_timestamps: bool,
+    ) -> anyhow::Result<(PooledState<'_>, Option<DetectedLanguage>)> {
+        let mut params = self.full_params();
+        params.set_token_timestamps(token_timestamps);
+
+        let mut state = self.states.acquire(&self.context)?;
+        let language = match self.params.language.as_deref() {
+            Some(code) => whisper_rs::get_lang_id(code)
+                .and_then(whisper_rs::get_lang_str)
+                .map(|code| DetectedLanguage {
+                    code,
+                    probability: 1.0,
+                }),
+            None => {
+                let n_threads = self.n_threads();
+                state.pcm_to_mel(samples, n_threads)?;
+                let (id, probs) = state.lang_detect(0, n_threads)?;
+                // Decode with the detected language so whisper.cpp skips its
+                // own (identical) detection pass
+                whisper_rs::get_lang_str(id).map(|code| {
+                    params.set_language(Some(code));
+                    DetectedLanguage {
+                        code,
+                        probability: probs.get(id as usize).copied().unwrap_or(0.0),
+                    }
+                })
+            }
+        };
+        state.full(params, samples)?;
+        Ok((state, language))
+    }
+
+    fn full_params(&self) -> FullParams<'_, '_> {
+        let p = &self.params;
+        let strategy = if p.beam_size > 1 {
+            SamplingStrategy::BeamSearch {
+                beam_size: p.beam_size as i32,
+                patience: -1.0,
+            }
+        } else {
+            SamplingStrategy::Greedy { best_of: 1 }
+        };
+
+        let mut params = FullParams::new(strategy);
         params.set_print_special(false);
         params.set_print_progress(false);
         params.set_print_realtime(false);
         params.set_print_timestamps(false);
-        params.set_language(None); // Auto-detect language (supports Chinese-English mixed)
-        params.set_translate(false);
+        params.set_language(p.language.as_deref());
+        params.set_translate(p.translate);
+        if let Some(n) = p.n_threads {
+            params.set_n_threads(n as i32);
+        }
+        if let Some(prompt) = p.initial_prompt.as_deref() {
+            params.set_initial_prompt(prompt);
+        }
         // Raw mode: preserve fillers and repetitions
         params.set_suppress_blank(false);
         params.set_suppress_nst(false);
-        params.set_temperature(0.0);
+        params.set_temperature(p.temperature);
+        params.set_temperature_inc(p.temperature_inc);
+        params.set_no_speech_thold(p.no_speech_thold);
         params.set_max_len(0);
+        params
+    }
 
-        let mut state = self.context.create_state()?;
-        state.full(params, samples)?;
-
-        let num_segments = state.full_n_segments();
-        let mut text = String::new();
-        for i in 0..num_segments {
-            if let Some(segment) = state.get_segment(i) {
-                if let Ok(txt) = segment.to_str() {
-                    text.push_str(txt);
-                }
-            }
-        }
-
-        Ok(text.trim().to_string())
+    /// Transcribe audio samples (16kHz, mono, f32)
+    pub fn transcribe(&self, samples: &[f32]) -> anyhow::Result<String> {
+        Ok(self.transcribe_with_progress(samples, |_| {})?.text)
     }
 
     /// Transcribe with progress callback
     pub fn transcribe_with_progress<F>(
         &self,
         samples: &[f32],
-        callback: F,
-    ) -> anyhow::Result<String>
+        mut callback: F,
+    ) -> anyhow::Result<Transcription>
     where
-        F: Fn(&str) + Send + 'static,
+        F: FnMut(&str),
     {
-        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
-        params.set_print_special(false);
-        params.set_print_progress(false);
-        params.set_print_realtime(false);
-        params.set_print_timestamps(false);
-        params.set_language(None); // Auto-detect language (supports Chinese-English mixed)
-        params.set_translate(false);
-        // Raw mode: preserve fillers and repetitions
-        params.set_suppress_blank(false);
-        params.set_suppress_nst(false);
-        params.set_temperature(0.0);
-        params.set_max_len(0);
-
-        let mut state = self.context.create_state()?;
-        state.full(params, samples)?;
+        let (state, language) = self.run(samples, false)?;
 
         let num_segments = state.full_n_segments();
         let mut text = String::new();
@@ -90,6 +306,82 @@ impl WhisperEngine {
             }
         }
 
-        Ok(text.trim().to_string())
+        Ok(Transcription::from_text(text.trim().to_string(), language))
+    }
+
+    /// Transcribe into timed segments with token-level timestamps and probabilities
+    pub fn transcribe_detailed(&self, samples: &[f32]) -> anyhow::Result<Transcription> {
+        let (state, language) = self.run(samples, true)?;
+
+        // Ids at or above EOT are special tokens (timestamps, language, task)
+        let token_eot = self.context.token_eot();
+        let num_segments = state.full_n_segments();
+        let mut segments = Vec::with_capacity(num_segments.max(0) as usize);
+        for i in 0..num_segments {
+            let Some(segment) = state.get_segment(i) else {
+                continue;
+            };
+
+            let mut tokens = Vec::new();
+            let mut logprob_sum = 0.0f32;
+            let mut n_text_tokens = 0usize;
+            // Byte-level BPE may split one CJK character across tokens; merge
+            // them so every token carries valid UTF-8.
+            let mut pending: Option<(Vec<u8>, Token)> = None;
+            for t in 0..segment.n_tokens() {
+                let Some(token) = segment.get_token(t) else {
+                    continue;
+                };
+                let data = token.token_data();
+                if data.id >= token_eot {
+                    continue;
+                }
+                logprob_sum += data.plog;
+                n_text_tokens += 1;
+
+                let (mut bytes, mut merged) = pending.take().unwrap_or_else(|| {
+                    (
+                        Vec::new(),
+                        Token {
+                            text: String::new(),
+                            p: 1.0,
+                            t0: data.t0 * 10,
+                            t1: data.t1 * 10,
+                        },
+                    )
+                });
+                bytes.extend_from_slice(token.to_bytes()?);
+                merged.p = merged.p.min(data.p);
+                merged.t1 = data.t1 * 10;
+                match String::from_utf8(bytes) {
+                    Ok(text) => {
+                        merged.text = text;
+                        tokens.push(merged);
+                    }
+                    Err(e) => pending = Some((e.into_bytes(), merged)),
+                }
+            }
+            if let Some((bytes, mut merged)) = pending {
+                merged.text = String::from_utf8_lossy(&bytes).into_owned();
+                tokens.push(merged);
+            }
+            let avg_logprob = if n_text_tokens == 0 {
+                0.0
+            } else {
+                logprob_sum / n_text_tokens as f32
+            };
+
+            segments.push(Segment {
+                // whisper.cpp timestamps are in centiseconds
+                start_ms: segment.start_timestamp() * 10,
+                end_ms: segment.end_timestamp() * 10,
+                text: segment.to_str_lossy()?.into_owned(),
+                avg_logprob,
+                no_speech_prob: segment.no_speech_probability(),
+                tokens,
+            });
+        }
+
+        Ok(Transcription::from_segments(segments, language))
     }
 }
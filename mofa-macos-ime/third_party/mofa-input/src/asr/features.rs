@@ -0,0 +1,540 @@
+//! Kaldi-compatible acoustic features
+//!
+//! Framing, pre-emphasis, windowing, FFT and mel filterbanks follow
+//! `compute-fbank-feats` (and `torchaudio.compliance.kaldi.fbank`), so ONNX
+//! models exported from Kaldi/FunASR/sherpa pipelines see the features they
+//! were trained on. LFR stacking and CMVN cover the FunASR front end.
+
+use std::f64::consts::PI;
+use std::path::Path;
+
+/// Analysis window applied to each frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WindowType {
+    /// Kaldi default: hann raised to the power 0.85
+    Povey,
+    Hamming,
+    Hanning,
+    Rectangular,
+}
+
+/// Filterbank options; defaults match Kaldi's `compute-fbank-feats`
+#[derive(Clone, Copy, Debug)]
+pub struct FbankOptions {
+    pub sample_rate: f32,
+    pub frame_length_ms: f32,
+    pub frame_shift_ms: f32,
+    pub num_mel_bins: usize,
+    pub low_freq: f32,
+    /// Upper mel cutoff; values <= 0 are an offset from Nyquist
+    pub high_freq: f32,
+    pub preemph_coeff: f32,
+    pub remove_dc_offset: bool,
+    pub window: WindowType,
+    /// Only emit frames that fit entirely in the signal
+    pub snip_edges: bool,
+    pub round_to_power_of_two: bool,
+    /// Power spectrum if true, magnitude otherwise
+    pub use_power: bool,
+    /// Take the log of filterbank energies
+    pub use_log_fbank: bool,
+}
+
+impl Default for FbankOptions {
+    fn default() -> Self {
+        Self {
+            sample_rate: 16_000.0,
+            frame_length_ms: 25.0,
+            frame_shift_ms: 10.0,
+            num_mel_bins: 23,
+            low_freq: 20.0,
+            high_freq: 0.0,
+            preemph_coeff: 0.97,
+            remove_dc_offset: true,
+            window: WindowType::Povey,
+            snip_edges: true,
+            round_to_power_of_two: true,
+            use_power: true,
+            use_log_fbank: true,
+        }
+    }
+}
+
+impl FbankOptions {
+    /// FunASR `WavFrontend`: 80 bins with a hamming window
+    pub fn funasr() -> Self {
+        Self {
+            num_mel_bins: 80,
+            window: WindowType::Hamming,
+            ..Self::default()
+        }
+    }
+
+    pub fn frame_length(&self) -> usize {
+        (self.sample_rate * self.frame_length_ms / 1000.0) as usize
+    }
+
+    pub fn frame_shift(&self) -> usize {
+        (self.sample_rate * self.frame_shift_ms / 1000.0) as usize
+    }
+
+    pub fn padded_length(&self) -> usize {
+        let len = self.frame_length();
+        if self.round_to_power_of_two {
+            len.next_power_of_two()
+        } else {
+            len
+        }
+    }
+}
+
+/// Log mel filterbank extractor with precomputed window and filters
+pub struct Fbank {
+    opts: FbankOptions,
+    window: Vec<f32>,
+    /// Per bin: first FFT bin and triangular weights from there
+    mel_banks: Vec<(usize, Vec<f32>)>,
+}
+
+impl Fbank {
+    pub fn new(opts: FbankOptions) -> anyhow::Result<Self> {
+        let frame_length = opts.frame_length();
+        if frame_length == 0 || opts.frame_shift() == 0 {
+            return Err(anyhow::anyhow!("Invalid frame length/shift: {:?}", opts));
+        }
+        if !opts.padded_length().is_power_of_two() {
+            return Err(anyhow::anyhow!(
+                "FFT length {} must be a power of two",
+                opts.padded_length()
+            ));
+        }
+
+        let window = make_window(opts.window, frame_length);
+        let mel_banks = make_mel_banks(&opts)?;
+        Ok(Self {
+            opts,
+            window,
+            mel_banks,
+        })
+    }
+
+    pub fn options(&self) -> &FbankOptions {
+        &self.opts
+    }
+
+    /// Number of frames produced for `num_samples` input samples
+    pub fn num_frames(&self, num_samples: usize) -> usize {
+        let length = self.opts.frame_length();
+        let shift = self.opts.frame_shift();
+        if self.opts.snip_edges {
+            if num_samples < length {
+                0
+            } else {
+                1 + (num_samples - length) / shift
+            }
+        } else {
+            (num_samples + shift / 2) / shift
+        }
+    }
+
+    /// Compute `[frames, num_mel_bins]` features. Samples are used as given;
+    /// Kaldi-trained models usually expect int16 scale (multiply by 32768).
+    pub fn compute(&self, samples: &[f32]) -> Vec<Vec<f32>> {
+        let num_frames = self.num_frames(samples.len());
+        let frame_length = self.opts.frame_length();
+        let padded = self.opts.padded_length();
+
+        let mut frame = vec![0.0f32; frame_length];
+        let mut re = vec![0.0f32; padded];
+        let mut im = vec![0.0f32; padded];
+        let mut out = Vec::with_capacity(num_frames);
+        for f in 0..num_frames {
+            self.extract_frame(samples, f, &mut frame);
+
+            if self.opts.remove_dc_offset {
+                let mean = frame.iter().sum::<f32>() / frame_length as f32;
+                for v in frame.iter_mut() {
+                    *v -= mean;
+                }
+            }
+            if self.opts.preemph_coeff != 0.0 {
+                let c = self.opts.preemph_coeff;
+                for i in (1..frame_length).rev() {
+                    frame[i] -= c * frame[i - 1];
+                }
+                frame[0] -= c * frame[0];
+            }
+
+            re.fill(0.0);
+            im.fill(0.0);
+            for i in 0..frame_length {
+                re[i] = frame[i] * self.window[i];
+            }
+            fft(&mut re, &mut im);
+
+            let spectrum: Vec<f32> = (0..=padded / 2)
+                .map(|k| {
+                    let power = re[k] * re[k] + im[k] * im[k];
+                    if self.opts.use_power {
+                        power
+                    } else {
+                        power.sqrt()
+                    }
+                })
+                .collect();
+
+            let mels = self
+                .mel_banks
+                .iter()
+                .map(|(first, weights)| {
+                    let energy: f32 = weights
+                        .iter()
+                        .zip(&spectrum[*first..])
+                        .map(|(w, p)| w * p)
+                        .sum();
+                    if self.opts.use_log_fbank {
+                        energy.max(f32::EPSILON).ln()
+                    } else {
+                        energy
+                    }
+                })
+                .collect();
+            out.push(mels);
+        }
+        out
+    }
+
+    /// Copy frame `f` into `dst`, reflecting at the edges when not snipping
+    fn extract_frame(&self, samples: &[f32], f: usize, dst: &mut [f32]) {
+        let length = self.opts.frame_length() as i64;
+        let shift = self.opts.frame_shift() as i64;
+        let start = if self.opts.snip_edges {
+            f as i64 * shift
+        } else {
+            f as i64 * shift + shift / 2 - length / 2
+        };
+
+        let n = samples.len() as i64;
+        for (i, v) in dst.iter_mut().enumerate() {
+            let mut idx = start + i as i64;
+            // Kaldi mirrors out-of-range indices back into the signal
+            while idx < 0 || idx >= n {
+                idx = if idx < 0 { -idx - 1 } else { 2 * n - 1 - idx };
+            }
+            *v = samples[idx as usize];
+        }
+    }
+}
+
+fn make_window(kind: WindowType, len: usize) -> Vec<f32> {
+    let a = 2.0 * PI / (len.max(2) - 1) as f64;
+    (0..len)
+        .map(|i| {
+            let x = i as f64;
+            let w = match kind {
+                WindowType::Povey => (0.5 - 0.5 * (a * x).cos()).powf(0.85),
+                WindowType::Hamming => 0.54 - 0.46 * (a * x).cos(),
+                WindowType::Hanning => 0.5 - 0.5 * (a * x).cos(),
+                WindowType::Rectangular => 1.0,
+            };
+            w as f32
+        })
+        .collect()
+}
+
+/// Kaldi mel scale
+pub fn mel_scale(freq: f32) -> f32 {
+    1127.0 * (1.0 + freq / 700.0).ln()
+}
+
+fn make_mel_banks(opts: &FbankOptions) -> anyhow::Result<Vec<(usize, Vec<f32>)>> {
+    let nyquist = opts.sample_rate / 2.0;
+    let high_freq = if opts.high_freq <= 0.0 {
+        nyquist + opts.high_freq
+    } else {
+        opts.high_freq
+    };
+    if opts.low_freq < 0.0 || high_freq > nyquist || opts.low_freq >= high_freq {
+        return Err(anyhow::anyhow!(
+            "Invalid mel range: low_freq {} high_freq {}",
+            opts.low_freq,
+            high_freq
+        ));
+    }
+
+    let padded = opts.padded_length();
+    let num_fft_bins = padded / 2;
+    let bin_width = opts.sample_rate / padded as f32;
+    let mel_low = mel_scale(opts.low_freq);
+    let mel_high = mel_scale(high_freq);
+    let mel_delta = (mel_high - mel_low) / (opts.num_mel_bins + 1) as f32;
+
+    Ok((0..opts.num_mel_bins)
+        .map(|b| {
+            let left = mel_low + b as f32 * mel_delta;
+            let center = left + mel_delta;
+            let right = center + mel_delta;
+
+            let weights: Vec<f32> = (0..num_fft_bins)
+                .map(|k| {
+                    let mel = mel_scale(bin_width * k as f32);
+                    if mel > left && mel < right {
+                        if mel <= center {
+                            (mel - left) / (center - left)
+                        } else {
+                            (right - mel) / (right - center)
+                        }
+                    } else {
+                        0.0
+                    }
+                })
+                .collect();
+
+            let first = weights.iter().position(|&w| w > 0.0).unwrap_or(0);
+            let last = weights.iter().rposition(|&w| w > 0.0).unwrap_or(0);
+            (first, weights[first..=last.max(first)].to_vec())
+        })
+        .collect())
+}
+
+/// Low frame rate stacking: `lfr_m` frames every `lfr_n`, padded with the
+/// first frame on the left and the last frame on the right (FunASR `apply_lfr`)
+pub fn apply_lfr(frames: &[Vec<f32>], lfr_m: usize, lfr_n: usize) -> Vec<Vec<f32>> {
+    if frames.is_empty() || lfr_m == 0 || lfr_n == 0 {
+        return Vec::new();
+    }
+    let left_pad = (lfr_m - 1) / 2;
+    let padded: Vec<&Vec<f32>> = std::iter::repeat_n(&frames[0], left_pad)
+        .chain(frames.iter())
+        .collect();
+    let t_lfr = frames.len().div_ceil(lfr_n);
+
+    (0..t_lfr)
+        .map(|i| {
+            let mut out = Vec::with_capacity(frames[0].len() * lfr_m);
+            for j in 0..lfr_m {
+                let idx = (i * lfr_n + j).min(padded.len() - 1);
+                out.extend_from_slice(padded[idx]);
+            }
+            out
+        })
+        .collect()
+}
+
+/// Global CMVN: `(x + shift) * scale` per dimension
+#[derive(Clone, Debug)]
+pub struct Cmvn {
+    pub shift: Vec<f32>,
+    pub scale: Vec<f32>,
+}
+
+impl Cmvn {
+    /// Parse a Kaldi nnet `am.mvn` with `<AddShift>` and `<Rescale>` components
+    pub fn from_kaldi_mvn(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read CMVN {:?}: {}", path, e))?;
+
+        let mut shift = Vec::new();
+        let mut scale = Vec::new();
+        let mut lines = content.lines();
+        while let Some(line) = lines.next() {
+            let target = if line.contains("<AddShift>") {
+                &mut shift
+            } else if line.contains("<Rescale>") {
+                &mut scale
+            } else {
+                continue;
+            };
+            if let Some(values) = lines.next() {
+                *target = parse_bracketed(values);
+            }
+        }
+
+        if shift.is_empty() || shift.len() != scale.len() {
+            return Err(anyhow::anyhow!("Invalid CMVN file: {:?}", path));
+        }
+        Ok(Self { shift, scale })
+    }
+
+    pub fn dim(&self) -> usize {
+        self.shift.len()
+    }
+
+    pub fn apply(&self, frames: &mut [Vec<f32>]) {
+        for frame in frames {
+            for ((v, shift), scale) in frame.iter_mut().zip(&self.shift).zip(&self.scale) {
+                *v = (*v + shift) * scale;
+            }
+        }
+    }
+}
+
+fn parse_bracketed(line: &str) -> Vec<f32> {
+    let (Some(start), Some(end)) = (line.find('['), line.rfind(']')) else {
+        return Vec::new();
+    };
+    line[start + 1..end]
+        .split_whitespace()
+        .filter_map(|v| v.parse().ok())
+        .collect()
+}
+
+/// In-place iterative radix-2 FFT; length must be a power of two
+pub fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let ang = -2.0 * PI / len as f64;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (ang * k as f64).sin_cos();
+                let (wr, wi) = (cos as f32, sin as f32);
+                let a = start + k;
+                let b = a + len / 2;
+                let tr = re[b] * wr - im[b] * wi;
+                let ti = re[b] * wi + im[b] * wr;
+                re[b] = re[a] - tr;
+                im[b] = im[a] - ti;
+                re[a] += tr;
+                im[a] += ti;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two tones plus LCG noise at int16 scale, 0.1 s at 16kHz
+    fn test_signal() -> Vec<f32> {
+        let mut state: u64 = 12345;
+        (0..1600)
+            .map(|i| {
+                state = (state * 1_103_515_245 + 12_345) % (1 << 31);
+                let noise = state as f64 / (1u64 << 31) as f64 - 0.5;
+                let t = i as f64 / 16_000.0;
+                (1000.0 * (2.0 * PI * 440.0 * t).sin()
+                    + 500.0 * (2.0 * PI * 1234.0 * t).sin()
+                    + 200.0 * noise) as f32
+            })
+            .collect()
+    }
+
+    /// `(frame, bin, value)` from a float64 port of
+    /// `torchaudio.compliance.kaldi.fbank` on the same signal
+    fn assert_matches(feats: &[Vec<f32>], expected: &[(usize, usize, f32)]) {
+        for &(frame, bin, value) in expected {
+            let got = feats[frame][bin];
+            assert!(
+                (got - value).abs() < 2e-3,
+                "frame {frame} bin {bin}: {got} != {value}"
+            );
+        }
+    }
+
+    #[test]
+    fn fbank_matches_kaldi_reference() {
+        let fbank = Fbank::new(FbankOptions::default()).unwrap();
+        let feats = fbank.compute(&test_signal());
+        assert_eq!(feats.len(), 8);
+        assert!(feats.iter().all(|f| f.len() == 23));
+        assert_matches(
+            &feats,
+            &[
+                (0, 0, 7.3542),
+                (0, 10, 13.012),
+                (0, 11, 14.06533),
+                (0, 22, 18.10816),
+                (7, 0, 7.97934),
+                (7, 10, 13.93637),
+                (7, 11, 14.9236),
+                (7, 22, 17.84024),
+            ],
+        );
+    }
+
+    #[test]
+    fn funasr_fbank_matches_reference() {
+        let fbank = Fbank::new(FbankOptions::funasr()).unwrap();
+        let feats = fbank.compute(&test_signal());
+        assert_eq!(feats.len(), 8);
+        assert!(feats.iter().all(|f| f.len() == 80));
+        assert_matches(
+            &feats,
+            &[
+                (0, 0, 8.04753),
+                (0, 10, 9.20315),
+                (0, 40, 13.32526),
+                (0, 79, 16.8807),
+                (7, 0, 7.97395),
+                (7, 10, 10.0131),
+                (7, 40, 14.12584),
+                (7, 79, 16.49196),
+            ],
+        );
+    }
+
+    #[test]
+    fn num_frames_without_snip_edges() {
+        let fbank = Fbank::new(FbankOptions {
+            snip_edges: false,
+            ..FbankOptions::default()
+        })
+        .unwrap();
+        assert_eq!(fbank.num_frames(1600), 10);
+        assert_eq!(fbank.compute(&test_signal()).len(), 10);
+    }
+
+    #[test]
+    fn lfr_pads_like_funasr() {
+        let frames: Vec<Vec<f32>> = (0..10).map(|t| vec![t as f32]).collect();
+        let lfr = apply_lfr(&frames, 7, 6);
+        assert_eq!(
+            lfr,
+            vec![
+                vec![0.0, 0.0, 0.0, 0.0, 1.0, 2.0, 3.0],
+                vec![3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0],
+            ]
+        );
+        // The last frame repeats when the signal runs out
+        let lfr = apply_lfr(&frames[..8], 7, 6);
+        assert_eq!(lfr[1], vec![3.0, 4.0, 5.0, 6.0, 7.0, 7.0, 7.0]);
+        assert!(apply_lfr(&[], 7, 6).is_empty());
+    }
+
+    #[test]
+    fn cmvn_parses_kaldi_mvn() {
+        let path = std::env::temp_dir().join(format!("mofa-am-{}.mvn", std::process::id()));
+        std::fs::write(
+            &path,
+            "<Nnet>\n<Splice> 2 2\n[ 0 ]\n<AddShift> 2 2\n\
+             <LearnRateCoef> 0 [ -1.5 2 ]\n<Rescale> 2 2\n\
+             <LearnRateCoef> 0 [ 0.5 -4 ]\n</Nnet>\n",
+        )
+        .unwrap();
+        let cmvn = Cmvn::from_kaldi_mvn(&path);
+        std::fs::remove_file(&path).unwrap();
+        let cmvn = cmvn.unwrap();
+        assert_eq!(cmvn.dim(), 2);
+
+        let mut frames = vec![vec![1.5, 0.0], vec![3.5, -2.25]];
+        cmvn.apply(&mut frames);
+        assert_eq!(frames, vec![vec![0.0, -8.0], vec![1.0, 1.0]]);
+    }
+}
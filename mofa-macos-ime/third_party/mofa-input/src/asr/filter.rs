@@ -0,0 +1,250 @@
+//! Post-ASR hallucination and no-speech filtering
+//!
+//! Whisper tends to emit phantom text ("谢谢观看", subtitle credits, looping
+//! phrases) on silence or noise. Segments are dropped using the same signals
+//! whisper's own fallback uses, plus a phrase blocklist.
+
+use std::io::Write;
+use std::path::Path;
+
+use super::transcript::Segment;
+
+/// Phrases Whisper commonly hallucinates from its subtitle training data
+const DEFAULT_BLOCKLIST: &[&str] = &[
+    "谢谢观看",
+    "谢谢大家观看",
+    "感谢观看",
+    "请不吝点赞",
+    "订阅转发",
+    "点赞订阅",
+    "字幕由",
+    "字幕志愿者",
+    "字幕提供",
+    "明镜与点点栏目",
+    "中文字幕",
+    "thanks for watching",
+    "thank you for watching",
+    "please subscribe",
+    "subtitles by",
+    "amara.org",
+];
+
+/// Why a segment was dropped
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DropReason {
+    /// High no-speech probability with low confidence
+    NoSpeech,
+    /// Highly repetitive text (compression ratio above threshold)
+    Repetitive,
+    /// Matches a blocklisted phrase
+    Blocklisted,
+}
+
+/// Segment filter; thresholds default to whisper.cpp's fallback values
+#[derive(Clone, Debug)]
+pub struct HallucinationFilter {
+    pub no_speech_thold: f32,
+    pub logprob_thold: f32,
+    pub compression_ratio_thold: f32,
+    /// Normalized (lowercase, no punctuation/whitespace) phrases
+    blocklist: Vec<String>,
+}
+
+impl Default for HallucinationFilter {
+    fn default() -> Self {
+        Self {
+            no_speech_thold: 0.6,
+            logprob_thold: -1.0,
+            compression_ratio_thold: 2.4,
+            blocklist: DEFAULT_BLOCKLIST.iter().map(|p| normalize(p)).collect(),
+        }
+    }
+}
+
+impl HallucinationFilter {
+    /// Add a phrase to the blocklist
+    pub fn block(&mut self, phrase: &str) {
+        let phrase = normalize(phrase);
+        if !phrase.is_empty() && !self.blocklist.contains(&phrase) {
+            self.blocklist.push(phrase);
+        }
+    }
+
+    /// Add phrases from a file, one per line; `#` starts a comment
+    pub fn load_blocklist(&mut self, path: &Path) -> anyhow::Result<()> {
+        let content = std::fs::read_to_string(path)?;
+        for line in content.lines() {
+            let line = line.trim();
+            if !line.is_empty() && !line.starts_with('#') {
+                self.block(line);
+            }
+        }
+        Ok(())
+    }
+
+    /// Reason to drop `segment`, if any
+    pub fn check(&self, segment: &Segment) -> Option<DropReason> {
+        if segment.no_speech_prob > self.no_speech_thold && segment.avg_logprob < self.logprob_thold
+        {
+            return Some(DropReason::NoSpeech);
+        }
+        if self.is_blocklisted(&segment.text) {
+            return Some(DropReason::Blocklisted);
+        }
+        if compression_ratio(&segment.text) > self.compression_ratio_thold {
+            return Some(DropReason::Repetitive);
+        }
+        None
+    }
+
+    /// Keep only segments that pass `check`
+    pub fn filter(&self, segments: Vec<Segment>) -> Vec<Segment> {
+        segments
+            .into_iter()
+            .filter(|s| self.check(s).is_none())
+            .collect()
+    }
+
+    /// Text-only variant for backends without segment confidences
+    pub fn filter_text(&self, text: &str) -> String {
+        if self.is_blocklisted(text) || compression_ratio(text) > self.compression_ratio_thold {
+            String::new()
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// Whether blocklisted phrases make up most of the text, so dictation
+    /// that merely mentions one is kept
+    fn is_blocklisted(&self, text: &str) -> bool {
+        let text: Vec<char> = normalize(text).chars().collect();
+        if text.is_empty() {
+            return false;
+        }
+        let mut covered = vec![false; text.len()];
+        for phrase in &self.blocklist {
+            let phrase: Vec<char> = phrase.chars().collect();
+            for start in 0..text.len().saturating_sub(phrase.len() - 1) {
+                if text[start..start + phrase.len()] == phrase[..] {
+                    covered[start..start + phrase.len()].fill(true);
+                }
+            }
+        }
+        let covered = covered.iter().filter(|&&c| c).count();
+        covered * 3 >= text.len() * 2
+    }
+}
+
+/// Whisper's repetition measure: UTF-8 length over zlib-compressed length
+pub fn compression_ratio(text: &str) -> f32 {
+    let bytes = text.trim().as_bytes();
+    if bytes.is_empty() {
+        return 0.0;
+    }
+    let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+    let compressed = encoder
+        .write_all(bytes)
+        .and_then(|_| encoder.finish())
+        .map(|v| v.len())
+        .unwrap_or(bytes.len());
+    bytes.len() as f32 / compressed.max(1) as f32
+}
+
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(text: &str, avg_logprob: f32, no_speech_prob: f32) -> Segment {
+        Segment {
+            start_ms: 0,
+            end_ms: 1000,
+            text: text.to_string(),
+            avg_logprob,
+            no_speech_prob,
+            tokens: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn blocklisted_segments_are_dropped() {
+        let filter = HallucinationFilter::default();
+        for text in [
+            "谢谢观看",
+            "谢谢观看！",
+            " Thanks for watching! ",
+            "字幕由Amara.org社区提供",
+            "谢谢观看，再见",
+        ] {
+            assert_eq!(
+                filter.check(&segment(text, -0.2, 0.1)),
+                Some(DropReason::Blocklisted),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn dictation_mentioning_a_blocked_phrase_is_kept() {
+        let filter = HallucinationFilter::default();
+        for text in [
+            "我先说谢谢观看的各位",
+            "the thanks for watching slide is done",
+        ] {
+            assert_eq!(filter.check(&segment(text, -0.2, 0.1)), None, "{text}");
+            assert_eq!(filter.filter_text(text), text);
+        }
+    }
+
+    #[test]
+    fn custom_phrases_are_normalized() {
+        let mut filter = HallucinationFilter::default();
+        filter.block("  Like and SHARE! ");
+        filter.block("");
+        assert_eq!(filter.filter_text("like and share"), "");
+        assert_eq!(filter.filter_text("不要忘了"), "不要忘了");
+    }
+
+    #[test]
+    fn repetitive_text_is_dropped() {
+        let filter = HallucinationFilter::default();
+        let looping = "我们今天来讲一下".repeat(12);
+        assert!(compression_ratio(&looping) > filter.compression_ratio_thold);
+        assert_eq!(
+            filter.check(&segment(&looping, -0.2, 0.1)),
+            Some(DropReason::Repetitive)
+        );
+        assert_eq!(filter.filter_text(&looping), "");
+
+        let normal = "今天下午三点在二楼会议室讨论下个季度的预算安排";
+        assert!(compression_ratio(normal) < filter.compression_ratio_thold);
+        assert_eq!(filter.check(&segment(normal, -0.2, 0.1)), None);
+        assert_eq!(compression_ratio("  "), 0.0);
+    }
+
+    #[test]
+    fn no_speech_needs_low_confidence_too() {
+        let filter = HallucinationFilter::default();
+        assert_eq!(
+            filter.check(&segment("嗯", -1.5, 0.9)),
+            Some(DropReason::NoSpeech)
+        );
+        // Confident text is kept even when the no-speech probability is high
+        assert_eq!(filter.check(&segment("好的", -0.3, 0.9)), None);
+        assert_eq!(filter.check(&segment("好的", -1.5, 0.3)), None);
+
+        let kept = filter.filter(vec![
+            segment("好的", -0.3, 0.1),
+            segment("嗯", -1.5, 0.9),
+            segment("谢谢观看", -0.3, 0.1),
+        ]);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].text, "好的");
+    }
+}
@@ -2,11 +2,16 @@
 //! Paraformer model - preserves fillers and repetitions
 
 use std::path::Path;
-use ndarray::Array2;
+use std::sync::Mutex;
+
+use ort::session::Session;
+use ort::value::Tensor;
+
+use super::frontend::WavFrontend;
 
 pub struct FunAsrEngine {
-    // ONNX session - simplified for now
-    model_path: std::path::PathBuf,
+    session: Mutex<Session>,
+    frontend: WavFrontend,
     vocab: Vec<String>,
 }
 
@@ -14,6 +19,7 @@ impl FunAsrEngine {
     pub fn new(model_dir: &Path) -> anyhow::Result<Self> {
         let model_path = model_dir.join("model.onnx");
         let vocab_path = model_dir.join("tokens.txt");
+        let mvn_path = model_dir.join("am.mvn");
 
         if !model_path.exists() {
             return Err(anyhow::anyhow!("Model file not found: {:?}", model_path));
@@ -21,64 +27,111 @@ impl FunAsrEngine {
 
         // Load vocabulary
         let vocab = Self::load_vocab(&vocab_path)?;
+        let frontend = WavFrontend::new(&mvn_path)?;
 
-        // TODO: Initialize ONNX session
-        // For now, we'll return a placeholder
+        let session = Session::builder()
+            .and_then(|mut b| b.commit_from_file(&model_path))
+            .map_err(|e| anyhow::anyhow!("Failed to load ONNX model {:?}: {}", model_path, e))?;
 
         Ok(Self {
-            model_path,
+            session: Mutex::new(session),
+            frontend,
             vocab,
         })
     }
 
+    /// One token per line; sherpa-style "token id" lines are also accepted
     fn load_vocab(path: &Path) -> anyhow::Result<Vec<String>> {
         let content = std::fs::read_to_string(path)?;
         let vocab: Vec<String> = content
             .lines()
-            .map(|line| line.trim().to_string())
-            .filter(|s| !s.is_empty())
+            .filter_map(|line| line.split_whitespace().next())
+            .map(|s| s.to_string())
             .collect();
         Ok(vocab)
     }
 
     /// Transcribe audio samples (16kHz, mono, f32)
     /// FunASR preserves um/ah fillers and repetitions
-    pub fn transcribe(&self, _samples: &[f32]) -> anyhow::Result<String> {
-        // TODO: Implement actual ONNX inference
-        // For now, return a placeholder message
-        Ok("[FunASR 模型加载中，请稍后...]".to_string())
-    }
+    pub fn transcribe(&self, samples: &[f32]) -> anyhow::Result<String> {
+        let feats = self.frontend.compute(samples);
+        if feats.is_empty() {
+            return Ok(String::new());
+        }
 
-    /// Preprocess audio: normalize and create input tensor
-    fn preprocess(&self, samples: &[f32]) -> Array2<f32> {
-        // Normalize to [-1, 1]
-        let max_val = samples.iter().map(|x| x.abs()).fold(0.0f32, f32::max);
-        let normalized: Vec<f32> = if max_val > 0.0 {
-            samples.iter().map(|x| x / max_val).collect()
-        } else {
-            samples.to_vec()
-        };
+        let num_frames = feats.len();
+        let dim = self.frontend.dim();
+        let speech = Tensor::from_array((
+            [1usize, num_frames, dim],
+            feats.into_iter().flatten().collect::<Vec<f32>>(),
+        ))?;
+        let speech_lengths = Tensor::from_array(([1usize], vec![num_frames as i32]))?;
 
-        // Create 2D array (batch_size=1, seq_len)
-        Array2::from_shape_vec((1, normalized.len()), normalized)
-            .unwrap_or_else(|_| Array2::zeros((1, 1)))
-    }
-}
+        let ids: Vec<usize> = {
+            let mut session = self.session.lock().unwrap();
+            let outputs = session.run(ort::inputs![
+                "speech" => speech,
+                "speech_lengths" => speech_lengths,
+            ])?;
+            let (shape, logits) = outputs["logits"].try_extract_tensor::<f32>()?;
+            if shape.len() != 3 {
+                return Err(anyhow::anyhow!("Unexpected logits shape: {:?}", shape));
+            }
+            let vocab_size = shape[2] as usize;
+            // The predictor's token count bounds the valid decoder positions
+            let token_num = match outputs.get("token_num") {
+                Some(v) => v
+                    .try_extract_tensor::<i32>()
+                    .map(|(_, n)| n.first().map(|&n| n as usize))
+                    .or_else(|_| {
+                        v.try_extract_tensor::<i64>()
+                            .map(|(_, n)| n.first().map(|&n| n as usize))
+                    })?,
+                None => None,
+            };
 
-/// Thread-safe ASR session wrapper for FunASR
-#[derive(Clone)]
-pub struct FunAsrSession {
-    // Placeholder - would contain Arc<Mutex<FunAsrEngine>> in full implementation
-}
+            logits
+                .chunks(vocab_size)
+                .take(token_num.unwrap_or(usize::MAX))
+                .map(|row| {
+                    row.iter()
+                        .enumerate()
+                        .max_by(|a, b| a.1.total_cmp(b.1))
+                        .map(|(i, _)| i)
+                        .unwrap_or(0)
+                })
+                .collect()
+        };
 
-impl FunAsrSession {
-    pub fn new(_model_dir: &Path) -> anyhow::Result<Self> {
-        // Placeholder implementation
-        Ok(Self {})
+        Ok(self.decode(&ids))
     }
 
-    pub fn transcribe(&self, _samples: &[f32]) -> anyhow::Result<String> {
-        // Placeholder - FunASR would actually preserve fillers here
-        Ok("[FunASR 暂为占位实现，需完善 ONNX Runtime 集成]".to_string())
+    /// Map token ids to text, joining "@@" BPE pieces and spacing English words
+    fn decode(&self, ids: &[usize]) -> String {
+        let mut text = String::new();
+        let mut prev_ascii = false;
+        let mut in_word = false;
+        for &id in ids {
+            let Some(token) = self.vocab.get(id) else {
+                continue;
+            };
+            // <blank>, <s>, </s>, <unk>
+            if token.starts_with('<') && token.ends_with('>') {
+                continue;
+            }
+
+            let (piece, continues) = match token.strip_suffix("@@") {
+                Some(piece) => (piece, true),
+                None => (token.as_str(), false),
+            };
+            let is_ascii = piece.chars().all(|c| c.is_ascii_alphanumeric() || c == '\'');
+            if is_ascii && prev_ascii && !in_word {
+                text.push(' ');
+            }
+            text.push_str(piece);
+            prev_ascii = is_ascii;
+            in_word = continues;
+        }
+        text
     }
 }
@@ -0,0 +1,45 @@
+//! Paraformer front end: Kaldi fbank, LFR stacking and CMVN
+//! Mirrors FunASR's `WavFrontend` (80 mel bins, hamming window, LFR 7/6)
+
+use std::path::Path;
+
+use crate::asr::features::{apply_lfr, Cmvn, Fbank, FbankOptions};
+
+const LFR_M: usize = 7;
+const LFR_N: usize = 6;
+
+/// Feature extractor producing `[frames, 560]` Paraformer inputs
+pub struct WavFrontend {
+    fbank: Fbank,
+    cmvn: Cmvn,
+}
+
+impl WavFrontend {
+    pub fn new(mvn_path: &Path) -> anyhow::Result<Self> {
+        let fbank = Fbank::new(FbankOptions::funasr())?;
+        let cmvn = Cmvn::from_kaldi_mvn(mvn_path)?;
+        if cmvn.dim() != fbank.options().num_mel_bins * LFR_M {
+            return Err(anyhow::anyhow!(
+                "Unexpected CMVN dimension {} in {:?}",
+                cmvn.dim(),
+                mvn_path
+            ));
+        }
+        Ok(Self { fbank, cmvn })
+    }
+
+    /// Feature dimension after LFR stacking
+    pub fn dim(&self) -> usize {
+        self.cmvn.dim()
+    }
+
+    /// Compute features for 16kHz mono samples in [-1, 1]
+    pub fn compute(&self, samples: &[f32]) -> Vec<Vec<f32>> {
+        // FunASR feeds int16-scaled samples to kaldi fbank
+        let scaled: Vec<f32> = samples.iter().map(|s| s * 32768.0).collect();
+        let fbank = self.fbank.compute(&scaled);
+        let mut feats = apply_lfr(&fbank, LFR_M, LFR_N);
+        self.cmvn.apply(&mut feats);
+        feats
+    }
+}
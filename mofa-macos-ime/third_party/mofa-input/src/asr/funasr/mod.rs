@@ -2,6 +2,7 @@
 //! FunASR preserves um/ah fillers and repetitions better than Whisper
 
 pub mod engine;
+pub mod frontend;
 pub mod model;
 
 pub use engine::FunAsrEngine;
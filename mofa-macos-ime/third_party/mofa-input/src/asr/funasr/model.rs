@@ -7,29 +7,24 @@ use std::path::PathBuf;
 pub enum FunAsrModelSize {
     /// Small model - fast, ~100MB
     Small,
-    /// Large model - better accuracy, ~300MB
-    Large,
 }
 
 impl FunAsrModelSize {
     pub fn name(&self) -> &'static str {
         match self {
             FunAsrModelSize::Small => "Paraformer-Small",
-            FunAsrModelSize::Large => "Paraformer-Large",
         }
     }
 
     pub fn description(&self) -> &'static str {
         match self {
             FunAsrModelSize::Small => "快速，保留语气词 (~100MB)",
-            FunAsrModelSize::Large => "精确，保留语气词 (~300MB)",
         }
     }
 
     pub fn size_mb(&self) -> u64 {
         match self {
             FunAsrModelSize::Small => 100,
-            FunAsrModelSize::Large => 300,
         }
     }
 
@@ -40,7 +35,6 @@ impl FunAsrModelSize {
 
         match self {
             FunAsrModelSize::Small => base.join("funasr-small"),
-            FunAsrModelSize::Large => base.join("funasr-large"),
         }
     }
 
@@ -53,13 +47,17 @@ impl FunAsrModelSize {
         self.base_dir().join("tokens.txt")
     }
 
+    pub fn mvn_path(&self) -> PathBuf {
+        self.base_dir().join("am.mvn")
+    }
+
     pub fn config_path(&self) -> PathBuf {
         self.base_dir().join("config.yaml")
     }
 
     /// Check if model files exist
     pub fn is_available(&self) -> bool {
-        self.model_path().exists() && self.vocab_path().exists()
+        self.model_path().exists() && self.vocab_path().exists() && self.mvn_path().exists()
     }
 
     /// Model download URLs (from HuggingFace)
@@ -67,19 +65,23 @@ impl FunAsrModelSize {
         // Using Paraformer-zh from FunASR
         match self {
             FunAsrModelSize::Small => "https://huggingface.co/funasr/paraformer-zh/resolve/main/model.onnx",
-            FunAsrModelSize::Large => "https://huggingface.co/funasr/paraformer-zh-streaming/resolve/main/model.onnx",
         }
     }
 
     pub fn vocab_url(&self) -> &'static str {
         match self {
             FunAsrModelSize::Small => "https://huggingface.co/funasr/paraformer-zh/resolve/main/tokens.txt",
-            FunAsrModelSize::Large => "https://huggingface.co/funasr/paraformer-zh-streaming/resolve/main/tokens.txt",
         }
     }
 
-    pub fn all() -> [FunAsrModelSize; 2] {
-        [FunAsrModelSize::Small, FunAsrModelSize::Large]
+    pub fn mvn_url(&self) -> &'static str {
+        match self {
+            FunAsrModelSize::Small => "https://huggingface.co/funasr/paraformer-zh/resolve/main/am.mvn",
+        }
+    }
+
+    pub fn all() -> [FunAsrModelSize; 1] {
+        [FunAsrModelSize::Small]
     }
 }
 
@@ -88,5 +90,6 @@ pub fn get_model_files(model: FunAsrModelSize) -> Vec<(String, PathBuf, &'static
     vec![
         ("模型".to_string(), model.model_path(), model.model_url()),
         ("词表".to_string(), model.vocab_path(), model.vocab_url()),
+        ("特征归一化".to_string(), model.mvn_path(), model.mvn_url()),
     ]
 }
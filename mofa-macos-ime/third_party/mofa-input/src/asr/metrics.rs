@@ -0,0 +1,207 @@
+//! Recognition accuracy metrics
+//!
+//! CER aligns characters and WER aligns words after lowercasing and dropping
+//! punctuation. In WER each CJK character counts as a word, so mixed
+//! Chinese-English references still score sensibly.
+
+/// Alignment operation for one position
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EditOp {
+    Hit,
+    Substitution,
+    Deletion,
+    Insertion,
+}
+
+/// One aligned position; `reference` is empty for insertions and
+/// `hypothesis` is empty for deletions
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlignedUnit {
+    pub op: EditOp,
+    pub reference: String,
+    pub hypothesis: String,
+}
+
+/// Minimum edit alignment between a reference and a hypothesis
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Alignment {
+    pub hits: usize,
+    pub substitutions: usize,
+    pub deletions: usize,
+    pub insertions: usize,
+    pub units: Vec<AlignedUnit>,
+}
+
+impl Alignment {
+    /// Number of reference units
+    pub fn reference_len(&self) -> usize {
+        self.hits + self.substitutions + self.deletions
+    }
+
+    pub fn errors(&self) -> usize {
+        self.substitutions + self.deletions + self.insertions
+    }
+
+    /// (S + D + I) / N; an empty reference scores 1.0 if anything was inserted
+    pub fn error_rate(&self) -> f64 {
+        match self.reference_len() {
+            0 if self.insertions > 0 => 1.0,
+            0 => 0.0,
+            n => self.errors() as f64 / n as f64,
+        }
+    }
+
+    /// Inline diff: `[ref→hyp]` substitution, `[-ref]` deletion, `[+hyp]`
+    /// insertion; units are joined with `sep`
+    pub fn diff(&self, sep: &str) -> String {
+        let pieces: Vec<String> = self
+            .units
+            .iter()
+            .map(|u| match u.op {
+                EditOp::Hit => u.reference.clone(),
+                EditOp::Substitution => format!("[{}→{}]", u.reference, u.hypothesis),
+                EditOp::Deletion => format!("[-{}]", u.reference),
+                EditOp::Insertion => format!("[+{}]", u.hypothesis),
+            })
+            .collect();
+        pieces.join(sep)
+    }
+}
+
+/// Character-level alignment
+pub fn cer(reference: &str, hypothesis: &str) -> Alignment {
+    align(&char_units(reference), &char_units(hypothesis))
+}
+
+/// Word-level alignment
+pub fn wer(reference: &str, hypothesis: &str) -> Alignment {
+    align(&word_units(reference), &word_units(hypothesis))
+}
+
+/// Lowercased characters without whitespace or punctuation
+pub fn char_units(text: &str) -> Vec<String> {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .map(String::from)
+        .collect()
+}
+
+/// Lowercased words; apostrophes stay inside words and every CJK character
+/// is its own word
+pub fn word_units(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for c in text.chars() {
+        if is_cjk(c) {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            words.push(c.to_string());
+        } else if c.is_alphanumeric() || (c == '\'' && !word.is_empty()) {
+            word.extend(c.to_lowercase());
+        } else if !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    for word in &mut words {
+        while word.ends_with('\'') {
+            word.pop();
+        }
+    }
+    words.retain(|w| !w.is_empty());
+    words
+}
+
+/// Whether CER is the meaningful metric for `text` (it is mostly CJK)
+pub fn prefers_cer(text: &str) -> bool {
+    let cjk = text.chars().filter(|&c| is_cjk(c)).count();
+    let letters = text.chars().filter(|c| c.is_alphanumeric()).count();
+    cjk * 2 >= letters && cjk > 0
+}
+
+/// Minimum edit distance alignment; among equally short alignments the one
+/// with the most hits wins, so diffs show `[-很]` rather than a chain of
+/// shifted substitutions
+pub fn align(reference: &[String], hypothesis: &[String]) -> Alignment {
+    let (n, m) = (reference.len(), hypothesis.len());
+    let width = m + 1;
+    // (edits, misses) per cell, compared lexicographically; a miss is any
+    // reference unit that is not a hit
+    let mut cost = vec![(0u32, 0u32); (n + 1) * width];
+    for i in 0..=n {
+        cost[i * width] = (i as u32, i as u32);
+    }
+    for (j, cell) in cost[..width].iter_mut().enumerate() {
+        *cell = (j as u32, 0);
+    }
+    let step = |(edits, misses): (u32, u32), op: EditOp| match op {
+        EditOp::Hit => (edits, misses),
+        EditOp::Substitution | EditOp::Deletion => (edits + 1, misses + 1),
+        EditOp::Insertion => (edits + 1, misses),
+    };
+    let diagonal_op = |i: usize, j: usize| {
+        if reference[i - 1] == hypothesis[j - 1] {
+            EditOp::Hit
+        } else {
+            EditOp::Substitution
+        }
+    };
+    for i in 1..=n {
+        for j in 1..=m {
+            let diagonal = step(cost[(i - 1) * width + j - 1], diagonal_op(i, j));
+            let deletion = step(cost[(i - 1) * width + j], EditOp::Deletion);
+            let insertion = step(cost[i * width + j - 1], EditOp::Insertion);
+            cost[i * width + j] = diagonal.min(deletion).min(insertion);
+        }
+    }
+
+    let mut alignment = Alignment::default();
+    let (mut i, mut j) = (n, m);
+    while i > 0 || j > 0 {
+        let here = cost[i * width + j];
+        let op = if i > 0 && j > 0 && step(cost[(i - 1) * width + j - 1], diagonal_op(i, j)) == here
+        {
+            diagonal_op(i, j)
+        } else if i > 0 && step(cost[(i - 1) * width + j], EditOp::Deletion) == here {
+            EditOp::Deletion
+        } else {
+            EditOp::Insertion
+        };
+        let (reference, hypothesis) = match op {
+            EditOp::Hit | EditOp::Substitution => {
+                i -= 1;
+                j -= 1;
+                (reference[i].clone(), hypothesis[j].clone())
+            }
+            EditOp::Deletion => {
+                i -= 1;
+                (reference[i].clone(), String::new())
+            }
+            EditOp::Insertion => {
+                j -= 1;
+                (String::new(), hypothesis[j].clone())
+            }
+        };
+        match op {
+            EditOp::Hit => alignment.hits += 1,
+            EditOp::Substitution => alignment.substitutions += 1,
+            EditOp::Deletion => alignment.deletions += 1,
+            EditOp::Insertion => alignment.insertions += 1,
+        }
+        alignment.units.push(AlignedUnit {
+            op,
+            reference,
+            hypothesis,
+        });
+    }
+    alignment.units.reverse();
+    alignment
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c, '\u{3040}'..='\u{30FF}' | '\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}' | '\u{AC00}'..='\u{D7AF}' | '\u{F900}'..='\u{FAFF}')
+}
@@ -2,23 +2,45 @@
 //! Supports both Whisper and FunASR
 
 use std::path::Path;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, RwLock, RwLockReadGuard};
 
 pub mod audio;
+pub mod backend;
+pub mod chunking;
 pub mod engine;
+pub mod features;
+pub mod filter;
 pub mod funasr;
+pub mod metrics;
+pub mod streaming;
+pub mod transcript;
 
-pub use engine::WhisperEngine;
+pub use backend::{AsrBackend, AsrBackendKind, AsrCapabilities};
+pub use chunking::ChunkConfig;
+pub use engine::{WhisperEngine, WhisperParams};
+pub use filter::{DropReason, HallucinationFilter};
 pub use funasr::{FunAsrEngine, FunAsrModelSize};
-pub use funasr::engine::FunAsrSession;
+pub use streaming::{StreamEvent, StreamingConfig, StreamingTranscriber};
+pub use transcript::{DetectedLanguage, Segment, Token, Transcription};
 
 /// Whisper model sizes
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum WhisperModelSize {
-    Tiny,   // 72MB
-    Base,   // 142MB
-    Small,  // 466MB
-    Medium, // 1.5GB
+    Tiny,     // 72MB
+    TinyQ5,   // 31MB
+    TinyQ8,   // 42MB
+    Base,     // 142MB
+    BaseQ5,   // 57MB
+    BaseQ8,   // 78MB
+    Small,    // 466MB
+    SmallQ5,  // 181MB
+    SmallQ8,  // 252MB
+    Medium,   // 1.5GB
+    MediumQ5, // 514MB
+    MediumQ8, // 785MB
+    Turbo,    // 1.6GB
+    TurboQ5,  // 547MB
+    TurboQ8,  // 834MB
 }
 
 impl WhisperModelSize {
@@ -27,87 +49,370 @@ impl WhisperModelSize {
             .map(|h| h.join(".mofa/models"))
             .unwrap_or_else(|| std::path::PathBuf::from("./models"));
 
+        base.join(self.file_name())
+    }
+
+    pub fn file_name(&self) -> &'static str {
         match self {
-            WhisperModelSize::Tiny => base.join("ggml-tiny.bin"),
-            WhisperModelSize::Base => base.join("ggml-base.bin"),
-            WhisperModelSize::Small => base.join("ggml-small.bin"),
-            WhisperModelSize::Medium => base.join("ggml-medium.bin"),
+            WhisperModelSize::Tiny => "ggml-tiny.bin",
+            WhisperModelSize::TinyQ5 => "ggml-tiny-q5_1.bin",
+            WhisperModelSize::TinyQ8 => "ggml-tiny-q8_0.bin",
+            WhisperModelSize::Base => "ggml-base.bin",
+            WhisperModelSize::BaseQ5 => "ggml-base-q5_1.bin",
+            WhisperModelSize::BaseQ8 => "ggml-base-q8_0.bin",
+            WhisperModelSize::Small => "ggml-small.bin",
+            WhisperModelSize::SmallQ5 => "ggml-small-q5_1.bin",
+            WhisperModelSize::SmallQ8 => "ggml-small-q8_0.bin",
+            WhisperModelSize::Medium => "ggml-medium.bin",
+            WhisperModelSize::MediumQ5 => "ggml-medium-q5_0.bin",
+            WhisperModelSize::MediumQ8 => "ggml-medium-q8_0.bin",
+            WhisperModelSize::Turbo => "ggml-large-v3-turbo.bin",
+            WhisperModelSize::TurboQ5 => "ggml-large-v3-turbo-q5_0.bin",
+            WhisperModelSize::TurboQ8 => "ggml-large-v3-turbo-q8_0.bin",
         }
     }
 
     pub fn name(&self) -> &'static str {
         match self {
             WhisperModelSize::Tiny => "Tiny",
+            WhisperModelSize::TinyQ5 => "Tiny Q5",
+            WhisperModelSize::TinyQ8 => "Tiny Q8",
             WhisperModelSize::Base => "Base",
+            WhisperModelSize::BaseQ5 => "Base Q5",
+            WhisperModelSize::BaseQ8 => "Base Q8",
             WhisperModelSize::Small => "Small",
+            WhisperModelSize::SmallQ5 => "Small Q5",
+            WhisperModelSize::SmallQ8 => "Small Q8",
             WhisperModelSize::Medium => "Medium",
+            WhisperModelSize::MediumQ5 => "Medium Q5",
+            WhisperModelSize::MediumQ8 => "Medium Q8",
+            WhisperModelSize::Turbo => "Large-v3 Turbo",
+            WhisperModelSize::TurboQ5 => "Turbo Q5",
+            WhisperModelSize::TurboQ8 => "Turbo Q8",
         }
     }
 
     pub fn description(&self) -> &'static str {
         match self {
             WhisperModelSize::Tiny => "超快，适合实时 (~72MB)",
+            WhisperModelSize::TinyQ5 => "Tiny 5-bit 量化 (~31MB)",
+            WhisperModelSize::TinyQ8 => "Tiny 8-bit 量化 (~42MB)",
             WhisperModelSize::Base => "平衡，推荐 (~142MB)",
+            WhisperModelSize::BaseQ5 => "Base 5-bit 量化 (~57MB)",
+            WhisperModelSize::BaseQ8 => "Base 8-bit 量化 (~78MB)",
             WhisperModelSize::Small => "质量较好 (~466MB)",
+            WhisperModelSize::SmallQ5 => "Small 5-bit 量化 (~181MB)",
+            WhisperModelSize::SmallQ8 => "Small 8-bit 量化 (~252MB)",
             WhisperModelSize::Medium => "质量最佳 (~1.5GB)",
+            WhisperModelSize::MediumQ5 => "Medium 5-bit 量化 (~514MB)",
+            WhisperModelSize::MediumQ8 => "Medium 8-bit 量化 (~785MB)",
+            WhisperModelSize::Turbo => "large-v3 精简解码器，质量最佳 (~1.6GB)",
+            WhisperModelSize::TurboQ5 => "Turbo 5-bit 量化，性价比最高 (~547MB)",
+            WhisperModelSize::TurboQ8 => "Turbo 8-bit 量化 (~834MB)",
         }
     }
 
     pub fn size_mb(&self) -> u64 {
         match self {
             WhisperModelSize::Tiny => 72,
+            WhisperModelSize::TinyQ5 => 31,
+            WhisperModelSize::TinyQ8 => 42,
             WhisperModelSize::Base => 142,
+            WhisperModelSize::BaseQ5 => 57,
+            WhisperModelSize::BaseQ8 => 78,
             WhisperModelSize::Small => 466,
+            WhisperModelSize::SmallQ5 => 181,
+            WhisperModelSize::SmallQ8 => 252,
             WhisperModelSize::Medium => 1500,
+            WhisperModelSize::MediumQ5 => 514,
+            WhisperModelSize::MediumQ8 => 785,
+            WhisperModelSize::Turbo => 1620,
+            WhisperModelSize::TurboQ5 => 547,
+            WhisperModelSize::TurboQ8 => 834,
         }
     }
 
     pub fn download_url(&self) -> &'static str {
         match self {
             WhisperModelSize::Tiny => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-tiny.bin",
+            WhisperModelSize::TinyQ5 => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-tiny-q5_1.bin",
+            WhisperModelSize::TinyQ8 => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-tiny-q8_0.bin",
             WhisperModelSize::Base => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base.bin",
+            WhisperModelSize::BaseQ5 => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base-q5_1.bin",
+            WhisperModelSize::BaseQ8 => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base-q8_0.bin",
             WhisperModelSize::Small => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-small.bin",
+            WhisperModelSize::SmallQ5 => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-small-q5_1.bin",
+            WhisperModelSize::SmallQ8 => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-small-q8_0.bin",
             WhisperModelSize::Medium => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-medium.bin",
+            WhisperModelSize::MediumQ5 => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-medium-q5_0.bin",
+            WhisperModelSize::MediumQ8 => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-medium-q8_0.bin",
+            WhisperModelSize::Turbo => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-large-v3-turbo.bin",
+            WhisperModelSize::TurboQ5 => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-large-v3-turbo-q5_0.bin",
+            WhisperModelSize::TurboQ8 => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-large-v3-turbo-q8_0.bin",
         }
     }
 
-    pub fn all() -> [WhisperModelSize; 4] {
+    /// Smallest to largest family; quantized variants first within a
+    /// family, so later entries are better
+    pub fn all() -> [WhisperModelSize; 15] {
         [
+            WhisperModelSize::TinyQ5,
+            WhisperModelSize::TinyQ8,
             WhisperModelSize::Tiny,
+            WhisperModelSize::BaseQ5,
+            WhisperModelSize::BaseQ8,
             WhisperModelSize::Base,
+            WhisperModelSize::SmallQ5,
+            WhisperModelSize::SmallQ8,
             WhisperModelSize::Small,
+            WhisperModelSize::MediumQ5,
+            WhisperModelSize::MediumQ8,
             WhisperModelSize::Medium,
+            WhisperModelSize::TurboQ5,
+            WhisperModelSize::TurboQ8,
+            WhisperModelSize::Turbo,
         ]
     }
 }
 
-/// Thread-safe ASR session
+/// Settings applied around the backend call
+struct SessionSettings {
+    params: WhisperParams,
+    filter: Option<HallucinationFilter>,
+    chunking: Option<ChunkConfig>,
+}
+
+/// Thread-safe ASR session over any `AsrBackend`
+///
+/// Clones share the loaded model. Transcriptions run concurrently up to
+/// `WhisperParams::parallelism`; changing parameters waits for them to finish.
 #[derive(Clone)]
 pub struct AsrSession {
-    engine: Arc<Mutex<WhisperEngine>>,
+    backend: Arc<RwLock<Box<dyn AsrBackend>>>,
+    settings: Arc<Mutex<SessionSettings>>,
 }
 
 impl AsrSession {
+    /// Load a model, picking the backend from the path
     pub fn new(model_path: &Path) -> anyhow::Result<Self> {
-        let engine = WhisperEngine::new(model_path)?;
-        Ok(Self {
-            engine: Arc::new(Mutex::new(engine)),
-        })
+        Self::with_params(model_path, WhisperParams::default())
+    }
+
+    pub fn with_params(model_path: &Path, params: WhisperParams) -> anyhow::Result<Self> {
+        Self::load(AsrBackendKind::detect(model_path), model_path, params)
+    }
+
+    pub fn load(
+        kind: AsrBackendKind,
+        model_path: &Path,
+        params: WhisperParams,
+    ) -> anyhow::Result<Self> {
+        let backend = kind.load(model_path, &params)?;
+        Ok(Self::from_backend(backend, params))
+    }
+
+    pub fn from_backend(backend: Box<dyn AsrBackend>, params: WhisperParams) -> Self {
+        Self {
+            backend: Arc::new(RwLock::new(backend)),
+            settings: Arc::new(Mutex::new(SessionSettings {
+                params,
+                filter: None,
+                chunking: Some(ChunkConfig::default()),
+            })),
+        }
+    }
+
+    fn backend(&self) -> RwLockReadGuard<'_, Box<dyn AsrBackend>> {
+        self.backend.read().unwrap()
+    }
+
+    fn filter(&self) -> Option<HallucinationFilter> {
+        self.settings.lock().unwrap().filter.clone()
+    }
+
+    pub fn backend_name(&self) -> &'static str {
+        self.backend().name()
+    }
+
+    pub fn capabilities(&self) -> AsrCapabilities {
+        self.backend().capabilities()
+    }
+
+    pub fn languages(&self) -> &'static [&'static str] {
+        self.backend().languages()
+    }
+
+    /// Current decoding parameters
+    pub fn params(&self) -> WhisperParams {
+        self.settings.lock().unwrap().params.clone()
+    }
+
+    /// Replace decoding parameters for subsequent transcriptions
+    pub fn set_params(&self, params: WhisperParams) {
+        self.backend.write().unwrap().set_params(&params);
+        self.settings.lock().unwrap().params = params;
+    }
+
+    /// Drop hallucinated segments from subsequent results; `None` disables
+    pub fn set_filter(&self, filter: Option<HallucinationFilter>) {
+        self.settings.lock().unwrap().filter = filter;
+    }
+
+    /// Split audio longer than one chunk before transcribing; `None` hands
+    /// the whole recording to the backend
+    pub fn set_chunking(&self, chunking: Option<ChunkConfig>) {
+        self.settings.lock().unwrap().chunking = chunking;
+    }
+
+    fn plan_chunks(&self, samples: &[f32]) -> Vec<chunking::Chunk> {
+        match self.settings.lock().unwrap().chunking {
+            Some(config) => chunking::plan_chunks(samples, &config),
+            None => vec![chunking::Chunk {
+                start: 0,
+                end: samples.len(),
+                overlap: 0,
+            }],
+        }
     }
 
     /// Transcribe audio samples (16kHz, mono, f32)
     pub fn transcribe(&self, samples: &[f32]) -> anyhow::Result<String> {
-        let engine = self.engine.lock().unwrap();
-        engine.transcribe(samples)
+        Ok(self.transcribe_with_progress(samples, |_| {})?.text)
+    }
+
+    /// Transcribe to text and the detected language, with progress callback
+    ///
+    /// Long audio is transcribed chunk by chunk and the callback receives
+    /// each chunk's text once merged.
+    pub fn transcribe_with_progress<F>(
+        &self,
+        samples: &[f32],
+        mut callback: F,
+    ) -> anyhow::Result<Transcription>
+    where
+        F: FnMut(&str),
+    {
+        let backend = self.backend();
+        let filter = self.filter();
+        let chunks = self.plan_chunks(samples);
+        if chunks.len() == 1 {
+            return transcribe_text(backend.as_ref(), filter.as_ref(), samples, &mut callback);
+        }
+
+        let mut merged = String::new();
+        let mut languages = Vec::new();
+        for chunk in &chunks {
+            let result = transcribe_text(
+                backend.as_ref(),
+                filter.as_ref(),
+                &samples[chunk.start..chunk.end],
+                &mut |_: &str| {},
+            )?;
+            languages.extend(result.language.map(|l| (l, chunk.end - chunk.start)));
+            let piece = chunking::merge_text(&mut merged, &result.text, chunk.overlap_ms());
+            callback(&piece);
+        }
+        Ok(Transcription::from_text(
+            merged,
+            chunking::merge_languages(&languages),
+        ))
     }
 
-    /// Transcribe with progress callback
-    pub fn transcribe_with_progress<F>(&self, samples: &[f32], callback: F) -> anyhow::Result<String>
+    /// Transcribe into segments, reporting each segment's text as it is ready;
+    /// backends without timestamps yield a single untimed segment
+    pub fn transcribe_segments_with_progress<F>(
+        &self,
+        samples: &[f32],
+        mut callback: F,
+    ) -> anyhow::Result<Transcription>
     where
-        F: Fn(&str) + Send + 'static,
+        F: FnMut(&str),
     {
-        let engine = self.engine.lock().unwrap();
-        engine.transcribe_with_progress(samples, callback)
+        if self.capabilities().timestamps {
+            return self.transcribe_chunked_segments(samples, &mut callback);
+        }
+
+        let mut result = self.transcribe_with_progress(samples, callback)?;
+        if !result.text.trim().is_empty() {
+            result.segments.push(Segment {
+                start_ms: 0,
+                end_ms: (samples.len() * 1000 / 16_000) as i64,
+                text: result.text.clone(),
+                avg_logprob: 0.0,
+                no_speech_prob: 0.0,
+                tokens: Vec::new(),
+            });
+        }
+        Ok(result)
+    }
+
+    /// Transcribe into timed segments (16kHz, mono, f32)
+    pub fn transcribe_detailed(&self, samples: &[f32]) -> anyhow::Result<Transcription> {
+        self.transcribe_chunked_segments(samples, &mut |_: &str| {})
+    }
+
+    /// Timed segments for each chunk, shifted onto the recording's timeline
+    /// and merged at the middle of each overlap
+    fn transcribe_chunked_segments(
+        &self,
+        samples: &[f32],
+        callback: &mut dyn FnMut(&str),
+    ) -> anyhow::Result<Transcription> {
+        let backend = self.backend();
+        let filter = self.filter();
+        let mut merged: Vec<Segment> = Vec::new();
+        let mut languages = Vec::new();
+        for chunk in self.plan_chunks(samples) {
+            let result = backend.transcribe_detailed(&samples[chunk.start..chunk.end])?;
+            languages.extend(result.language.map(|l| (l, chunk.end - chunk.start)));
+            let mut segments = result.segments;
+            if let Some(filter) = &filter {
+                segments = filter.filter(segments);
+            }
+            chunking::offset_segments(&mut segments, chunk.start_ms());
+            let added = if chunk.overlap > 0 {
+                chunking::merge_segments(&mut merged, segments, chunk.handover_ms())
+            } else {
+                let added = segments.len();
+                merged.extend(segments);
+                added
+            };
+            for segment in &merged[merged.len() - added..] {
+                callback(&segment.text);
+            }
+        }
+        Ok(Transcription::from_segments(
+            merged,
+            chunking::merge_languages(&languages),
+        ))
+    }
+}
+
+/// Transcribe one piece of audio as text, filtered when a filter is set
+fn transcribe_text(
+    backend: &dyn AsrBackend,
+    filter: Option<&HallucinationFilter>,
+    samples: &[f32],
+    callback: &mut dyn FnMut(&str),
+) -> anyhow::Result<Transcription> {
+    let Some(filter) = filter else {
+        return backend.transcribe_streaming(samples, callback);
+    };
+
+    if backend.capabilities().timestamps {
+        let result = backend.transcribe_detailed(samples)?;
+        let segments = filter.filter(result.segments);
+        for segment in &segments {
+            callback(&segment.text);
+        }
+        Ok(Transcription::from_text(
+            transcript::join_segments(&segments),
+            result.language,
+        ))
+    } else {
+        let text = filter.filter_text(&backend.transcribe(samples)?);
+        callback(&text);
+        Ok(Transcription::from_text(text, None))
     }
 }
 
@@ -116,3 +421,25 @@ pub fn is_model_available(model: WhisperModelSize) -> bool {
     let path = model.path();
     path.exists() && path.metadata().map(|m| m.len() > 1000).unwrap_or(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn whisper_models_are_ordered_by_quality() {
+        let all = WhisperModelSize::all();
+        for family in all.chunks(3) {
+            let [q5, q8, full] = family else {
+                panic!("families have three variants");
+            };
+            assert!(q5.name().ends_with("Q5"), "{}", q5.name());
+            assert!(q8.name().ends_with("Q8"), "{}", q8.name());
+            assert!(!full.name().contains(" Q"), "{}", full.name());
+            assert!(q5.size_mb() < q8.size_mb() && q8.size_mb() < full.size_mb());
+        }
+        let full: Vec<u64> = all.chunks(3).map(|f| f[2].size_mb()).collect();
+        assert!(full.windows(2).all(|w| w[0] < w[1]), "{full:?}");
+        assert_eq!(all.last(), Some(&WhisperModelSize::Turbo));
+    }
+}
@@ -0,0 +1,549 @@
+//! Streaming transcription over a growing audio buffer
+//!
+//! Re-decodes a sliding window on every step and commits the token prefix that
+//! two consecutive passes agree on (LocalAgreement-2). Audio behind fully
+//! committed segments is dropped from the window, so the final pass only has
+//! to decode the unstable tail.
+
+use std::borrow::Cow;
+
+use super::chunking::offset_segments;
+use super::transcript::{DetectedLanguage, Segment, Token, Transcription};
+use super::AsrSession;
+use crate::audio::{preprocess, DspConfig};
+
+const SAMPLE_RATE: usize = 16_000;
+
+/// Streaming configuration
+#[derive(Clone, Copy, Debug)]
+pub struct StreamingConfig {
+    /// Minimum new audio between two decoding passes
+    pub step_ms: u32,
+    /// Window length after which committed segments are trimmed
+    pub max_window_ms: u32,
+    /// Window length at which trimming is forced regardless of agreement
+    pub hard_window_ms: u32,
+}
+
+impl Default for StreamingConfig {
+    fn default() -> Self {
+        Self {
+            step_ms: 1000,
+            max_window_ms: 15_000,
+            hard_window_ms: 27_000,
+        }
+    }
+}
+
+/// Transcription progress
+#[derive(Clone, Debug, PartialEq)]
+pub enum StreamEvent {
+    /// Stable text plus the not-yet-agreed tail
+    Partial { committed: String, tentative: String },
+    /// Full transcript after the last pass
+    Final { text: String },
+}
+
+impl StreamEvent {
+    /// Text to display for this event
+    pub fn text(&self) -> String {
+        match self {
+            StreamEvent::Partial {
+                committed,
+                tentative,
+            } => format!("{committed}{tentative}").trim().to_string(),
+            StreamEvent::Final { text } => text.clone(),
+        }
+    }
+}
+
+/// Incremental transcriber fed with the whole 16kHz buffer recorded so far
+pub struct StreamingTranscriber {
+    session: AsrSession,
+    config: StreamingConfig,
+    /// Preprocessing applied to the buffer before every pass
+    dsp: Option<DspConfig>,
+    /// Segments whose audio has left the window, on the buffer's timeline
+    committed: Vec<Segment>,
+    /// First sample of the current window
+    window_start: usize,
+    /// Committed tokens still inside the window
+    window_committed: Vec<Token>,
+    /// Previous hypothesis beyond the committed prefix
+    tentative: Vec<Token>,
+    /// Language of the latest pass
+    language: Option<DetectedLanguage>,
+    last_pass_len: usize,
+}
+
+impl StreamingTranscriber {
+    pub fn new(session: AsrSession) -> Self {
+        Self::with_config(session, StreamingConfig::default())
+    }
+
+    pub fn with_config(session: AsrSession, config: StreamingConfig) -> Self {
+        Self {
+            session,
+            config,
+            dsp: None,
+            committed: Vec::new(),
+            window_start: 0,
+            window_committed: Vec::new(),
+            tentative: Vec::new(),
+            language: None,
+            last_pass_len: 0,
+        }
+    }
+
+    /// Preprocess the raw buffer before every pass, including the last, so
+    /// interim and final passes hear the same audio
+    pub fn with_dsp(mut self, dsp: DspConfig) -> Self {
+        self.dsp = Some(dsp);
+        self
+    }
+
+    /// Run a pass if enough new audio has arrived since the last one
+    pub fn push(&mut self, samples: &[f32]) -> anyhow::Result<Option<StreamEvent>> {
+        let step = ms_to_samples(self.config.step_ms);
+        if samples.len() < self.last_pass_len + step || samples.len() <= self.window_start {
+            return Ok(None);
+        }
+        self.last_pass_len = samples.len();
+
+        let samples = self.prepare(samples);
+        let window = &samples[self.window_start..];
+        let result = self.session.transcribe_detailed(window)?;
+        self.language = result.language.or(self.language);
+        let segments = result.segments;
+        let hypothesis: Vec<Token> = segments.iter().flat_map(|s| s.tokens.clone()).collect();
+
+        // A pass that revises committed tokens commits nothing new
+        let matched = self.after_committed(&hypothesis);
+        let rest = matched.unwrap_or(&[]);
+        let agreed = self
+            .tentative
+            .iter()
+            .zip(rest)
+            .take_while(|(prev, cur)| prev.text == cur.text)
+            .count();
+        self.window_committed.extend_from_slice(&rest[..agreed]);
+        self.tentative = rest[agreed..].to_vec();
+
+        if window.len() > ms_to_samples(self.config.hard_window_ms) {
+            self.force_trim(&segments);
+        } else if matched.is_some() && window.len() > ms_to_samples(self.config.max_window_ms) {
+            self.trim_committed(&segments);
+        }
+
+        Ok(Some(StreamEvent::Partial {
+            committed: format!(
+                "{}{}",
+                self.committed_text(),
+                join_tokens(&self.window_committed)
+            ),
+            tentative: join_tokens(&self.tentative),
+        }))
+    }
+
+    /// Audio before `sample` holds no speech; the final pass starts there
+    /// if the window has not moved past it yet
+    pub fn skip_to(&mut self, sample: usize) {
+        self.window_start = self.window_start.max(sample);
+    }
+
+    /// Decode the remaining window and return the full transcript
+    pub fn finish(&mut self, samples: &[f32]) -> anyhow::Result<StreamEvent> {
+        let result = self.finish_transcription(samples)?;
+        Ok(StreamEvent::Final { text: result.text })
+    }
+
+    /// Decode the remaining window and return all segments, timed from the
+    /// start of `samples`, with the language of the last pass
+    ///
+    /// The last pass sees the whole window, so its hypothesis replaces the
+    /// window's committed and tentative tokens.
+    pub fn finish_transcription(&mut self, samples: &[f32]) -> anyhow::Result<Transcription> {
+        let mut segments = std::mem::take(&mut self.committed);
+        if samples.len() > self.window_start {
+            let samples = self.prepare(samples);
+            let result = self
+                .session
+                .transcribe_detailed(&samples[self.window_start..])?;
+            self.language = result.language.or(self.language);
+            let mut window = result.segments;
+            offset_segments(&mut window, self.window_offset_ms());
+            segments.extend(window);
+        }
+        self.window_committed.clear();
+        self.tentative.clear();
+        Ok(Transcription::from_segments(segments, self.language))
+    }
+
+    /// The buffer after the configured preprocessing; the whole buffer is
+    /// processed since noise suppression profiles its start
+    fn prepare<'a>(&self, samples: &'a [f32]) -> Cow<'a, [f32]> {
+        match &self.dsp {
+            Some(dsp) => Cow::Owned(preprocess(samples, dsp)),
+            None => Cow::Borrowed(samples),
+        }
+    }
+
+    /// The hypothesis beyond the window's committed tokens, or `None` if it
+    /// does not start with them
+    fn after_committed<'a>(&self, hypothesis: &'a [Token]) -> Option<&'a [Token]> {
+        let n = self.window_committed.len();
+        let prefix = hypothesis.get(..n)?;
+        prefix
+            .iter()
+            .zip(&self.window_committed)
+            .all(|(cur, committed)| cur.text == committed.text)
+            .then(|| &hypothesis[n..])
+    }
+
+    fn committed_text(&self) -> String {
+        self.committed
+            .iter()
+            .map(|seg| join_tokens(&seg.tokens))
+            .collect()
+    }
+
+    /// Move `done` out of the window onto the buffer's timeline and advance
+    /// the window past the last of them
+    fn commit_segments(&mut self, done: &[Segment]) {
+        let Some(last) = done.last() else {
+            return;
+        };
+        let mut done = done.to_vec();
+        offset_segments(&mut done, self.window_offset_ms());
+        self.window_start += ms_to_samples(last.end_ms.max(0) as u32);
+        self.committed.extend(done);
+    }
+
+    fn window_offset_ms(&self) -> i64 {
+        (self.window_start * 1000 / SAMPLE_RATE) as i64
+    }
+
+    /// Drop leading segments whose tokens are all committed
+    fn trim_committed(&mut self, segments: &[Segment]) {
+        let mut n_tokens = 0;
+        let mut n_segments = 0;
+        // Keep the last segment: its end is not yet reliable
+        for seg in segments.iter().take(segments.len().saturating_sub(1)) {
+            if n_tokens + seg.tokens.len() > self.window_committed.len() {
+                break;
+            }
+            n_tokens += seg.tokens.len();
+            n_segments += 1;
+        }
+
+        if n_segments > 0 {
+            self.window_committed.drain(..n_tokens);
+            self.commit_segments(&segments[..n_segments]);
+        }
+    }
+
+    /// Commit every segment but the last, even without agreement
+    fn force_trim(&mut self, segments: &[Segment]) {
+        let Some((last, done)) = segments.split_last() else {
+            return;
+        };
+        if done.is_empty() {
+            return;
+        }
+
+        self.commit_segments(done);
+        self.window_committed.clear();
+        self.tentative = last.tokens.clone();
+    }
+}
+
+fn join_tokens(tokens: &[Token]) -> String {
+    tokens.iter().map(|t| t.text.as_str()).collect()
+}
+
+fn ms_to_samples(ms: u32) -> usize {
+    ms as usize * SAMPLE_RATE / 1000
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::asr::{AsrBackend, AsrCapabilities, WhisperParams};
+
+    /// 100 ms, the stub's decoding unit
+    const BLOCK: usize = SAMPLE_RATE / 10;
+
+    /// Hears one word per 100 ms block: level n / 100 is word n and silence
+    /// ends a segment. A word still being spoken at the end of the window
+    /// is misheard, or with `unstable` every word differs between passes.
+    #[derive(Default)]
+    struct BlockBackend {
+        unstable: bool,
+        calls: AtomicUsize,
+        inputs: Arc<Mutex<Vec<Vec<f32>>>>,
+    }
+
+    impl AsrBackend for BlockBackend {
+        fn name(&self) -> &'static str {
+            "block"
+        }
+
+        fn capabilities(&self) -> AsrCapabilities {
+            AsrCapabilities {
+                timestamps: true,
+                ..Default::default()
+            }
+        }
+
+        fn languages(&self) -> &'static [&'static str] {
+            &["en"]
+        }
+
+        fn transcribe(&self, samples: &[f32]) -> anyhow::Result<String> {
+            Ok(self.transcribe_detailed(samples)?.text)
+        }
+
+        fn transcribe_detailed(&self, samples: &[f32]) -> anyhow::Result<Transcription> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            self.inputs.lock().unwrap().push(samples.to_vec());
+
+            let n_blocks = samples.len() / BLOCK;
+            let mut segments = Vec::new();
+            let mut tokens = Vec::new();
+            for (i, block) in samples.chunks_exact(BLOCK).enumerate() {
+                let word = level_word(block[0]);
+                if word == 0 {
+                    segments.extend(segment(std::mem::take(&mut tokens)));
+                    continue;
+                }
+                let mut text = format!(" w{word}");
+                if self.unstable {
+                    text.push_str(&format!("#{call}"));
+                } else if i + 1 == n_blocks {
+                    text.push('?');
+                }
+                let t0 = i as i64 * 100;
+                tokens.push(Token {
+                    text,
+                    p: 1.0,
+                    t0,
+                    t1: t0 + 100,
+                });
+            }
+            segments.extend(segment(tokens));
+            Ok(Transcription::from_segments(segments, None))
+        }
+    }
+
+    fn segment(tokens: Vec<Token>) -> Option<Segment> {
+        Some(Segment {
+            start_ms: tokens.first()?.t0,
+            end_ms: tokens.last()?.t1,
+            text: join_tokens(&tokens),
+            avg_logprob: 0.0,
+            no_speech_prob: 0.0,
+            tokens,
+        })
+    }
+
+    fn level_word(level: f32) -> usize {
+        (level * 100.0).round() as usize
+    }
+
+    /// One block per entry, 0 for silence
+    fn speech(words: &[usize]) -> Vec<f32> {
+        words
+            .iter()
+            .flat_map(|&w| std::iter::repeat_n(w as f32 / 100.0, BLOCK))
+            .collect()
+    }
+
+    /// `groups` runs of three words, each followed by a silent block
+    fn phrases(groups: usize) -> Vec<usize> {
+        (0..groups)
+            .flat_map(|g| [3 * g + 1, 3 * g + 2, 3 * g + 3, 0])
+            .collect()
+    }
+
+    fn expected_text(words: &[usize]) -> String {
+        words
+            .iter()
+            .filter(|&&w| w > 0)
+            .map(|w| format!(" w{w}"))
+            .collect()
+    }
+
+    fn streamer(backend: BlockBackend, config: StreamingConfig) -> StreamingTranscriber {
+        let session = AsrSession::from_backend(Box::new(backend), WhisperParams::default());
+        StreamingTranscriber::with_config(session, config)
+    }
+
+    fn small_windows() -> StreamingConfig {
+        StreamingConfig {
+            step_ms: 200,
+            max_window_ms: 1000,
+            hard_window_ms: 1500,
+        }
+    }
+
+    /// Every token starts at the block holding its word in `words`
+    fn assert_on_timeline(result: &Transcription, words: &[usize]) {
+        let tokens: Vec<&Token> = result.segments.iter().flat_map(|s| &s.tokens).collect();
+        assert!(!tokens.is_empty());
+        for token in tokens {
+            let word: usize = token
+                .text
+                .trim_start_matches(" w")
+                .split(|c: char| !c.is_ascii_digit())
+                .next()
+                .and_then(|n| n.parse().ok())
+                .unwrap();
+            let block = words.iter().position(|&w| w == word).unwrap();
+            assert_eq!(token.t0, block as i64 * 100, "{}", token.text);
+        }
+    }
+
+    #[test]
+    fn committed_text_never_regresses_and_stays_on_the_timeline() {
+        let mut words = phrases(8);
+        words.push(0);
+        let audio = speech(&words);
+        let expected = expected_text(&words);
+        let backend = BlockBackend::default();
+        let inputs = backend.inputs.clone();
+        let mut streamer = streamer(backend, small_windows());
+
+        let mut last = String::new();
+        for end in (2 * BLOCK..=audio.len()).step_by(2 * BLOCK) {
+            let Some(StreamEvent::Partial { committed, .. }) =
+                streamer.push(&audio[..end]).unwrap()
+            else {
+                panic!("no pass at {end}");
+            };
+            assert!(committed.starts_with(&last), "{last:?} -> {committed:?}");
+            assert!(expected.starts_with(&committed), "{committed:?}");
+            last = committed;
+        }
+        assert!(last.len() > expected.len() / 2, "{last:?}");
+
+        let result = streamer.finish_transcription(&audio).unwrap();
+        assert_eq!(result.text, expected.trim());
+        assert_on_timeline(&result, &words);
+        // Committed phrases were trimmed, so the last pass saw only the tail
+        assert!(inputs.lock().unwrap().last().unwrap().len() < audio.len());
+    }
+
+    #[test]
+    fn revised_pass_commits_nothing_new() {
+        let words: Vec<usize> = (1..=12).collect();
+        let audio = speech(&words);
+        let config = StreamingConfig {
+            step_ms: 200,
+            ..Default::default()
+        };
+        let mut streamer = streamer(BlockBackend::default(), config);
+        let mut push = |samples: &[f32]| match streamer.push(samples).unwrap() {
+            Some(StreamEvent::Partial { committed, .. }) => committed,
+            other => panic!("{other:?}"),
+        };
+
+        push(&audio[..4 * BLOCK]);
+        // The word at the edge of the first pass was misheard
+        assert_eq!(push(&audio[..6 * BLOCK]), " w1 w2 w3");
+
+        // A pass that hears w2 differently keeps what was committed
+        let mut revised = audio[..8 * BLOCK].to_vec();
+        revised[BLOCK..2 * BLOCK].fill(0.99);
+        assert_eq!(push(&revised), " w1 w2 w3");
+
+        // Agreement resumes after the committed prefix
+        assert_eq!(push(&audio[..10 * BLOCK]), " w1 w2 w3");
+        assert_eq!(push(&audio[..12 * BLOCK]), " w1 w2 w3 w4 w5 w6 w7 w8 w9");
+    }
+
+    #[test]
+    fn forced_trim_keeps_offsets_without_agreement() {
+        let words = phrases(6);
+        let audio = speech(&words);
+        let backend = BlockBackend {
+            unstable: true,
+            ..Default::default()
+        };
+        let inputs = backend.inputs.clone();
+        let config = small_windows();
+        let mut streamer = streamer(backend, config);
+
+        let mut committed = String::new();
+        for end in (2 * BLOCK..=audio.len()).step_by(2 * BLOCK) {
+            if let Some(StreamEvent::Partial { committed: c, .. }) =
+                streamer.push(&audio[..end]).unwrap()
+            {
+                committed = c;
+            }
+        }
+        // Nothing ever agreed, yet the window was cut at the hard limit
+        assert!(!committed.is_empty());
+        let limit = ms_to_samples(config.hard_window_ms + 2 * config.step_ms);
+        assert!(inputs.lock().unwrap().iter().all(|w| w.len() <= limit));
+
+        let result = streamer.finish_transcription(&audio).unwrap();
+        assert_on_timeline(&result, &words);
+    }
+
+    #[test]
+    fn skip_to_starts_the_final_pass_on_the_buffer_timeline() {
+        let mut words = vec![0; 5];
+        words.extend(phrases(2));
+        let audio = speech(&words);
+        let backend = BlockBackend::default();
+        let inputs = backend.inputs.clone();
+        let mut streamer = streamer(backend, small_windows());
+
+        streamer.skip_to(5 * BLOCK);
+        let result = streamer.finish_transcription(&audio).unwrap();
+        assert_eq!(result.text, expected_text(&words).trim());
+        assert_eq!(result.segments[0].start_ms, 500);
+        assert_on_timeline(&result, &words);
+        assert_eq!(inputs.lock().unwrap()[0].len(), audio.len() - 5 * BLOCK);
+    }
+
+    #[test]
+    fn skip_to_never_moves_the_window_back() {
+        let mut words = phrases(8);
+        words.push(0);
+        let audio = speech(&words);
+        let mut streamer = streamer(BlockBackend::default(), small_windows());
+        for end in (2 * BLOCK..=audio.len()).step_by(2 * BLOCK) {
+            streamer.push(&audio[..end]).unwrap();
+        }
+        let trimmed_to = streamer.window_start;
+        assert!(trimmed_to > BLOCK);
+
+        streamer.skip_to(BLOCK);
+        assert_eq!(streamer.window_start, trimmed_to);
+        let result = streamer.finish_transcription(&audio).unwrap();
+        assert_eq!(result.text, expected_text(&words).trim());
+    }
+
+    #[test]
+    fn interim_and_final_passes_hear_the_same_preprocessing() {
+        let words = phrases(4);
+        let audio = speech(&words);
+        let dsp = DspConfig::default();
+        let backend = BlockBackend::default();
+        let inputs = backend.inputs.clone();
+        let mut streamer = streamer(backend, StreamingConfig::default()).with_dsp(dsp);
+
+        streamer.push(&audio[..10 * BLOCK]).unwrap();
+        streamer.skip_to(2 * BLOCK);
+        streamer.finish_transcription(&audio).unwrap();
+
+        let inputs = inputs.lock().unwrap();
+        assert_eq!(inputs.len(), 2);
+        assert_eq!(inputs[0], preprocess(&audio[..10 * BLOCK], &dsp));
+        assert_eq!(inputs[1], preprocess(&audio, &dsp)[2 * BLOCK..]);
+    }
+}
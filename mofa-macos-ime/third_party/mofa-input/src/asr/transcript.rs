@@ -0,0 +1,119 @@
+//! Timed transcription results
+
+use serde::Serialize;
+
+/// A decoded token with its timing and probability
+///
+/// Sub-character BPE pieces are merged, so `text` is always whole characters.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Token {
+    pub text: String,
+    /// Token probability (0.0 - 1.0), the minimum over merged pieces
+    pub p: f32,
+    /// Start time in milliseconds
+    pub t0: i64,
+    /// End time in milliseconds
+    pub t1: i64,
+}
+
+/// A transcribed segment with timing and confidence information
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Segment {
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub text: String,
+    /// Mean log probability over text tokens
+    pub avg_logprob: f32,
+    pub no_speech_prob: f32,
+    pub tokens: Vec<Token>,
+}
+
+/// Spoken language reported by the recognizer
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct DetectedLanguage {
+    /// Whisper language code, e.g. "zh", "en", "ja"
+    pub code: &'static str,
+    /// Detection probability (0.0 - 1.0)
+    pub probability: f32,
+}
+
+/// Result of one transcription
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Transcription {
+    pub text: String,
+    /// Timed segments; empty for text-only results
+    pub segments: Vec<Segment>,
+    /// Language the audio was decoded in, if the backend reports one;
+    /// probability is 1.0 when the language was forced
+    pub language: Option<DetectedLanguage>,
+}
+
+impl Transcription {
+    pub fn from_text(text: String, language: Option<DetectedLanguage>) -> Self {
+        Self {
+            text,
+            segments: Vec::new(),
+            language,
+        }
+    }
+
+    pub fn from_segments(segments: Vec<Segment>, language: Option<DetectedLanguage>) -> Self {
+        Self {
+            text: join_segments(&segments),
+            segments,
+            language,
+        }
+    }
+}
+
+/// Join segment texts into a single transcript
+pub fn join_segments(segments: &[Segment]) -> String {
+    let text: String = segments.iter().map(|s| s.text.as_str()).collect();
+    text.trim().to_string()
+}
+
+/// Render segments as SubRip subtitles
+pub fn to_srt(segments: &[Segment]) -> String {
+    let mut out = String::new();
+    for (i, seg) in cues(segments).enumerate() {
+        out.push_str(&format!(
+            "{}\n{} --> {}\n{}\n\n",
+            i + 1,
+            timestamp(seg.start_ms, ','),
+            timestamp(seg.end_ms, ','),
+            seg.text.trim()
+        ));
+    }
+    out
+}
+
+/// Render segments as WebVTT subtitles
+pub fn to_vtt(segments: &[Segment]) -> String {
+    let mut out = String::from("WEBVTT\n\n");
+    for seg in cues(segments) {
+        out.push_str(&format!(
+            "{} --> {}\n{}\n\n",
+            timestamp(seg.start_ms, '.'),
+            timestamp(seg.end_ms, '.'),
+            seg.text.trim()
+        ));
+    }
+    out
+}
+
+fn cues(segments: &[Segment]) -> impl Iterator<Item = &Segment> {
+    segments.iter().filter(|s| !s.text.trim().is_empty())
+}
+
+/// `HH:MM:SS,mmm` (SRT) or `HH:MM:SS.mmm` (VTT)
+fn timestamp(ms: i64, frac_sep: char) -> String {
+    let ms = ms.max(0);
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        frac_sep,
+        ms % 1000
+    )
+}
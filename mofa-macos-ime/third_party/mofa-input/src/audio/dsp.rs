@@ -0,0 +1,360 @@
+//! Preprocessing of 16kHz mono audio before ASR
+//!
+//! Three stages run in order, each switchable: a Butterworth high-pass that
+//! removes DC and rumble, spectral noise suppression against a noise profile
+//! taken from the start of the recording, and automatic gain control that
+//! brings speech to a target level with a limiter against clipping. The
+//! output always has the input's length.
+
+use std::f32::consts::PI;
+
+use super::vad::EnergyDetector;
+use crate::asr::features::fft;
+
+const SAMPLE_RATE: f32 = 16_000.0;
+/// STFT frame for noise suppression (32 ms), analysed at 50% overlap
+const FRAME: usize = 512;
+const HOP: usize = FRAME / 2;
+
+/// Preprocessing configuration
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DspConfig {
+    pub high_pass: bool,
+    pub high_pass_hz: f32,
+    pub noise_suppression: bool,
+    /// Leading audio used as the noise profile
+    pub noise_profile_ms: u32,
+    /// Multiple of the noise power subtracted from each bin
+    pub over_subtraction: f32,
+    /// Lowest gain applied to a bin, keeping some noise to avoid warbling
+    pub suppression_floor: f32,
+    pub agc: bool,
+    /// Speech RMS that AGC aims for
+    pub target_rms: f32,
+    pub max_gain: f32,
+    /// Peak level the limiter holds samples under
+    pub limit: f32,
+}
+
+impl Default for DspConfig {
+    fn default() -> Self {
+        Self {
+            high_pass: true,
+            high_pass_hz: 80.0,
+            noise_suppression: false,
+            noise_profile_ms: 300,
+            over_subtraction: 1.5,
+            suppression_floor: 0.1,
+            agc: true,
+            target_rms: 0.1,
+            max_gain: 20.0,
+            limit: 0.95,
+        }
+    }
+}
+
+/// Run the enabled stages over 16kHz mono audio
+pub fn preprocess(samples: &[f32], config: &DspConfig) -> Vec<f32> {
+    let mut out = samples.to_vec();
+    if config.high_pass {
+        high_pass(&mut out, config.high_pass_hz);
+    }
+    if config.noise_suppression {
+        out = suppress_noise(
+            &out,
+            config.noise_profile_ms,
+            config.over_subtraction,
+            config.suppression_floor,
+        );
+    }
+    if config.agc {
+        auto_gain(&mut out, config.target_rms, config.max_gain);
+        limit(&mut out, config.limit);
+    }
+    out
+}
+
+/// Second-order Butterworth high-pass (RBJ biquad) at `cutoff_hz`
+pub fn high_pass(samples: &mut [f32], cutoff_hz: f32) {
+    let w0 = 2.0 * PI * cutoff_hz.clamp(1.0, SAMPLE_RATE / 2.0 - 1.0) / SAMPLE_RATE;
+    let alpha = w0.sin() / (2.0 * std::f32::consts::FRAC_1_SQRT_2);
+    let cos = w0.cos();
+    let a0 = 1.0 + alpha;
+    let b0 = (1.0 + cos) / 2.0 / a0;
+    let b1 = -(1.0 + cos) / a0;
+    let b2 = b0;
+    let a1 = -2.0 * cos / a0;
+    let a2 = (1.0 - alpha) / a0;
+
+    let (mut x1, mut x2, mut y1, mut y2) = (0.0f32, 0.0f32, 0.0f32, 0.0f32);
+    for s in samples {
+        let x = *s;
+        let y = b0 * x + b1 * x1 + b2 * x2 - a1 * y1 - a2 * y2;
+        x2 = x1;
+        x1 = x;
+        y2 = y1;
+        y1 = y;
+        *s = y;
+    }
+}
+
+/// Spectral subtraction with the noise spectrum averaged over the first
+/// `profile_ms`; when that stretch is not quiet (speech from the first
+/// instant) the quietest frames of the clip are used instead
+pub fn suppress_noise(
+    samples: &[f32],
+    profile_ms: u32,
+    over_subtraction: f32,
+    floor: f32,
+) -> Vec<f32> {
+    if samples.len() < FRAME {
+        return samples.to_vec();
+    }
+    // sqrt-Hann on analysis and synthesis sums to one at 50% overlap
+    let window: Vec<f32> = (0..FRAME)
+        .map(|i| (0.5 - 0.5 * (2.0 * PI * i as f32 / FRAME as f32).cos()).sqrt())
+        .collect();
+
+    // Pad so every sample is covered by two frames
+    let mut padded = vec![0.0f32; HOP];
+    padded.extend_from_slice(samples);
+    padded.resize((padded.len().div_ceil(HOP) + 1) * HOP, 0.0);
+
+    let spectra: Vec<(Vec<f32>, Vec<f32>)> = (0..=(padded.len() - FRAME) / HOP)
+        .map(|f| {
+            let mut re: Vec<f32> = padded[f * HOP..f * HOP + FRAME]
+                .iter()
+                .zip(&window)
+                .map(|(s, w)| s * w)
+                .collect();
+            let mut im = vec![0.0f32; FRAME];
+            fft(&mut re, &mut im);
+            (re, im)
+        })
+        .collect();
+    let power: Vec<Vec<f32>> = spectra
+        .iter()
+        .map(|(re, im)| re.iter().zip(im).map(|(r, i)| r * r + i * i).collect())
+        .collect();
+    let noise = noise_profile(&power, profile_ms);
+
+    let mut out = vec![0.0f32; padded.len()];
+    let mut prev_gain = vec![1.0f32; FRAME];
+    for (f, ((mut re, mut im), frame_power)) in spectra.into_iter().zip(&power).enumerate() {
+        for k in 0..FRAME {
+            let gain = if frame_power[k] > 0.0 {
+                (1.0 - over_subtraction * noise[k] / frame_power[k])
+                    .max(floor * floor)
+                    .sqrt()
+            } else {
+                floor
+            };
+            // Smoothing over time tames isolated bins flickering on and off
+            let gain = 0.5 * gain + 0.5 * prev_gain[k].min(gain.max(floor) * 2.0);
+            prev_gain[k] = gain;
+            // Conjugate for the inverse transform
+            re[k] *= gain;
+            im[k] *= -gain;
+        }
+        fft(&mut re, &mut im);
+        for (i, (r, w)) in re.iter().zip(&window).enumerate() {
+            out[f * HOP + i] += r / FRAME as f32 * w;
+        }
+    }
+    out[HOP..HOP + samples.len()].to_vec()
+}
+
+/// Mean power per bin over the leading frames, or over the quietest frames
+/// when the start of the clip is not quiet
+fn noise_profile(power: &[Vec<f32>], profile_ms: u32) -> Vec<f32> {
+    let energy: Vec<f32> = power.iter().map(|p| p.iter().sum()).collect();
+    let count = ((profile_ms as f32 / 1000.0 * SAMPLE_RATE) as usize / HOP).clamp(1, power.len());
+
+    let mut by_energy: Vec<usize> = (0..power.len()).collect();
+    by_energy.sort_by(|&a, &b| energy[a].total_cmp(&energy[b]));
+    let quiet_level = energy[by_energy[by_energy.len() / 10]];
+    let lead_level = energy[..count].iter().sum::<f32>() / count as f32;
+    let frames: Vec<usize> = if lead_level <= quiet_level * 2.0 {
+        (0..count).collect()
+    } else {
+        by_energy[..count].to_vec()
+    };
+
+    let mut noise = vec![0.0f32; FRAME];
+    for &f in &frames {
+        for (n, p) in noise.iter_mut().zip(&power[f]) {
+            *n += p / frames.len() as f32;
+        }
+    }
+    noise
+}
+
+/// Scale so speech (per the energy VAD) has `target_rms`, by at most
+/// `max_gain`; clips without speech are left alone
+pub fn auto_gain(samples: &mut [f32], target_rms: f32, max_gain: f32) {
+    let detector = EnergyDetector::default();
+    let speech = detector.speech_frames(samples);
+    let (sum, count) = samples
+        .chunks(detector.frame_len)
+        .zip(&speech)
+        .filter(|(_, &is_speech)| is_speech)
+        .fold((0.0f64, 0usize), |(sum, count), (frame, _)| {
+            let energy: f64 = frame.iter().map(|&s| (s as f64) * (s as f64)).sum();
+            (sum + energy, count + frame.len())
+        });
+    if count == 0 {
+        return;
+    }
+    let speech_rms = (sum / count as f64).sqrt() as f32;
+    let gain = (target_rms / speech_rms.max(1e-6)).min(max_gain);
+    samples.iter_mut().for_each(|s| *s *= gain);
+}
+
+/// Keep peaks under `ceiling`: gain drops at once on a peak and recovers
+/// over about 50 ms
+pub fn limit(samples: &mut [f32], ceiling: f32) {
+    let release = 1.0 / (0.05 * SAMPLE_RATE);
+    let mut gain = 1.0f32;
+    for s in samples {
+        let needed = if s.abs() > ceiling {
+            ceiling / s.abs()
+        } else {
+            1.0
+        };
+        gain = needed.min(gain + release);
+        *s *= gain;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(freq: f32, amplitude: f32, secs: f32) -> Vec<f32> {
+        (0..(secs * SAMPLE_RATE) as usize)
+            .map(|i| amplitude * (2.0 * PI * freq * i as f32 / SAMPLE_RATE).sin())
+            .collect()
+    }
+
+    /// Deterministic white noise, uniform in ±`amplitude`
+    fn noise(amplitude: f32, secs: f32) -> Vec<f32> {
+        let mut state = 0x2545_f491_u32;
+        (0..(secs * SAMPLE_RATE) as usize)
+            .map(|_| {
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                amplitude * ((state >> 8) as f32 / (1 << 23) as f32 - 1.0)
+            })
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    /// Least-squares fit of a `freq` sinusoid; returns the fitted tone
+    fn fit_tone(samples: &[f32], freq: f32) -> Vec<f32> {
+        let w = 2.0 * PI * freq / SAMPLE_RATE;
+        let (mut sin, mut cos) = (0.0f32, 0.0f32);
+        for (i, s) in samples.iter().enumerate() {
+            sin += s * (w * i as f32).sin();
+            cos += s * (w * i as f32).cos();
+        }
+        let scale = 2.0 / samples.len() as f32;
+        (0..samples.len())
+            .map(|i| scale * (sin * (w * i as f32).sin() + cos * (w * i as f32).cos()))
+            .collect()
+    }
+
+    /// Tone-to-residual ratio in dB
+    fn snr_db(samples: &[f32], freq: f32) -> f32 {
+        let fitted = fit_tone(samples, freq);
+        let residual: Vec<f32> = samples.iter().zip(&fitted).map(|(s, t)| s - t).collect();
+        20.0 * (rms(&fitted) / rms(&residual)).log10()
+    }
+
+    #[test]
+    fn high_pass_removes_dc_and_rumble() {
+        let speech = tone(1000.0, 0.1, 1.0);
+        let mut samples: Vec<f32> = speech.iter().map(|s| s + 0.3).collect();
+        high_pass(&mut samples, 80.0);
+        // Past the filter's settling time
+        let settled = &samples[1600..];
+        let mean = settled.iter().sum::<f32>() / settled.len() as f32;
+        assert!(mean.abs() < 1e-3, "{mean}");
+        assert!((rms(settled) / rms(&speech[1600..]) - 1.0).abs() < 0.01);
+
+        let mut rumble = tone(20.0, 0.3, 1.0);
+        high_pass(&mut rumble, 80.0);
+        let attenuation = 20.0 * (rms(&rumble[1600..]) / rms(&tone(20.0, 0.3, 0.9))).log10();
+        assert!(attenuation < -20.0, "{attenuation} dB");
+    }
+
+    #[test]
+    fn noise_suppression_raises_snr() {
+        // Noise alone first, as the profile
+        let noisy = noise(0.02, 2.5);
+        let mut samples = noisy.clone();
+        for (s, t) in samples[8000..].iter_mut().zip(tone(1000.0, 0.1, 2.0)) {
+            *s += t;
+        }
+        let out = suppress_noise(&samples, 300, 1.5, 0.1);
+        assert_eq!(out.len(), samples.len());
+
+        let before = snr_db(&samples[12_000..36_000], 1000.0);
+        let after = snr_db(&out[12_000..36_000], 1000.0);
+        assert!(after - before > 6.0, "{before:.1} dB -> {after:.1} dB");
+
+        // The lead-in is noise only and is pushed towards the floor
+        let leading = rms(&out[1000..7000]) / rms(&samples[1000..7000]);
+        assert!(leading < 0.5, "{leading}");
+    }
+
+    #[test]
+    fn auto_gain_brings_speech_to_target() {
+        let mut samples = [vec![0.0; 8000], tone(300.0, 0.02, 1.0), vec![0.0; 8000]].concat();
+        auto_gain(&mut samples, 0.1, 20.0);
+        let level = rms(&samples[8000..24_000]);
+        assert!((level - 0.1).abs() < 0.005, "{level}");
+
+        // Quiet speech is boosted by at most max_gain
+        let mut quiet = [vec![0.0; 8000], tone(300.0, 0.003, 1.0)].concat();
+        auto_gain(&mut quiet, 0.1, 20.0);
+        let level = rms(&quiet[8000..]);
+        assert!((level - 0.003 / 2f32.sqrt() * 20.0).abs() < 1e-3, "{level}");
+
+        // Silence is left alone
+        let mut silence = vec![0.0005; 16_000];
+        auto_gain(&mut silence, 0.1, 20.0);
+        assert!(silence.iter().all(|&s| s == 0.0005));
+    }
+
+    #[test]
+    fn limiter_holds_peaks_under_ceiling_and_recovers() {
+        let mut samples = [tone(300.0, 2.0, 0.2), tone(300.0, 0.5, 0.5)].concat();
+        limit(&mut samples, 0.95);
+        let peak = samples.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        assert!(peak <= 0.95 + 1e-6, "{peak}");
+
+        // 50 ms of release later the quiet part passes unchanged
+        let tail = &samples[(0.2 * SAMPLE_RATE) as usize + 1600..];
+        let expected = &tone(300.0, 0.5, 0.7)[(0.2 * SAMPLE_RATE) as usize + 1600..];
+        assert!(tail.iter().zip(expected).all(|(a, b)| (a - b).abs() < 1e-4));
+    }
+
+    #[test]
+    fn preprocess_hits_target_without_clipping() {
+        let samples = [noise(0.005, 0.5), tone(300.0, 0.01, 1.0), noise(0.005, 0.5)].concat();
+        let config = DspConfig {
+            noise_suppression: true,
+            // Sine peaks above the ceiling, so the limiter has work to do
+            target_rms: 0.8,
+            max_gain: 200.0,
+            ..Default::default()
+        };
+        let out = preprocess(&samples, &config);
+        assert_eq!(out.len(), samples.len());
+        let peak = out.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        assert!(peak <= config.limit + 1e-6, "{peak}");
+        assert!(rms(&out[9600..22_400]) > 0.5);
+    }
+}
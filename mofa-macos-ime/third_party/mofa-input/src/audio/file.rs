@@ -0,0 +1,178 @@
+//! Audio file decoding for batch transcription
+//!
+//! WAV is read with hound; FLAC, MP3, Ogg/Vorbis, M4A (AAC/ALAC) and other
+//! compressed formats go through symphonia, so no system codecs are needed.
+
+use std::path::Path;
+
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL, CODEC_TYPE_OPUS};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use crate::asr::audio::resample_to_16khz;
+
+/// Decoded PCM with interleaved channels
+#[derive(Clone, Debug)]
+pub struct DecodedAudio {
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+impl DecodedAudio {
+    /// Duration in seconds
+    pub fn duration_secs(&self) -> f64 {
+        let frames = self.samples.len() / self.channels.max(1) as usize;
+        frames as f64 / self.sample_rate.max(1) as f64
+    }
+
+    /// Downmix and resample to 16kHz mono, the format ASR expects
+    pub fn into_asr_input(self) -> anyhow::Result<Vec<f32>> {
+        let mono = downmix(&self.samples, self.channels);
+        resample_to_16khz(&mono, self.sample_rate)
+    }
+}
+
+/// Decode a WAV file (integer PCM of any width, or 32-bit float)
+pub fn decode_wav(path: &Path) -> anyhow::Result<DecodedAudio> {
+    let mut reader = hound::WavReader::open(path)
+        .map_err(|e| anyhow::anyhow!("Failed to open {}: {}", path.display(), e))?;
+    let spec = reader.spec();
+    let samples = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<Vec<_>, _>>()?,
+        hound::SampleFormat::Int => {
+            let scale = 1.0 / (1u64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|s| s as f32 * scale))
+                .collect::<Result<Vec<_>, _>>()?
+        }
+    };
+    Ok(DecodedAudio {
+        samples,
+        sample_rate: spec.sample_rate,
+        channels: spec.channels,
+    })
+}
+
+/// Decode any supported audio file, picking the decoder from the extension
+/// and falling back to content probing
+pub fn decode_file(path: &Path) -> anyhow::Result<DecodedAudio> {
+    let is_wav = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("wav"));
+    if is_wav {
+        // hound only reads plain PCM; let symphonia try ADPCM and friends
+        if let Ok(audio) = decode_wav(path) {
+            return Ok(audio);
+        }
+    }
+    decode_compressed(path)
+}
+
+/// Decode a compressed audio file with symphonia
+pub fn decode_compressed(path: &Path) -> anyhow::Result<DecodedAudio> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| anyhow::anyhow!("Failed to open {}: {}", path.display(), e))?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(ext);
+    }
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| anyhow::anyhow!("Unsupported audio file {}: {}", path.display(), e))?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| anyhow::anyhow!("No audio track in {}", path.display()))?;
+    if track.codec_params.codec == CODEC_TYPE_OPUS {
+        return Err(anyhow::anyhow!(
+            "Opus audio is not supported: {} (convert it to FLAC or WAV first)",
+            path.display()
+        ));
+    }
+    let track_id = track.id;
+    let mut sample_rate = track.codec_params.sample_rate.unwrap_or(0);
+    let mut channels = track.codec_params.channels.map(|c| c.count()).unwrap_or(0);
+    let mut decoder =
+        symphonia::default::get_codecs().make(&track.codec_params, &DecoderOptions::default())?;
+
+    let mut samples = Vec::new();
+    let mut buffer: Option<SampleBuffer<f32>> = None;
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break
+            }
+            Err(SymphoniaError::ResetRequired) => break,
+            Err(e) => return Err(e.into()),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A corrupt packet costs a few milliseconds of audio, not the file
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(e) => return Err(e.into()),
+        };
+        let spec = *decoded.spec();
+        if channels != 0 && spec.channels.count() != channels {
+            return Err(anyhow::anyhow!(
+                "Channel layout changes mid-stream in {}",
+                path.display()
+            ));
+        }
+        sample_rate = spec.rate;
+        channels = spec.channels.count();
+
+        let needed = decoded.capacity() * channels;
+        let buf = match &mut buffer {
+            Some(buf) if buf.capacity() >= needed => buf,
+            _ => buffer.insert(SampleBuffer::new(decoded.capacity() as u64, spec)),
+        };
+        buf.copy_interleaved_ref(decoded);
+        samples.extend_from_slice(buf.samples());
+    }
+
+    if sample_rate == 0 || channels == 0 {
+        return Err(anyhow::anyhow!("No decodable audio in {}", path.display()));
+    }
+    Ok(DecodedAudio {
+        samples,
+        sample_rate,
+        channels: channels as u16,
+    })
+}
+
+/// Load an audio file as 16kHz mono f32
+pub fn load_for_asr(path: &Path) -> anyhow::Result<Vec<f32>> {
+    decode_file(path)?.into_asr_input()
+}
+
+/// Average interleaved channels into mono
+pub fn downmix(samples: &[f32], channels: u16) -> Vec<f32> {
+    let channels = channels.max(1) as usize;
+    if channels == 1 {
+        return samples.to_vec();
+    }
+    samples
+        .chunks_exact(channels)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect()
+}
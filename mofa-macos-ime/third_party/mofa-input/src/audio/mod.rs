@@ -1,21 +1,19 @@
 // Audio recording and processing
 
-pub fn list_devices() -> Vec<String> {
-    vec![]
-}
+pub mod dsp;
+pub mod file;
+pub mod recorder;
+pub mod resample;
+pub mod ring;
+pub mod source;
+pub mod vad;
 
-pub struct Recorder;
-
-impl Recorder {
-    pub fn new() -> Self {
-        Self
-    }
-
-    pub fn start(&mut self) {
-        // TODO
-    }
-
-    pub fn stop(&mut self) -> Vec<f32> {
-        vec![]
-    }
-}
+pub use dsp::{preprocess, DspConfig};
+pub use file::{decode_compressed, decode_file, decode_wav, downmix, load_for_asr, DecodedAudio};
+pub use recorder::{CaptureStats, Recorder, SampleBuffer, ASR_SAMPLE_RATE, DEFAULT_MAX_DURATION};
+pub use resample::{resample, ResampleQuality, Resampler};
+pub use ring::{ring_buffer, Consumer, Producer};
+pub use source::{default_device, list_devices, AudioSource, FileSource, MicrophoneSource};
+pub use vad::{
+    Endpointer, EnergyDetector, SileroDetector, SpeechDetector, SpeechRegion, Vad, VadConfig,
+};
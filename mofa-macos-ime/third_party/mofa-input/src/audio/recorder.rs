@@ -0,0 +1,408 @@
+//! Recording to 16kHz mono for ASR
+//!
+//! Sources push samples into a lock-free ring buffer from their real-time
+//! callback. A collector thread drains it every 10 ms into the recording
+//! buffer that previews read, enforcing the length limit and, when warm,
+//! trimming idle audio to the pre-roll.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use super::resample::{resample, ResampleQuality};
+use super::ring::{ring_buffer, Consumer};
+use super::source::{AudioSource, MicrophoneSource};
+
+/// Sample rate of `Recorder::stop` output
+pub const ASR_SAMPLE_RATE: u32 = 16000;
+
+/// Default cap on a single recording
+pub const DEFAULT_MAX_DURATION: Duration = Duration::from_secs(600);
+
+/// Ring capacity in samples, about 5 s at 48kHz
+const RING_CAPACITY: usize = 1 << 18;
+const COLLECT_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Mono samples at the source rate, appended as they are captured
+pub type SampleBuffer = Arc<Mutex<Vec<f32>>>;
+
+/// Samples lost during the current recording, for diagnostics
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CaptureStats {
+    /// Dropped by the audio callback because the ring buffer was full
+    pub overflowed: u64,
+    /// Discarded after the maximum duration was reached
+    pub truncated: u64,
+}
+
+/// Records from an `AudioSource` and returns 16kHz mono audio
+///
+/// The microphone is the default source; any `AudioSource` can replace it.
+pub struct Recorder {
+    source: Box<dyn AudioSource>,
+    samples: SampleBuffer,
+    quality: ResampleQuality,
+    max_duration: Option<Duration>,
+    recording: bool,
+    warm: bool,
+    state: Arc<CaptureState>,
+    capture: Option<Capture>,
+}
+
+/// Shared between the recorder and its collector thread
+#[derive(Default)]
+struct CaptureState {
+    recording: AtomicBool,
+    /// Samples kept while idle and warm
+    keep: AtomicUsize,
+    /// Most samples one recording holds
+    max_samples: AtomicUsize,
+    limit_reached: AtomicBool,
+    overflowed: AtomicU64,
+    truncated: AtomicU64,
+}
+
+impl CaptureState {
+    /// Begin a recording of at most `max_samples`
+    fn reset(&self, max_samples: usize) {
+        self.max_samples.store(max_samples, Ordering::SeqCst);
+        self.limit_reached.store(false, Ordering::SeqCst);
+        self.overflowed.store(0, Ordering::SeqCst);
+        self.truncated.store(0, Ordering::SeqCst);
+        self.recording.store(true, Ordering::SeqCst);
+    }
+}
+
+/// A running source: the consumer end of its ring and the thread draining it
+struct Capture {
+    consumer: Arc<Mutex<Consumer>>,
+    stop: Arc<AtomicBool>,
+    collector: Option<JoinHandle<()>>,
+}
+
+impl Drop for Capture {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(collector) = self.collector.take() {
+            let _ = collector.join();
+        }
+    }
+}
+
+impl Recorder {
+    /// Record from the system default input device
+    pub fn new() -> Self {
+        Self::with_source(MicrophoneSource::new())
+    }
+
+    /// Record from the input device named `name`
+    pub fn with_device(name: &str) -> Self {
+        Self::with_source(MicrophoneSource::with_device(name))
+    }
+
+    pub fn with_source(source: impl AudioSource + 'static) -> Self {
+        Self {
+            source: Box::new(source),
+            samples: Arc::new(Mutex::new(Vec::new())),
+            quality: ResampleQuality::default(),
+            max_duration: Some(DEFAULT_MAX_DURATION),
+            recording: false,
+            warm: false,
+            state: Arc::new(CaptureState::default()),
+            capture: None,
+        }
+    }
+
+    /// Resampling quality used by `stop`
+    pub fn set_quality(&mut self, quality: ResampleQuality) {
+        self.quality = quality;
+    }
+
+    /// Longest recording kept, from the next `start`; later audio is
+    /// discarded and counted in `stats`. `None` removes the cap
+    pub fn set_max_duration(&mut self, max: Option<Duration>) {
+        self.max_duration = max;
+    }
+
+    /// Keep the source running while idle so each recording begins with
+    /// the last `pre_roll_ms` captured before `start`
+    ///
+    /// Idle audio stays in memory only and is trimmed every 10 ms.
+    /// Calling again changes the length.
+    pub fn warm_up(&mut self, pre_roll_ms: u32) -> anyhow::Result<()> {
+        if self.recording {
+            return Err(anyhow::anyhow!("Cannot warm up while recording"));
+        }
+        if !self.warm {
+            self.lock_samples()?.clear();
+            self.open_capture()?;
+            self.warm = true;
+        }
+        let keep = self.source.sample_rate() as usize * pre_roll_ms as usize / 1000;
+        self.state.keep.store(keep, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Stop the idle capture started by `warm_up` and drop its audio; a
+    /// recording in progress continues
+    pub fn cool_down(&mut self) {
+        if !std::mem::take(&mut self.warm) || self.recording {
+            return;
+        }
+        self.close_capture();
+        if let Ok(mut buf) = self.samples.lock() {
+            buf.clear();
+        }
+    }
+
+    /// Whether the source runs between recordings
+    pub fn is_warm(&self) -> bool {
+        self.warm
+    }
+
+    /// Start a new recording, discarding the previous one
+    pub fn start(&mut self) -> anyhow::Result<()> {
+        if self.recording && !self.warm {
+            self.close_capture();
+        }
+        let restart = std::mem::replace(&mut self.recording, false);
+
+        if self.capture.is_none() {
+            // Marked as recording first so the collector never trims; the
+            // cap is set again once the source knows its real rate
+            self.state.reset(self.max_samples());
+            self.lock_samples()?.clear();
+            if let Err(e) = self.open_capture() {
+                self.state.recording.store(false, Ordering::SeqCst);
+                return Err(e);
+            }
+            self.state
+                .max_samples
+                .store(self.max_samples(), Ordering::SeqCst);
+        } else {
+            // Warm: the buffer holds the pre-roll, or a recording to discard
+            let mut buf = self.lock_samples()?;
+            if restart {
+                buf.clear();
+            } else {
+                trim_to(&mut buf, self.state.keep.load(Ordering::SeqCst));
+            }
+            self.state.reset(self.max_samples());
+        }
+        self.recording = true;
+        Ok(())
+    }
+
+    /// Stop recording and return the audio as 16kHz mono
+    pub fn stop(&mut self) -> anyhow::Result<Vec<f32>> {
+        if !self.recording {
+            return Ok(Vec::new());
+        }
+        self.recording = false;
+        if self.warm {
+            if let Some(capture) = &self.capture {
+                collect(&capture.consumer, &self.samples, &self.state);
+            }
+        } else {
+            self.close_capture();
+        }
+        let raw = {
+            let mut buf = self.lock_samples()?;
+            self.state.recording.store(false, Ordering::SeqCst);
+            std::mem::take(&mut *buf)
+        };
+        resample(
+            &raw,
+            self.source.sample_rate(),
+            ASR_SAMPLE_RATE,
+            self.quality,
+        )
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    /// Whether the current recording hit the maximum duration
+    pub fn limit_reached(&self) -> bool {
+        self.recording && self.state.limit_reached.load(Ordering::SeqCst)
+    }
+
+    /// Samples lost since the last `start`
+    pub fn stats(&self) -> CaptureStats {
+        CaptureStats {
+            overflowed: self.state.overflowed.load(Ordering::SeqCst),
+            truncated: self.state.truncated.load(Ordering::SeqCst),
+        }
+    }
+
+    /// Rate of the samples in `sample_buffer`
+    pub fn sample_rate(&self) -> u32 {
+        self.source.sample_rate()
+    }
+
+    /// Live buffer of samples captured so far at the source rate, for
+    /// previews while recording
+    pub fn sample_buffer(&self) -> SampleBuffer {
+        Arc::clone(&self.samples)
+    }
+
+    fn max_samples(&self) -> usize {
+        self.max_duration.map_or(usize::MAX, |max| {
+            (max.as_secs_f64() * self.source.sample_rate() as f64) as usize
+        })
+    }
+
+    /// Start the source feeding a fresh ring and a collector draining it
+    fn open_capture(&mut self) -> anyhow::Result<()> {
+        let (producer, consumer) = ring_buffer(RING_CAPACITY);
+        let consumer = Arc::new(Mutex::new(consumer));
+        let stop = Arc::new(AtomicBool::new(false));
+        // Running before the source starts, since a file source waits for room
+        let collector = {
+            let (consumer, stop) = (Arc::clone(&consumer), Arc::clone(&stop));
+            let (samples, state) = (Arc::clone(&self.samples), Arc::clone(&self.state));
+            std::thread::spawn(move || {
+                while !stop.load(Ordering::SeqCst) {
+                    std::thread::sleep(COLLECT_INTERVAL);
+                    collect(&consumer, &samples, &state);
+                }
+            })
+        };
+        let capture = Capture {
+            consumer,
+            stop,
+            collector: Some(collector),
+        };
+        self.source.start(producer)?;
+        self.capture = Some(capture);
+        Ok(())
+    }
+
+    /// Stop the source and move what is left in the ring to the buffer
+    fn close_capture(&mut self) {
+        self.source.stop();
+        if let Some(capture) = self.capture.take() {
+            collect(&capture.consumer, &self.samples, &self.state);
+        }
+    }
+
+    fn lock_samples(&self) -> anyhow::Result<std::sync::MutexGuard<'_, Vec<f32>>> {
+        self.samples
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Sample buffer poisoned"))
+    }
+}
+
+/// Drain the ring into `samples`, keeping at most the pre-roll while idle
+/// and at most the length limit while recording
+fn collect(consumer: &Mutex<Consumer>, samples: &SampleBuffer, state: &CaptureState) {
+    let (Ok(mut consumer), Ok(mut buf)) = (consumer.lock(), samples.lock()) else {
+        return;
+    };
+    let overflowed = consumer.take_dropped();
+    if !state.recording.load(Ordering::SeqCst) {
+        consumer.pop_into(&mut buf, usize::MAX);
+        trim_to(&mut buf, state.keep.load(Ordering::SeqCst));
+        return;
+    }
+    state.overflowed.fetch_add(overflowed, Ordering::SeqCst);
+    let room = state
+        .max_samples
+        .load(Ordering::SeqCst)
+        .saturating_sub(buf.len());
+    consumer.pop_into(&mut buf, room);
+    let truncated = consumer.skip_all();
+    if truncated > 0 {
+        state
+            .truncated
+            .fetch_add(truncated as u64, Ordering::SeqCst);
+        state.limit_reached.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Drop all but the newest `keep` samples
+fn trim_to(buf: &mut Vec<f32>, keep: usize) {
+    if buf.len() > keep {
+        let excess = buf.len() - keep;
+        buf.drain(..excess);
+    }
+}
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        if self.capture.is_some() {
+            self.source.stop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::FileSource;
+
+    /// One second of a constant level at 48kHz
+    fn source() -> FileSource {
+        FileSource::from_samples(vec![0.5; 48_000], 48_000)
+    }
+
+    #[test]
+    fn records_a_file_as_16khz() {
+        let mut recorder = Recorder::with_source(source());
+        recorder.start().unwrap();
+        assert!(recorder.is_recording());
+        let audio = recorder.stop().unwrap();
+        assert!(!recorder.is_recording());
+        assert_eq!(audio.len(), 16_000);
+        assert!(audio[1000..15_000].iter().all(|s| (s - 0.5).abs() < 1e-3));
+        assert_eq!(recorder.stats(), CaptureStats::default());
+        assert!(recorder.stop().unwrap().is_empty());
+    }
+
+    #[test]
+    fn max_duration_truncates_and_counts() {
+        let mut recorder = Recorder::with_source(source());
+        recorder.set_max_duration(Some(Duration::from_millis(250)));
+        recorder.start().unwrap();
+        let audio = recorder.stop().unwrap();
+        assert_eq!(audio.len(), 4000);
+        assert_eq!(
+            recorder.stats(),
+            CaptureStats {
+                overflowed: 0,
+                truncated: 36_000,
+            }
+        );
+
+        // The next recording starts with fresh counters
+        recorder.set_max_duration(None);
+        recorder.start().unwrap();
+        assert_eq!(recorder.stop().unwrap().len(), 16_000);
+        assert_eq!(recorder.stats(), CaptureStats::default());
+    }
+
+    #[test]
+    fn warm_recorder_keeps_only_the_pre_roll() {
+        let mut recorder = Recorder::with_source(source());
+        recorder.warm_up(100).unwrap();
+        assert!(recorder.is_warm());
+        // Let the collector drain the file and trim it
+        std::thread::sleep(COLLECT_INTERVAL * 10);
+        assert_eq!(recorder.sample_buffer().lock().unwrap().len(), 4800);
+
+        recorder.start().unwrap();
+        assert_eq!(recorder.stop().unwrap().len(), 1600);
+        assert!(recorder.is_warm());
+        recorder.cool_down();
+        assert!(!recorder.is_warm());
+        assert!(recorder.sample_buffer().lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,293 @@
+//! Band-limited resampling
+//!
+//! Polyphase windowed-sinc (Kaiser) interpolation. The cutoff sits just below
+//! the lower of the two Nyquist frequencies, so downsampling 48kHz microphone
+//! input removes what linear interpolation would alias into the speech band.
+
+use std::f64::consts::PI;
+
+/// Resampler quality preset, trading filter length for speed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ResampleQuality {
+    /// Short filter for live previews
+    Fast,
+    /// Good stopband for ASR input
+    #[default]
+    Balanced,
+    /// Long filter for offline conversion
+    Best,
+}
+
+impl ResampleQuality {
+    /// (zero crossings per side, Kaiser beta, cutoff as a fraction of Nyquist)
+    fn design(self) -> (usize, f64, f64) {
+        match self {
+            ResampleQuality::Fast => (8, 6.0, 0.90),
+            ResampleQuality::Balanced => (16, 8.6, 0.94),
+            ResampleQuality::Best => (32, 10.0, 0.97),
+        }
+    }
+}
+
+/// Resampler between two fixed rates with a precomputed filter bank
+#[derive(Clone, Debug)]
+pub struct Resampler {
+    /// Output samples per `down` input samples, after dividing out the gcd
+    up: usize,
+    down: usize,
+    /// Input samples before the interpolation point covered by each phase
+    half_len: usize,
+    /// One filter per output phase, `2 * half_len` taps each
+    phases: Vec<Vec<f32>>,
+}
+
+impl Resampler {
+    pub fn new(from_rate: u32, to_rate: u32, quality: ResampleQuality) -> anyhow::Result<Self> {
+        if from_rate == 0 || to_rate == 0 {
+            return Err(anyhow::anyhow!(
+                "Invalid sample rate: {} Hz to {} Hz",
+                from_rate,
+                to_rate
+            ));
+        }
+        let (from_rate, to_rate) = (from_rate as usize, to_rate as usize);
+        let g = gcd(from_rate, to_rate);
+        let (up, down) = (to_rate / g, from_rate / g);
+
+        let (zero_crossings, beta, rolloff) = quality.design();
+        // Below 1 when downsampling: the filter widens to cut at the new Nyquist
+        let scale = (up as f64 / down as f64).min(1.0);
+        let cutoff = scale * rolloff;
+        let half_len = (zero_crossings as f64 / scale).ceil() as usize;
+        let norm = bessel_i0(beta);
+
+        let phases = (0..up)
+            .map(|phase| {
+                let frac = phase as f64 / up as f64;
+                let mut taps: Vec<f64> = (0..2 * half_len)
+                    .map(|k| {
+                        // Distance from the interpolation point to input tap k
+                        let x = k as f64 - (half_len as f64 - 1.0) - frac;
+                        let r = x / half_len as f64;
+                        if r.abs() >= 1.0 {
+                            return 0.0;
+                        }
+                        let window = bessel_i0(beta * (1.0 - r * r).sqrt()) / norm;
+                        cutoff * sinc(cutoff * x) * window
+                    })
+                    .collect();
+                // Unity gain at DC for every phase
+                let sum: f64 = taps.iter().sum();
+                if sum.abs() > f64::EPSILON {
+                    taps.iter_mut().for_each(|t| *t /= sum);
+                }
+                taps.into_iter().map(|t| t as f32).collect()
+            })
+            .collect();
+
+        Ok(Self {
+            up,
+            down,
+            half_len,
+            phases,
+        })
+    }
+
+    /// Output length for `input_len` input samples
+    pub fn output_len(&self, input_len: usize) -> usize {
+        input_len * self.up / self.down
+    }
+
+    /// Resample a whole signal; samples beyond either end count as silence
+    pub fn process(&self, samples: &[f32]) -> Vec<f32> {
+        if self.up == self.down {
+            return samples.to_vec();
+        }
+        (0..self.output_len(samples.len()))
+            .map(|n| self.output_at(samples, n))
+            .collect()
+    }
+
+    /// Resample more of a growing signal: the outputs after the first `done`
+    /// whose filter taps all lie inside `samples`
+    ///
+    /// Calling again once `samples` has grown continues exactly where this
+    /// call stopped, so live audio is converted only once.
+    pub fn process_tail(&self, samples: &[f32], done: usize) -> Vec<f32> {
+        if self.up == self.down {
+            return samples.get(done..).unwrap_or_default().to_vec();
+        }
+        // Output n reads inputs up to n * down / up + half_len
+        let settled = samples.len().saturating_sub(self.half_len);
+        let ready = (settled * self.up).div_ceil(self.down);
+        (done..ready).map(|n| self.output_at(samples, n)).collect()
+    }
+
+    fn output_at(&self, samples: &[f32], n: usize) -> f32 {
+        let len = samples.len() as isize;
+        let pos = n * self.down;
+        let (index, phase) = (pos / self.up, pos % self.up);
+        let first = index as isize + 1 - self.half_len as isize;
+        let taps = &self.phases[phase];
+        if first >= 0 && first + taps.len() as isize <= len {
+            let start = first as usize;
+            return taps
+                .iter()
+                .zip(&samples[start..start + taps.len()])
+                .map(|(t, s)| t * s)
+                .sum();
+        }
+        taps.iter()
+            .enumerate()
+            .filter_map(|(k, t)| {
+                let i = first + k as isize;
+                (0..len).contains(&i).then(|| t * samples[i as usize])
+            })
+            .sum()
+    }
+}
+
+/// Resample mono audio from `from_rate` to `to_rate`
+pub fn resample(
+    samples: &[f32],
+    from_rate: u32,
+    to_rate: u32,
+    quality: ResampleQuality,
+) -> anyhow::Result<Vec<f32>> {
+    Ok(Resampler::new(from_rate, to_rate, quality)?.process(samples))
+}
+
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-9 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+/// Zeroth-order modified Bessel function of the first kind
+fn bessel_i0(x: f64) -> f64 {
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let half = x / 2.0;
+    for k in 1..64 {
+        term *= (half / k as f64) * (half / k as f64);
+        sum += term;
+        if term < sum * 1e-12 {
+            break;
+        }
+    }
+    sum
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUALITIES: [ResampleQuality; 3] = [
+        ResampleQuality::Fast,
+        ResampleQuality::Balanced,
+        ResampleQuality::Best,
+    ];
+
+    fn tone(freq: f64, rate: u32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| (2.0 * PI * freq * i as f64 / rate as f64).sin() as f32)
+            .collect()
+    }
+
+    /// RMS away from the edges, where the filter reads past the signal
+    fn rms(samples: &[f32]) -> f64 {
+        let body = &samples[samples.len() / 4..samples.len() * 3 / 4];
+        (body.iter().map(|&s| s as f64 * s as f64).sum::<f64>() / body.len() as f64).sqrt()
+    }
+
+    fn gain_db(samples: &[f32]) -> f64 {
+        20.0 * (rms(samples) * 2f64.sqrt()).log10()
+    }
+
+    #[test]
+    fn passband_is_flat() {
+        for quality in QUALITIES {
+            for (from, to) in [(48_000, 16_000), (44_100, 16_000), (16_000, 48_000)] {
+                for freq in [300.0, 1000.0, 3000.0] {
+                    let out =
+                        resample(&tone(freq, from, from as usize), from, to, quality).unwrap();
+                    let gain = gain_db(&out);
+                    assert!(
+                        gain.abs() < 0.1,
+                        "{quality:?} {from}->{to} {freq} Hz: {gain:.3} dB"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn stopband_is_rejected() {
+        for (quality, floor) in [
+            (ResampleQuality::Fast, -60.0),
+            (ResampleQuality::Balanced, -90.0),
+            (ResampleQuality::Best, -110.0),
+        ] {
+            // Above the 8kHz output Nyquist; would alias into the speech band
+            for (from, freq) in [(48_000, 12_000.0), (44_100, 12_000.0), (48_000, 20_000.0)] {
+                let out =
+                    resample(&tone(freq, from, from as usize), from, 16_000, quality).unwrap();
+                let gain = gain_db(&out);
+                assert!(gain < floor, "{quality:?} {from} {freq} Hz: {gain:.1} dB");
+            }
+        }
+    }
+
+    #[test]
+    fn output_length_follows_rate_ratio() {
+        for quality in QUALITIES {
+            for (from, to, input, expected) in [
+                (48_000, 16_000, 48_000, 16_000),
+                (48_000, 16_000, 1001, 333),
+                (44_100, 16_000, 44_100, 16_000),
+                (22_050, 16_000, 441, 320),
+                (16_000, 48_000, 100, 300),
+                (16_000, 16_000, 123, 123),
+            ] {
+                let resampler = Resampler::new(from, to, quality).unwrap();
+                assert_eq!(resampler.output_len(input), expected);
+                let out = resample(&vec![0.5; input], from, to, quality).unwrap();
+                assert_eq!(out.len(), expected, "{quality:?} {from}->{to}");
+            }
+        }
+    }
+
+    #[test]
+    fn process_tail_matches_process() {
+        let input = tone(440.0, 44_100, 10_000);
+        for quality in QUALITIES {
+            let resampler = Resampler::new(44_100, 16_000, quality).unwrap();
+            let mut out = Vec::new();
+            for end in (0..input.len()).step_by(777).chain([input.len()]) {
+                out.extend(resampler.process_tail(&input[..end], out.len()));
+            }
+            let whole = resampler.process(&input);
+            // Only the outputs whose taps run past the end are held back
+            let held = whole.len() - out.len();
+            assert!(held <= resampler.half_len * 16_000 / 44_100 + 1, "{held}");
+            assert_eq!(out[..], whole[..out.len()]);
+        }
+    }
+
+    #[test]
+    fn zero_rate_is_an_error() {
+        assert!(Resampler::new(0, 16_000, ResampleQuality::Fast).is_err());
+        assert!(Resampler::new(16_000, 0, ResampleQuality::Fast).is_err());
+        assert!(resample(&[0.0; 10], 0, 16_000, ResampleQuality::Fast).is_err());
+    }
+}
@@ -0,0 +1,226 @@
+//! Lock-free single-producer single-consumer ring buffer for samples
+//!
+//! The producer never blocks or allocates, so it can run inside a real-time
+//! audio callback. Samples that do not fit are dropped and counted rather
+//! than waited for.
+
+use std::cell::UnsafeCell;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Create a ring holding at least `capacity` samples
+pub fn ring_buffer(capacity: usize) -> (Producer, Consumer) {
+    let capacity = capacity.max(2).next_power_of_two();
+    let shared = Arc::new(Shared {
+        slots: (0..capacity).map(|_| UnsafeCell::new(0.0)).collect(),
+        mask: capacity - 1,
+        head: AtomicUsize::new(0),
+        tail: AtomicUsize::new(0),
+        dropped: AtomicU64::new(0),
+    });
+    (
+        Producer {
+            shared: Arc::clone(&shared),
+        },
+        Consumer { shared },
+    )
+}
+
+struct Shared {
+    slots: Box<[UnsafeCell<f32>]>,
+    mask: usize,
+    /// Total samples read; only the consumer stores it
+    head: AtomicUsize,
+    /// Total samples written; only the producer stores it
+    tail: AtomicUsize,
+    dropped: AtomicU64,
+}
+
+// SAFETY: the producer only writes slots in `tail..head + capacity` and the
+// consumer only reads slots in `head..tail`. Each side publishes its index
+// with Release after touching the slots and the other side loads it with
+// Acquire, so no slot is accessed by both at once.
+unsafe impl Sync for Shared {}
+
+impl Shared {
+    fn len(&self) -> usize {
+        let tail = self.tail.load(Ordering::Acquire);
+        let head = self.head.load(Ordering::Acquire);
+        tail.wrapping_sub(head)
+    }
+}
+
+/// Writing end, owned by the audio callback
+pub struct Producer {
+    shared: Arc<Shared>,
+}
+
+impl Producer {
+    /// Write what fits and count the rest as dropped; never blocks
+    pub fn push_lossy(&mut self, samples: impl IntoIterator<Item = f32>) -> usize {
+        let shared = &*self.shared;
+        let tail = shared.tail.load(Ordering::Relaxed);
+        let free = shared.slots.len() - tail.wrapping_sub(shared.head.load(Ordering::Acquire));
+        let mut written = 0;
+        let mut dropped = 0u64;
+        for sample in samples {
+            if written < free {
+                let slot = &shared.slots[tail.wrapping_add(written) & shared.mask];
+                // SAFETY: the slot is free, see `Shared`
+                unsafe { *slot.get() = sample };
+                written += 1;
+            } else {
+                dropped += 1;
+            }
+        }
+        shared
+            .tail
+            .store(tail.wrapping_add(written), Ordering::Release);
+        if dropped > 0 {
+            shared.dropped.fetch_add(dropped, Ordering::Relaxed);
+        }
+        written
+    }
+
+    /// Write the leading part of `samples` that fits, returning its length;
+    /// nothing is counted as dropped, so callers that may wait can retry
+    pub fn try_push_slice(&mut self, samples: &[f32]) -> usize {
+        let n = samples.len().min(self.free_len());
+        self.push_lossy(samples[..n].iter().copied())
+    }
+
+    /// Samples that can be written without dropping
+    pub fn free_len(&self) -> usize {
+        self.shared.slots.len() - self.shared.len()
+    }
+}
+
+/// Reading end, drained by a consumer thread
+pub struct Consumer {
+    shared: Arc<Shared>,
+}
+
+impl Consumer {
+    /// Move up to `max` samples to the end of `out`, returning how many
+    pub fn pop_into(&mut self, out: &mut Vec<f32>, max: usize) -> usize {
+        let shared = &*self.shared;
+        let head = shared.head.load(Ordering::Relaxed);
+        let n = shared
+            .tail
+            .load(Ordering::Acquire)
+            .wrapping_sub(head)
+            .min(max);
+        out.reserve(n);
+        for i in 0..n {
+            let slot = &shared.slots[head.wrapping_add(i) & shared.mask];
+            // SAFETY: the slot has been published, see `Shared`
+            out.push(unsafe { *slot.get() });
+        }
+        shared.head.store(head.wrapping_add(n), Ordering::Release);
+        n
+    }
+
+    /// Discard everything buffered, returning how many samples that was
+    pub fn skip_all(&mut self) -> usize {
+        let shared = &*self.shared;
+        let head = shared.head.load(Ordering::Relaxed);
+        let tail = shared.tail.load(Ordering::Acquire);
+        shared.head.store(tail, Ordering::Release);
+        tail.wrapping_sub(head)
+    }
+
+    pub fn len(&self) -> usize {
+        self.shared.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.shared.slots.len()
+    }
+
+    /// Samples the producer dropped since the last call
+    pub fn take_dropped(&mut self) -> u64 {
+        self.shared.dropped.swap(0, Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capacity_rounds_up_to_a_power_of_two() {
+        assert_eq!(ring_buffer(0).1.capacity(), 2);
+        assert_eq!(ring_buffer(5).1.capacity(), 8);
+        assert_eq!(ring_buffer(1024).1.capacity(), 1024);
+    }
+
+    #[test]
+    fn samples_come_out_in_order_across_the_wrap() {
+        let (mut producer, mut consumer) = ring_buffer(8);
+        let mut out = Vec::new();
+        assert_eq!(producer.push_lossy((0..5).map(|i| i as f32)), 5);
+        assert_eq!(consumer.pop_into(&mut out, 3), 3);
+        assert_eq!(producer.push_lossy((5..11).map(|i| i as f32)), 6);
+        assert_eq!(consumer.len(), 8);
+        assert_eq!(consumer.pop_into(&mut out, usize::MAX), 8);
+        assert!(consumer.is_empty());
+        assert_eq!(out, (0..11).map(|i| i as f32).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn lossy_push_counts_what_does_not_fit() {
+        let (mut producer, mut consumer) = ring_buffer(4);
+        assert_eq!(producer.push_lossy([1.0; 6]), 4);
+        assert_eq!(producer.push_lossy([2.0; 3]), 0);
+        assert_eq!(consumer.take_dropped(), 5);
+        assert_eq!(consumer.take_dropped(), 0);
+
+        let mut out = Vec::new();
+        consumer.pop_into(&mut out, usize::MAX);
+        assert_eq!(out, [1.0; 4]);
+    }
+
+    #[test]
+    fn try_push_writes_the_prefix_that_fits() {
+        let (mut producer, mut consumer) = ring_buffer(4);
+        assert_eq!(producer.try_push_slice(&[1.0, 2.0, 3.0]), 3);
+        assert_eq!(producer.free_len(), 1);
+        assert_eq!(producer.try_push_slice(&[4.0, 5.0]), 1);
+        assert_eq!(consumer.take_dropped(), 0);
+        assert_eq!(consumer.skip_all(), 4);
+        assert_eq!(producer.free_len(), 4);
+    }
+
+    #[test]
+    fn threads_see_every_sample_in_order() {
+        const TOTAL: usize = 200_000;
+        let (mut producer, mut consumer) = ring_buffer(256);
+        let writer = std::thread::spawn(move || {
+            let samples: Vec<f32> = (0..TOTAL).map(|i| (i % 65_536) as f32).collect();
+            let mut rest = &samples[..];
+            while !rest.is_empty() {
+                let n = producer.try_push_slice(&rest[..rest.len().min(100)]);
+                rest = &rest[n..];
+                if n == 0 {
+                    std::thread::yield_now();
+                }
+            }
+        });
+        let mut out = Vec::with_capacity(TOTAL);
+        while out.len() < TOTAL {
+            if consumer.pop_into(&mut out, 64) == 0 {
+                std::thread::yield_now();
+            }
+        }
+        writer.join().unwrap();
+        assert!(out
+            .iter()
+            .enumerate()
+            .all(|(i, &s)| s == (i % 65_536) as f32));
+        assert_eq!(consumer.take_dropped(), 0);
+    }
+}
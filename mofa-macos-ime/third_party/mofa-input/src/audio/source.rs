@@ -0,0 +1,231 @@
+//! Audio sources feeding a `Recorder`
+//!
+//! A source pushes mono f32 samples at its own rate into a lock-free ring
+//! buffer. The microphone source wraps cpal; `FileSource` replays decoded
+//! audio so tests and tools can stand a file in for a microphone.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, SizedSample};
+
+use super::ring::Producer;
+
+/// Something that produces audio while started
+pub trait AudioSource {
+    /// Begin pushing mono samples to `sink`
+    fn start(&mut self, sink: Producer) -> anyhow::Result<()>;
+
+    /// Stop capturing; every sample captured so far is in the ring on return
+    fn stop(&mut self);
+
+    /// Rate of the samples pushed to the sink
+    fn sample_rate(&self) -> u32;
+}
+
+/// Names of the available input devices
+pub fn list_devices() -> Vec<String> {
+    let host = cpal::default_host();
+    match host.input_devices() {
+        Ok(devices) => devices.filter_map(|d| d.name().ok()).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Name of the system default input device
+pub fn default_device() -> Option<String> {
+    cpal::default_host().default_input_device()?.name().ok()
+}
+
+/// Capture from an input device through cpal
+pub struct MicrophoneSource {
+    device_name: Option<String>,
+    stream: Option<cpal::Stream>,
+    sample_rate: u32,
+}
+
+impl MicrophoneSource {
+    /// The system default input device
+    pub fn new() -> Self {
+        Self {
+            device_name: None,
+            stream: None,
+            sample_rate: 16000,
+        }
+    }
+
+    /// The input device named `name`, as listed by `list_devices`
+    pub fn with_device(name: &str) -> Self {
+        Self {
+            device_name: Some(name.to_string()),
+            ..Self::new()
+        }
+    }
+
+    fn device(&self) -> anyhow::Result<cpal::Device> {
+        let host = cpal::default_host();
+        match &self.device_name {
+            None => host
+                .default_input_device()
+                .ok_or_else(|| anyhow::anyhow!("No input device available")),
+            Some(name) => host
+                .input_devices()?
+                .find(|d| d.name().is_ok_and(|n| n == *name))
+                .ok_or_else(|| anyhow::anyhow!("Input device not found: {}", name)),
+        }
+    }
+}
+
+impl Default for MicrophoneSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AudioSource for MicrophoneSource {
+    fn start(&mut self, sink: Producer) -> anyhow::Result<()> {
+        let device = self.device()?;
+        let config = device.default_input_config()?;
+        let format = config.sample_format();
+        let channels = config.channels() as usize;
+        let config: cpal::StreamConfig = config.into();
+
+        let stream = match format {
+            cpal::SampleFormat::I8 => build_stream::<i8>(&device, &config, channels, sink)?,
+            cpal::SampleFormat::I16 => build_stream::<i16>(&device, &config, channels, sink)?,
+            cpal::SampleFormat::I32 => build_stream::<i32>(&device, &config, channels, sink)?,
+            cpal::SampleFormat::I64 => build_stream::<i64>(&device, &config, channels, sink)?,
+            cpal::SampleFormat::U8 => build_stream::<u8>(&device, &config, channels, sink)?,
+            cpal::SampleFormat::U16 => build_stream::<u16>(&device, &config, channels, sink)?,
+            cpal::SampleFormat::U32 => build_stream::<u32>(&device, &config, channels, sink)?,
+            cpal::SampleFormat::U64 => build_stream::<u64>(&device, &config, channels, sink)?,
+            cpal::SampleFormat::F32 => build_stream::<f32>(&device, &config, channels, sink)?,
+            cpal::SampleFormat::F64 => build_stream::<f64>(&device, &config, channels, sink)?,
+            other => return Err(anyhow::anyhow!("Unsupported sample format: {:?}", other)),
+        };
+        stream.play()?;
+
+        self.sample_rate = config.sample_rate.0;
+        self.stream = Some(stream);
+        Ok(())
+    }
+
+    fn stop(&mut self) {
+        if self.stream.take().is_some() {
+            // Give the driver a moment to deliver the last callback
+            std::thread::sleep(Duration::from_millis(40));
+        }
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+}
+
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    channels: usize,
+    mut sink: Producer,
+) -> anyhow::Result<cpal::Stream>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    let channels = channels.max(1);
+    let stream = device.build_input_stream(
+        config,
+        // Real-time thread: no locks or allocation, overflow is counted
+        move |data: &[T], _| {
+            sink.push_lossy(data.chunks(channels).map(|frame| {
+                let sum: f32 = frame.iter().map(|&s| s.to_sample::<f32>()).sum();
+                sum / frame.len() as f32
+            }));
+        },
+        |err| eprintln!("Audio stream error: {}", err),
+        None,
+    )?;
+    Ok(stream)
+}
+
+/// Replay decoded audio as if it were being captured
+pub struct FileSource {
+    samples: Arc<Vec<f32>>,
+    sample_rate: u32,
+    paced: bool,
+    stop: Arc<AtomicBool>,
+    feeder: Option<JoinHandle<()>>,
+}
+
+impl FileSource {
+    /// Decode an audio file, downmixed to mono at its own rate
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let audio = super::decode_file(path)?;
+        let mono = super::downmix(&audio.samples, audio.channels);
+        Ok(Self::from_samples(mono, audio.sample_rate))
+    }
+
+    pub fn from_samples(samples: Vec<f32>, sample_rate: u32) -> Self {
+        Self {
+            samples: Arc::new(samples),
+            sample_rate,
+            paced: false,
+            stop: Arc::new(AtomicBool::new(false)),
+            feeder: None,
+        }
+    }
+
+    /// Deliver audio in 10 ms blocks at real-time speed instead of all at
+    /// once on start; stopping early keeps only what was played
+    pub fn paced(mut self, paced: bool) -> Self {
+        self.paced = paced;
+        self
+    }
+}
+
+impl AudioSource for FileSource {
+    fn start(&mut self, mut sink: Producer) -> anyhow::Result<()> {
+        if !self.paced {
+            // Not real-time, so wait for the consumer instead of dropping
+            let mut rest = &self.samples[..];
+            while !rest.is_empty() {
+                let n = sink.try_push_slice(rest);
+                rest = &rest[n..];
+                if n == 0 {
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+            }
+            return Ok(());
+        }
+
+        self.stop.store(false, Ordering::SeqCst);
+        let samples = Arc::clone(&self.samples);
+        let stop = Arc::clone(&self.stop);
+        let block = (self.sample_rate as usize / 100).max(1);
+        self.feeder = Some(std::thread::spawn(move || {
+            for chunk in samples.chunks(block) {
+                if stop.load(Ordering::SeqCst) {
+                    break;
+                }
+                sink.push_lossy(chunk.iter().copied());
+                std::thread::sleep(Duration::from_millis(10));
+            }
+        }));
+        Ok(())
+    }
+
+    fn stop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(feeder) = self.feeder.take() {
+            let _ = feeder.join();
+        }
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+}
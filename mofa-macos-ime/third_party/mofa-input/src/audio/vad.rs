@@ -0,0 +1,599 @@
+//! Voice activity detection
+//!
+//! A `SpeechDetector` scores fixed-size frames of 16kHz audio; `Vad` smooths
+//! the scores into speech regions used to trim silence before ASR, reject
+//! clips without speech and pick chunk boundaries. The energy/ZCR detector
+//! needs no model; Silero VAD runs through ONNX Runtime when its model is
+//! downloaded.
+
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use ort::session::Session;
+use ort::value::Tensor;
+
+const SAMPLE_RATE: usize = 16_000;
+
+/// Scores frames of 16kHz mono audio
+pub trait SpeechDetector: Send + Sync {
+    /// Samples per scored frame
+    fn frame_len(&self) -> usize;
+
+    /// Speech probability (0..1) of each frame; a trailing partial frame is
+    /// scored as well
+    fn frame_probabilities(&self, samples: &[f32]) -> anyhow::Result<Vec<f32>>;
+}
+
+/// Frame energy and zero-crossing detector against an adaptive noise floor
+#[derive(Clone, Debug)]
+pub struct EnergyDetector {
+    pub frame_len: usize,
+    /// Speech must be this many times louder than the noise floor
+    pub floor_ratio: f32,
+    /// Frames quieter than this are never speech
+    pub min_rms: f32,
+    /// Frames louder than this are always speech, whatever the floor
+    pub max_rms: f32,
+    /// Zero-crossing rate that marks quieter frames as unvoiced consonants
+    pub fricative_zcr: f32,
+}
+
+impl Default for EnergyDetector {
+    fn default() -> Self {
+        Self {
+            frame_len: 480,
+            floor_ratio: 3.0,
+            min_rms: 0.0015,
+            max_rms: 0.02,
+            fricative_zcr: 0.3,
+        }
+    }
+}
+
+impl EnergyDetector {
+    /// Speech flag for each frame
+    pub fn speech_frames(&self, samples: &[f32]) -> Vec<bool> {
+        let frames: Vec<(f32, f32)> = samples
+            .chunks(self.frame_len.max(1))
+            .map(frame_features)
+            .collect();
+        self.classify(&frames)
+    }
+
+    /// Speech flags from per-frame (RMS, zero-crossing rate)
+    fn classify(&self, frames: &[(f32, f32)]) -> Vec<bool> {
+        if frames.is_empty() {
+            return Vec::new();
+        }
+
+        // The quietest tenth of the clip approximates background noise, but
+        // only when the loudest tenth stands clear of it; a clip that is
+        // speech throughout has no quiet part to measure
+        let mut levels: Vec<f32> = frames.iter().map(|&(rms, _)| rms).collect();
+        levels.sort_by(f32::total_cmp);
+        let floor = levels[levels.len() / 10];
+        let loud = levels[levels.len() * 9 / 10];
+        let threshold = if floor * self.floor_ratio <= loud {
+            (floor * self.floor_ratio).clamp(self.min_rms, self.max_rms)
+        } else {
+            self.min_rms
+        };
+
+        frames
+            .iter()
+            .map(|&(rms, zcr)| {
+                rms >= threshold || (rms >= threshold / 2.0 && zcr >= self.fricative_zcr)
+            })
+            .collect()
+    }
+}
+
+impl SpeechDetector for EnergyDetector {
+    fn frame_len(&self) -> usize {
+        self.frame_len
+    }
+
+    fn frame_probabilities(&self, samples: &[f32]) -> anyhow::Result<Vec<f32>> {
+        Ok(self
+            .speech_frames(samples)
+            .into_iter()
+            .map(|speech| if speech { 1.0 } else { 0.0 })
+            .collect())
+    }
+}
+
+/// Silero VAD (v5 ONNX model)
+pub struct SileroDetector {
+    session: Mutex<Session>,
+}
+
+impl SileroDetector {
+    const FRAME: usize = 512;
+    /// Samples of the previous frame the model sees before each frame
+    const CONTEXT: usize = 64;
+    const STATE_LEN: usize = 2 * 128;
+
+    pub fn new(model_path: &Path) -> anyhow::Result<Self> {
+        if !model_path.exists() {
+            return Err(anyhow::anyhow!("VAD model not found: {:?}", model_path));
+        }
+        let session = Session::builder()
+            .and_then(|mut b| b.commit_from_file(model_path))
+            .map_err(|e| anyhow::anyhow!("Failed to load VAD model {:?}: {}", model_path, e))?;
+        Ok(Self {
+            session: Mutex::new(session),
+        })
+    }
+}
+
+impl SpeechDetector for SileroDetector {
+    fn frame_len(&self) -> usize {
+        Self::FRAME
+    }
+
+    fn frame_probabilities(&self, samples: &[f32]) -> anyhow::Result<Vec<f32>> {
+        let mut session = self.session.lock().unwrap();
+        let mut state = vec![0.0f32; Self::STATE_LEN];
+        let mut input = vec![0.0f32; Self::CONTEXT + Self::FRAME];
+        let mut probabilities = Vec::with_capacity(samples.len() / Self::FRAME + 1);
+
+        for frame in samples.chunks(Self::FRAME) {
+            // Carry the tail of the previous frame over as context
+            input.copy_within(Self::FRAME.., 0);
+            input[Self::CONTEXT..].fill(0.0);
+            input[Self::CONTEXT..Self::CONTEXT + frame.len()].copy_from_slice(frame);
+
+            let outputs = session.run(ort::inputs![
+                "input" => Tensor::from_array(([1usize, input.len()], input.clone()))?,
+                "state" => Tensor::from_array(([2usize, 1, 128], state))?,
+                "sr" => Tensor::from_array(((), vec![SAMPLE_RATE as i64]))?,
+            ])?;
+            let (_, prob) = outputs["output"].try_extract_tensor::<f32>()?;
+            probabilities.push(prob.first().copied().unwrap_or(0.0));
+            let (_, next_state) = outputs["stateN"].try_extract_tensor::<f32>()?;
+            state = next_state.to_vec();
+        }
+        Ok(probabilities)
+    }
+}
+
+/// Smoothing applied to detector scores
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VadConfig {
+    /// Probability at which speech starts; it ends 0.15 below
+    pub threshold: f32,
+    /// Shorter bursts are dropped as clicks
+    pub min_speech_ms: u32,
+    /// Shorter gaps are bridged into one region
+    pub min_silence_ms: u32,
+    /// Audio kept around each region so word edges survive trimming
+    pub padding_ms: u32,
+}
+
+impl Default for VadConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 0.5,
+            min_speech_ms: 120,
+            min_silence_ms: 400,
+            padding_ms: 200,
+        }
+    }
+}
+
+/// A span of speech in samples (16kHz)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SpeechRegion {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl SpeechRegion {
+    pub fn start_ms(&self) -> i64 {
+        (self.start * 1000 / SAMPLE_RATE) as i64
+    }
+
+    pub fn end_ms(&self) -> i64 {
+        (self.end * 1000 / SAMPLE_RATE) as i64
+    }
+}
+
+/// Voice activity detector over 16kHz mono audio
+pub struct Vad {
+    detector: Box<dyn SpeechDetector>,
+    config: VadConfig,
+}
+
+impl Vad {
+    pub fn new(detector: impl SpeechDetector + 'static) -> Self {
+        Self {
+            detector: Box::new(detector),
+            config: VadConfig::default(),
+        }
+    }
+
+    /// Model-free energy/ZCR detection
+    pub fn energy() -> Self {
+        Self::new(EnergyDetector::default())
+    }
+
+    pub fn silero(model_path: &Path) -> anyhow::Result<Self> {
+        Ok(Self::new(SileroDetector::new(model_path)?))
+    }
+
+    /// Silero when its model is downloaded and loads, energy detection otherwise
+    pub fn auto() -> Self {
+        let path = silero_model_path();
+        if path.exists() {
+            match Self::silero(&path) {
+                Ok(vad) => return vad,
+                Err(e) => eprintln!("Silero VAD unavailable, using energy VAD: {}", e),
+            }
+        }
+        Self::energy()
+    }
+
+    pub fn with_config(mut self, config: VadConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Speech regions in order, padded and merged across short gaps
+    pub fn speech_regions(&self, samples: &[f32]) -> anyhow::Result<Vec<SpeechRegion>> {
+        let frame = self.detector.frame_len().max(1);
+        let probabilities = self.detector.frame_probabilities(samples)?;
+        let ms_to_frames = |ms: u32| (ms as usize * SAMPLE_RATE / 1000).div_ceil(frame);
+
+        // Hysteresis keeps a region open through brief dips
+        let mut frames: Vec<Range<usize>> = Vec::new();
+        let mut start = None;
+        for (i, &p) in probabilities.iter().enumerate() {
+            match start {
+                None if p >= self.config.threshold => start = Some(i),
+                Some(s) if p < self.config.threshold - 0.15 => {
+                    frames.push(s..i);
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(s) = start {
+            frames.push(s..probabilities.len());
+        }
+
+        let min_gap = ms_to_frames(self.config.min_silence_ms);
+        let mut merged: Vec<Range<usize>> = Vec::new();
+        for range in frames {
+            match merged.last_mut() {
+                Some(last) if range.start - last.end < min_gap => last.end = range.end,
+                _ => merged.push(range),
+            }
+        }
+
+        let min_len = ms_to_frames(self.config.min_speech_ms);
+        let padding = self.config.padding_ms as usize * SAMPLE_RATE / 1000;
+        let mut regions: Vec<SpeechRegion> = Vec::new();
+        for range in merged.into_iter().filter(|r| r.len() >= min_len) {
+            let start = (range.start * frame).saturating_sub(padding);
+            let end = (range.end * frame + padding).min(samples.len());
+            match regions.last_mut() {
+                Some(last) if start <= last.end => last.end = end,
+                _ => regions.push(SpeechRegion { start, end }),
+            }
+        }
+        Ok(regions)
+    }
+
+    /// Whether the audio contains any speech
+    pub fn contains_speech(&self, samples: &[f32]) -> anyhow::Result<bool> {
+        Ok(!self.speech_regions(samples)?.is_empty())
+    }
+
+    /// Samples from the start of the first speech region to the end of the
+    /// last, or `None` when there is no speech
+    pub fn speech_span(&self, samples: &[f32]) -> anyhow::Result<Option<Range<usize>>> {
+        let regions = self.speech_regions(samples)?;
+        Ok(match (regions.first(), regions.last()) {
+            (Some(first), Some(last)) => Some(first.start..last.end),
+            _ => None,
+        })
+    }
+
+    /// The audio with leading and trailing silence removed, or `None` when
+    /// there is no speech
+    pub fn trim<'a>(&self, samples: &'a [f32]) -> anyhow::Result<Option<&'a [f32]>> {
+        Ok(self.speech_span(samples)?.map(|span| &samples[span]))
+    }
+}
+
+/// Incremental end-of-speech detection for hands-free recording
+///
+/// Audio is pushed as it arrives; the energy detector re-judges everything
+/// heard so far, so the noise floor keeps adapting.
+pub struct Endpointer {
+    detector: EnergyDetector,
+    frames: Vec<(f32, f32)>,
+    pending: Vec<f32>,
+    silence_ms: u32,
+}
+
+impl Endpointer {
+    /// Frames of speech needed before silence can end the recording
+    const MIN_SPEECH_FRAMES: usize = 5;
+
+    /// Done once `silence_ms` of silence follows speech
+    pub fn new(silence_ms: u32) -> Self {
+        Self {
+            detector: EnergyDetector::default(),
+            frames: Vec::new(),
+            pending: Vec::new(),
+            silence_ms,
+        }
+    }
+
+    /// Append 16kHz mono samples
+    pub fn push(&mut self, samples: &[f32]) {
+        let frame_len = self.detector.frame_len.max(1);
+        self.pending.extend_from_slice(samples);
+        let whole = self.pending.len() / frame_len * frame_len;
+        self.frames
+            .extend(self.pending[..whole].chunks(frame_len).map(frame_features));
+        self.pending.drain(..whole);
+    }
+
+    /// Silence since the last speech, or `None` before speech has started
+    pub fn trailing_silence_ms(&self) -> Option<u32> {
+        let speech = self.detector.classify(&self.frames);
+        let mut started = false;
+        let mut last_speech = None;
+        let mut run = 0;
+        for (i, &is_speech) in speech.iter().enumerate() {
+            run = if is_speech { run + 1 } else { 0 };
+            started |= run >= Self::MIN_SPEECH_FRAMES;
+            // Single-frame clicks do not count as talking
+            if run >= 2 {
+                last_speech = Some(i);
+            }
+        }
+        let last = last_speech.filter(|_| started)?;
+        let frames = speech.len() - 1 - last;
+        Some((frames * self.detector.frame_len * 1000 / SAMPLE_RATE) as u32)
+    }
+
+    /// Whether speech has been followed by enough silence
+    pub fn is_done(&self) -> bool {
+        self.trailing_silence_ms()
+            .is_some_and(|ms| ms >= self.silence_ms)
+    }
+}
+
+/// Where the Silero VAD model is looked for
+pub fn silero_model_path() -> PathBuf {
+    dirs::home_dir()
+        .map(|h| h.join(".mofa/models"))
+        .unwrap_or_else(|| PathBuf::from("./models"))
+        .join("silero_vad.onnx")
+}
+
+fn frame_features(frame: &[f32]) -> (f32, f32) {
+    (rms(frame), zero_crossing_rate(frame))
+}
+
+fn rms(frame: &[f32]) -> f32 {
+    (frame.iter().map(|s| s * s).sum::<f32>() / frame.len().max(1) as f32).sqrt()
+}
+
+fn zero_crossing_rate(frame: &[f32]) -> f32 {
+    let crossings = frame
+        .windows(2)
+        .filter(|w| (w[0] >= 0.0) != (w[1] >= 0.0))
+        .count();
+    crossings as f32 / frame.len().max(1) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Samples for `ms` of a 300 Hz tone, or of silence at amplitude 0
+    fn tone(ms: usize, amplitude: f32) -> Vec<f32> {
+        (0..ms * SAMPLE_RATE / 1000)
+            .map(|i| {
+                let t = i as f32 / SAMPLE_RATE as f32;
+                amplitude * (2.0 * std::f32::consts::PI * 300.0 * t).sin()
+            })
+            .collect()
+    }
+
+    /// Deterministic white noise
+    fn noise(ms: usize, amplitude: f32) -> Vec<f32> {
+        let mut state = 0x2545_f491_u32;
+        (0..ms * SAMPLE_RATE / 1000)
+            .map(|_| {
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                amplitude * ((state >> 8) as f32 / (1 << 23) as f32 - 1.0)
+            })
+            .collect()
+    }
+
+    /// Replays fixed probabilities for 10 ms frames
+    struct Scripted(Vec<f32>);
+
+    impl SpeechDetector for Scripted {
+        fn frame_len(&self) -> usize {
+            160
+        }
+
+        fn frame_probabilities(&self, _samples: &[f32]) -> anyhow::Result<Vec<f32>> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn unpadded() -> VadConfig {
+        VadConfig {
+            min_speech_ms: 0,
+            min_silence_ms: 0,
+            padding_ms: 0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn energy_detector_marks_loud_frames() {
+        let samples = [tone(300, 0.0), tone(300, 0.2), tone(300, 0.0)].concat();
+        let frames = EnergyDetector::default().speech_frames(&samples);
+        assert_eq!(frames.len(), 30);
+        assert!(frames[..10].iter().all(|&s| !s));
+        assert!(frames[10..20].iter().all(|&s| s));
+        assert!(frames[20..].iter().all(|&s| !s));
+    }
+
+    #[test]
+    fn energy_detector_adapts_to_noise_floor() {
+        let detector = EnergyDetector::default();
+        let mut samples = noise(900, 0.01);
+        for (s, t) in samples[4800..9600].iter_mut().zip(tone(300, 0.1)) {
+            *s += t;
+        }
+        let frames = detector.speech_frames(&samples);
+        assert!(frames[10..20].iter().all(|&s| s));
+        assert!(frames[..10].iter().chain(&frames[20..]).all(|&s| !s));
+    }
+
+    #[test]
+    fn speech_throughout_uses_the_fixed_gate() {
+        // No quiet part to take a floor from, so min_rms decides
+        let detector = EnergyDetector::default();
+        let steady = tone(2000, 0.007);
+        assert!(detector.speech_frames(&steady).iter().all(|&s| s));
+
+        let varying: Vec<f32> = tone(2000, 0.01)
+            .iter()
+            .enumerate()
+            .map(|(i, s)| s * (0.7 + 0.3 * (i as f32 / 1500.0).sin()))
+            .collect();
+        assert!(detector.speech_frames(&varying).iter().all(|&s| s));
+        assert!(Vad::energy().contains_speech(&steady).unwrap());
+        assert!(!detector.speech_frames(&tone(2000, 0.001)).contains(&true));
+    }
+
+    #[test]
+    fn hysteresis_keeps_region_open_through_dips() {
+        let vad = Vad::new(Scripted(vec![0.0, 0.6, 0.4, 0.6, 0.3, 0.0])).with_config(unpadded());
+        let regions = vad.speech_regions(&[0.0; 960]).unwrap();
+        assert_eq!(
+            regions,
+            vec![SpeechRegion {
+                start: 160,
+                end: 640
+            }]
+        );
+    }
+
+    #[test]
+    fn short_gaps_merge_and_short_bursts_drop() {
+        let mut probabilities = vec![0.0; 150];
+        probabilities[10..30].fill(1.0);
+        probabilities[50..70].fill(1.0);
+        // 10 ms click, far enough from speech not to be bridged
+        probabilities[120] = 1.0;
+        let config = VadConfig {
+            min_speech_ms: 100,
+            min_silence_ms: 300,
+            ..unpadded()
+        };
+        let vad = Vad::new(Scripted(probabilities.clone())).with_config(config);
+        let regions = vad.speech_regions(&[0.0; 24_000]).unwrap();
+        assert_eq!(
+            regions,
+            vec![SpeechRegion {
+                start: 1600,
+                end: 11_200
+            }]
+        );
+
+        let config = VadConfig {
+            min_silence_ms: 100,
+            ..config
+        };
+        let vad = Vad::new(Scripted(probabilities)).with_config(config);
+        let regions = vad.speech_regions(&[0.0; 24_000]).unwrap();
+        assert_eq!(
+            regions,
+            vec![
+                SpeechRegion {
+                    start: 1600,
+                    end: 4800
+                },
+                SpeechRegion {
+                    start: 8000,
+                    end: 11_200
+                },
+            ]
+        );
+        assert_eq!((regions[1].start_ms(), regions[1].end_ms()), (500, 700));
+    }
+
+    #[test]
+    fn padding_is_clamped_to_the_clip() {
+        let mut probabilities = vec![1.0; 20];
+        probabilities[..5].fill(0.0);
+        let config = VadConfig {
+            padding_ms: 200,
+            ..unpadded()
+        };
+        let vad = Vad::new(Scripted(probabilities)).with_config(config);
+        let samples = vec![0.0; 3200];
+        assert_eq!(vad.speech_span(&samples).unwrap(), Some(0..3200));
+    }
+
+    #[test]
+    fn speech_span_covers_the_tone_with_padding() {
+        // Whole 30 ms frames of each
+        let samples = [tone(990, 0.0), tone(990, 0.2), tone(990, 0.0)].concat();
+        let vad = Vad::energy();
+        let span = vad.speech_span(&samples).unwrap().unwrap();
+        assert_eq!(span, 15_840 - 3200..31_680 + 3200);
+        assert_eq!(vad.trim(&samples).unwrap().unwrap().len(), 15_840 + 6400);
+    }
+
+    #[test]
+    fn silence_and_clicks_are_not_speech() {
+        let vad = Vad::energy();
+        assert!(!vad.contains_speech(&tone(1000, 0.0)).unwrap());
+        assert_eq!(vad.trim(&[]).unwrap(), None);
+
+        let click = [tone(500, 0.0), tone(30, 0.5), tone(500, 0.0)].concat();
+        assert!(!vad.contains_speech(&click).unwrap());
+    }
+
+    #[test]
+    fn endpointer_waits_for_speech_then_silence() {
+        let mut endpointer = Endpointer::new(400);
+        endpointer.push(&tone(1000, 0.0));
+        assert_eq!(endpointer.trailing_silence_ms(), None);
+        assert!(!endpointer.is_done());
+
+        // Arrives in pieces that do not line up with frames
+        for chunk in tone(500, 0.2).chunks(333) {
+            endpointer.push(chunk);
+        }
+        assert_eq!(endpointer.trailing_silence_ms(), Some(0));
+        assert!(!endpointer.is_done());
+
+        endpointer.push(&tone(300, 0.0));
+        assert_eq!(endpointer.trailing_silence_ms(), Some(300));
+        assert!(!endpointer.is_done());
+        endpointer.push(&tone(150, 0.0));
+        assert!(endpointer.is_done());
+    }
+
+    #[test]
+    fn endpointer_ignores_clicks() {
+        let mut endpointer = Endpointer::new(400);
+        endpointer.push(&[tone(500, 0.0), tone(60, 0.5), tone(1000, 0.0)].concat());
+        assert_eq!(endpointer.trailing_silence_ms(), None);
+        assert!(!endpointer.is_done());
+    }
+}
@@ -2,7 +2,7 @@ use eframe::egui;
 use std::path::PathBuf;
 use std::sync::mpsc::{channel, Sender, Receiver};
 use std::collections::{HashMap, HashSet};
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 enum ModelSize {
@@ -150,7 +150,6 @@ enum AppEvent {
     AsrDownloadError(WhisperModelSize, String),
     AsrResult(WhisperModelSize, String),  // Model-specific result
     AsrModelError(WhisperModelSize, String), // Model-specific error
-    AsrError(String),
 }
 
 struct ChatApp {
@@ -175,17 +174,14 @@ struct ChatApp {
     pending_delete: Option<ModelSize>,
 
     // ASR fields - multiple models, each with its own text box
-    asr_sessions: HashMap<WhisperModelSize, Arc<Mutex<mofa_input::asr::AsrSession>>>,
+    asr_sessions: HashMap<WhisperModelSize, mofa_input::asr::AsrSession>,
     asr_texts: HashMap<WhisperModelSize, String>, // Each model has its own text box
     asr_download_progress: HashMap<WhisperModelSize, f32>,
     asr_downloading_models: HashSet<WhisperModelSize>,
     is_recording: bool,
-    audio_samples: Arc<Mutex<Vec<f32>>>,
+    recorder: Option<mofa_input::audio::Recorder>,
     show_asr_manager: bool,
     asr_status: String,
-    _recording_sample_rate: Arc<Mutex<u32>>, // Store actual recording sample rate for resampling
-    recording_stop_signal: Option<std::sync::mpsc::Sender<()>>, // Signal to stop recording
-    recording_thread_handle: Option<std::thread::JoinHandle<()>>, // Handle to wait for thread
 }
 
 impl ChatApp {
@@ -218,12 +214,9 @@ impl ChatApp {
             asr_download_progress: HashMap::new(),
             asr_downloading_models: HashSet::new(),
             is_recording: false,
-            audio_samples: Arc::new(Mutex::new(Vec::new())),
+            recorder: None,
             show_asr_manager: false,
             asr_status: "请选择语音模型".to_string(),
-            _recording_sample_rate: Arc::new(Mutex::new(16000)),
-            recording_stop_signal: None,
-            recording_thread_handle: None,
         }
     }
 
@@ -471,7 +464,7 @@ impl ChatApp {
 
         match mofa_input::asr::AsrSession::new(&model_path) {
             Ok(session) => {
-                self.asr_sessions.insert(model, Arc::new(Mutex::new(session)));
+                self.asr_sessions.insert(model, session);
                 // Initialize text box for this model if not exists
                 self.asr_texts.entry(model).or_default();
                 self.asr_status = format!("{} 已就绪", model.name());
@@ -487,160 +480,63 @@ impl ChatApp {
         self.asr_status = format!("{} 已卸载", model.name());
     }
 
-    /// Resample audio from source rate to 16kHz (Whisper requires 16kHz)
-    fn resample_audio(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
-        if from_rate == to_rate {
-            return samples.to_vec();
+    fn start_recording(&mut self) {
+        if self.asr_sessions.is_empty() {
+            self.asr_status = "错误: 请先加载语音模型".to_string();
+            return;
         }
 
-        let ratio = to_rate as f64 / from_rate as f64;
-        let new_len = (samples.len() as f64 * ratio) as usize;
-        let mut result = Vec::with_capacity(new_len);
-
-        for i in 0..new_len {
-            let src_idx = i as f64 / ratio;
-            let idx0 = src_idx.floor() as usize;
-            let idx1 = (idx0 + 1).min(samples.len() - 1);
-            let frac = src_idx - idx0 as f64;
-
-            let val0 = samples[idx0] as f64;
-            let val1 = samples[idx1] as f64;
-            result.push((val0 + (val1 - val0) * frac) as f32);
+        let mut recorder = mofa_input::audio::Recorder::new();
+        if let Err(e) = recorder.start() {
+            self.asr_status = format!("错误: {}", e);
+            return;
         }
-
-        result
+        self.recorder = Some(recorder);
+        self.is_recording = true;
+        self.asr_status = "按住说话...".to_string();
     }
 
-    fn start_recording(&mut self) {
-        use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-
-        if self.asr_sessions.is_empty() {
-            self.asr_status = "错误: 请先加载语音模型".to_string();
+    fn stop_recording(&mut self) {
+        if !self.is_recording {
             return;
         }
+        self.is_recording = false;
 
-        let host = cpal::default_host();
-        let device = match host.default_input_device() {
-            Some(d) => d,
-            None => {
-                self.asr_status = "错误: 无麦克风".to_string();
-                return;
-            }
+        let Some(mut recorder) = self.recorder.take() else {
+            return;
         };
-
-        let config = match device.default_input_config() {
-            Ok(c) => c,
+        let samples = match recorder.stop() {
+            Ok(samples) => samples,
             Err(e) => {
                 self.asr_status = format!("错误: {}", e);
                 return;
             }
         };
 
-        let sample_rate = config.sample_rate().0;
-        let channels = config.channels();
-        let audio_samples_for_stream = self.audio_samples.clone();
-        let audio_samples_for_process = self.audio_samples.clone();
-        let sender = self.event_sender.clone();
-        let asr_sessions: Vec<_> = self.asr_sessions.iter().map(|(k, v)| (*k, v.clone())).collect();
-
-        // Create stop signal channel
-        let (stop_tx, stop_rx) = std::sync::mpsc::channel::<()>();
-        self.recording_stop_signal = Some(stop_tx);
-
-        self.audio_samples.lock().unwrap().clear();
-        self.is_recording = true;
-        self.asr_status = "按住说话...".to_string();
-
-        let handle = std::thread::spawn(move || {
-            let err_fn = |err| eprintln!("音频错误: {}", err);
-
-            let stream = match config.sample_format() {
-                cpal::SampleFormat::F32 => {
-                    device.build_input_stream(
-                        &config.into(),
-                        move |data: &[f32], _| {
-                            let mut samples = audio_samples_for_stream.lock().unwrap();
-                            if channels == 2 {
-                                for chunk in data.chunks(2) {
-                                    let mono = (chunk[0] + chunk[1]) / 2.0;
-                                    samples.push(mono);
-                                }
-                            } else {
-                                samples.extend_from_slice(data);
-                            }
-                        },
-                        err_fn,
-                        None,
-                    )
-                }
-                _ => {
-                    let _ = sender.send(AppEvent::AsrError("不支持的音频格式".to_string()));
-                    return;
-                }
-            };
-
-            if let Ok(stream) = stream {
-                let _ = stream.play();
-
-                // Wait for stop signal
-                let _ = stop_rx.recv();
-
-                drop(stream);
-
-                // Get recorded samples
-                let raw_samples = {
-                    let s = audio_samples_for_process.lock().unwrap();
-                    s.clone()
-                };
-
-                // Check if we have enough samples (at least 0.5 seconds at 16kHz)
-                if raw_samples.len() < (sample_rate as usize / 2) {
-                    let _ = sender.send(AppEvent::AsrError("录音太短，请按住更长时间".to_string()));
-                    return;
-                }
-
-                // Resample to 16kHz (Whisper requirement)
-                let samples = Self::resample_audio(&raw_samples, sample_rate, 16000);
-
-                // Transcribe with all loaded models in parallel
-                for (model, session) in asr_sessions {
-                    let sender_clone = sender.clone();
-                    let session_clone = session.clone();
-                    let samples_clone = samples.clone();
-
-                    std::thread::spawn(move || {
-                        match session_clone.lock().unwrap().transcribe(&samples_clone) {
-                            Ok(text) => {
-                                let _ = sender_clone.send(AppEvent::AsrResult(model, text));
-                            }
-                            Err(e) => {
-                                let _ = sender_clone.send(AppEvent::AsrModelError(model, format!("识别失败: {}", e)));
-                            }
-                        }
-                    });
-                }
-            }
-        });
-
-        self.recording_thread_handle = Some(handle);
-    }
-
-    fn stop_recording(&mut self) {
-        if !self.is_recording {
+        // Check if we have enough samples (at least 0.5 seconds at 16kHz)
+        if samples.len() < 8000 {
+            self.asr_status = "录音太短，请按住更长时间".to_string();
             return;
         }
-
-        // Send stop signal
-        if let Some(stop_tx) = self.recording_stop_signal.take() {
-            let _ = stop_tx.send(());
-        }
-
-        self.is_recording = false;
         self.asr_status = "识别中...".to_string();
 
-        // Wait for recording thread to finish
-        if let Some(handle) = self.recording_thread_handle.take() {
-            let _ = handle.join();
+        // Transcribe with all loaded models in parallel
+        let samples = mofa_input::audio::preprocess(&samples, &Default::default());
+        let samples = Arc::new(samples);
+        for (model, session) in &self.asr_sessions {
+            let model = *model;
+            let sender = self.event_sender.clone();
+            let session = session.clone();
+            let samples = Arc::clone(&samples);
+
+            std::thread::spawn(move || match session.transcribe(&samples) {
+                Ok(text) => {
+                    let _ = sender.send(AppEvent::AsrResult(model, text));
+                }
+                Err(e) => {
+                    let _ = sender.send(AppEvent::AsrModelError(model, format!("识别失败: {}", e)));
+                }
+            });
         }
     }
 
@@ -816,19 +712,13 @@ impl ChatApp {
                     self.asr_status = format!("{} 下载失败: {}", model.name(), e);
                 }
                 AppEvent::AsrResult(model, text) => {
-                    self.is_recording = false;
                     self.asr_status = format!("{} 识别完成", model.name());
                     // Store result in model-specific text box
                     self.asr_texts.insert(model, text);
                 }
                 AppEvent::AsrModelError(model, e) => {
-                    self.is_recording = false;
                     self.asr_status = format!("{} {}", model.name(), e);
                 }
-                AppEvent::AsrError(e) => {
-                    self.is_recording = false;
-                    self.asr_status = e;
-                }
             }
         }
     }
@@ -0,0 +1,365 @@
+//! `mofa-input eval`: ASR accuracy against reference transcripts
+
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use mofa_input::asr::metrics::{self, Alignment};
+use mofa_input::asr::{AsrSession, HallucinationFilter, WhisperParams};
+use mofa_input::text::{cleanup::Cleanup, itn, script, Script};
+
+use super::{default_model, take_value};
+
+pub const USAGE: &str = "\
+Usage: mofa-input eval [options] <dir>
+
+Scores every audio file in <dir> (wav, flac, mp3, ogg, m4a) that has a
+reference transcript with the same name and a .txt extension. CER is
+reported over Chinese references and WER over the others.
+
+Options:
+  -m, --model <path>       ASR model to evaluate; repeat to compare models
+                           (default: the best model in ~/.mofa/models)
+  -l, --language <code>    Force the spoken language (zh, en, ...); default auto
+  -o, --output <file>      Write per-file alignments and diffs as JSON
+      --post <steps>       Post-process hypotheses before scoring; comma list
+                           of itn, cleanup, simplified, traditional
+      --no-filter          Keep segments flagged as hallucinations";
+
+const AUDIO_EXTENSIONS: &[&str] = &["wav", "flac", "mp3", "ogg", "m4a"];
+
+/// Text post-processing applied to hypotheses before scoring
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PostStep {
+    Itn,
+    Cleanup,
+    Simplified,
+    Traditional,
+}
+
+impl PostStep {
+    fn parse(value: &str) -> anyhow::Result<Self> {
+        match value {
+            "itn" => Ok(Self::Itn),
+            "cleanup" => Ok(Self::Cleanup),
+            "simplified" => Ok(Self::Simplified),
+            "traditional" => Ok(Self::Traditional),
+            other => Err(anyhow::anyhow!("Unknown post-processing step: {}", other)),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Itn => "itn",
+            Self::Cleanup => "cleanup",
+            Self::Simplified => "simplified",
+            Self::Traditional => "traditional",
+        }
+    }
+
+    fn apply(self, text: &str) -> String {
+        match self {
+            Self::Itn => itn::inverse_normalize(text),
+            Self::Cleanup => Cleanup::default().clean(text),
+            Self::Simplified => script::convert(text, Script::Simplified),
+            Self::Traditional => script::convert(text, Script::Traditional),
+        }
+    }
+}
+
+struct Options {
+    models: Vec<PathBuf>,
+    params: WhisperParams,
+    output: Option<PathBuf>,
+    post: Vec<PostStep>,
+    filter: bool,
+    dir: PathBuf,
+}
+
+impl Options {
+    fn parse(args: &[String]) -> anyhow::Result<Self> {
+        let mut models = Vec::new();
+        let mut params = WhisperParams::default();
+        let mut output = None;
+        let mut post = Vec::new();
+        let mut filter = true;
+        let mut dir = None;
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-m" | "--model" => models.push(take_value(arg, &mut args)?.into()),
+                "-l" | "--language" => {
+                    let lang = take_value(arg, &mut args)?;
+                    params.language = (lang != "auto").then(|| lang.to_string());
+                }
+                "-o" | "--output" => output = Some(take_value(arg, &mut args)?.into()),
+                "--post" => {
+                    for step in take_value(arg, &mut args)?.split(',') {
+                        post.push(PostStep::parse(step.trim())?);
+                    }
+                }
+                "--no-filter" => filter = false,
+                flag if flag.starts_with('-') => {
+                    return Err(anyhow::anyhow!("Unknown option: {}\n\n{}", flag, USAGE))
+                }
+                path if dir.is_none() => dir = Some(PathBuf::from(path)),
+                extra => return Err(anyhow::anyhow!("Unexpected argument: {}", extra)),
+            }
+        }
+        let dir = dir.ok_or_else(|| anyhow::anyhow!("No input directory\n\n{}", USAGE))?;
+        if models.is_empty() {
+            models.push(default_model()?);
+        }
+        Ok(Self {
+            models,
+            params,
+            output,
+            post,
+            filter,
+            dir,
+        })
+    }
+}
+
+/// A recording with its reference transcript, decoded once for all models
+struct Case {
+    path: PathBuf,
+    reference: String,
+    samples: Vec<f32>,
+    duration_secs: f64,
+}
+
+/// Error totals over a set of files
+#[derive(Default)]
+struct Totals {
+    errors: usize,
+    reference_len: usize,
+}
+
+impl Totals {
+    fn add(&mut self, alignment: &Alignment) {
+        self.errors += alignment.errors();
+        self.reference_len += alignment.reference_len();
+    }
+
+    fn rate(&self) -> Option<f64> {
+        (self.reference_len > 0).then(|| self.errors as f64 / self.reference_len as f64)
+    }
+}
+
+/// Aggregate result for one model
+struct ModelSummary {
+    name: String,
+    files: usize,
+    failed: usize,
+    cer: Option<f64>,
+    wer: Option<f64>,
+    rtf: f64,
+}
+
+pub fn run(args: &[String]) -> anyhow::Result<()> {
+    let opts = Options::parse(args)?;
+    let cases = load_cases(&opts.dir)?;
+    if cases.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No audio files with .txt references in {}",
+            opts.dir.display()
+        ));
+    }
+    eprintln!("{} files in {}", cases.len(), opts.dir.display());
+
+    let mut summaries = Vec::new();
+    let mut reports = Vec::new();
+    for model in &opts.models {
+        let (summary, report) = evaluate_model(&opts, model, &cases)?;
+        summaries.push(summary);
+        reports.push(report);
+    }
+
+    print_table(&summaries);
+    if let Some(output) = &opts.output {
+        let report = serde_json::json!({
+            "dir": opts.dir.display().to_string(),
+            "post": opts.post.iter().map(|s| s.name()).collect::<Vec<_>>(),
+            "models": reports,
+        });
+        std::fs::write(output, serde_json::to_string_pretty(&report)?)?;
+        eprintln!("Report written to {}", output.display());
+    }
+    Ok(())
+}
+
+fn load_cases(dir: &Path) -> anyhow::Result<Vec<Case>> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", dir.display(), e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| AUDIO_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+        })
+        .collect();
+    paths.sort();
+
+    let mut cases = Vec::new();
+    for path in paths {
+        let reference_path = path.with_extension("txt");
+        let Ok(reference) = std::fs::read_to_string(&reference_path) else {
+            eprintln!("Skipping {}: no reference transcript", path.display());
+            continue;
+        };
+        let audio = mofa_input::audio::decode_file(&path)?;
+        cases.push(Case {
+            duration_secs: audio.duration_secs(),
+            samples: audio.into_asr_input()?,
+            reference: reference.trim().to_string(),
+            path,
+        });
+    }
+    Ok(cases)
+}
+
+fn evaluate_model(
+    opts: &Options,
+    model: &Path,
+    cases: &[Case],
+) -> anyhow::Result<(ModelSummary, serde_json::Value)> {
+    let name = model
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| model.display().to_string());
+    eprintln!("Loading {}...", model.display());
+    let load_start = Instant::now();
+    let session = AsrSession::with_params(model, opts.params.clone())?;
+    let load_secs = load_start.elapsed().as_secs_f64();
+    if opts.filter {
+        session.set_filter(Some(HallucinationFilter::default()));
+    }
+
+    let mut cer_totals = Totals::default();
+    let mut wer_totals = Totals::default();
+    let mut audio_secs = 0.0;
+    let mut decode_secs = 0.0;
+    let mut failed = 0;
+    let mut files = Vec::new();
+    for (i, case) in cases.iter().enumerate() {
+        let file = case.path.display().to_string();
+        let start = Instant::now();
+        let (raw, language) = match session.transcribe_with_progress(&case.samples, |_| {}) {
+            Ok(result) => (result.text, result.language),
+            Err(e) => {
+                eprintln!("[{}] {}: error: {}", name, file, e);
+                failed += 1;
+                files.push(serde_json::json!({ "file": file, "error": e.to_string() }));
+                continue;
+            }
+        };
+        let elapsed = start.elapsed().as_secs_f64();
+        let hypothesis = opts
+            .post
+            .iter()
+            .fold(raw.clone(), |text, step| step.apply(&text));
+
+        let cer = metrics::cer(&case.reference, &hypothesis);
+        let wer = metrics::wer(&case.reference, &hypothesis);
+        let chinese = metrics::prefers_cer(&case.reference);
+        let (metric, rate) = if chinese {
+            cer_totals.add(&cer);
+            ("cer", cer.error_rate())
+        } else {
+            wer_totals.add(&wer);
+            ("wer", wer.error_rate())
+        };
+        audio_secs += case.duration_secs;
+        decode_secs += elapsed;
+        eprintln!(
+            "[{}] {}/{} {}  {} {:.1}%",
+            name,
+            i + 1,
+            cases.len(),
+            file,
+            metric.to_uppercase(),
+            rate * 100.0
+        );
+
+        files.push(serde_json::json!({
+            "file": file,
+            "reference": case.reference,
+            "raw": raw,
+            "hypothesis": hypothesis,
+            "language": language,
+            "duration_secs": case.duration_secs,
+            "decode_secs": elapsed,
+            "rtf": elapsed / case.duration_secs.max(f64::EPSILON),
+            "metric": metric,
+            "cer": alignment_json(&cer, ""),
+            "wer": alignment_json(&wer, " "),
+        }));
+    }
+
+    let rtf = decode_secs / f64::max(audio_secs, f64::EPSILON);
+    let summary = ModelSummary {
+        name,
+        files: cases.len() - failed,
+        failed,
+        cer: cer_totals.rate(),
+        wer: wer_totals.rate(),
+        rtf,
+    };
+    let report = serde_json::json!({
+        "model": model.display().to_string(),
+        "load_secs": load_secs,
+        "files_scored": summary.files,
+        "files_failed": summary.failed,
+        "cer": summary.cer,
+        "cer_errors": cer_totals.errors,
+        "cer_reference_len": cer_totals.reference_len,
+        "wer": summary.wer,
+        "wer_errors": wer_totals.errors,
+        "wer_reference_len": wer_totals.reference_len,
+        "audio_secs": audio_secs,
+        "decode_secs": decode_secs,
+        "rtf": rtf,
+        "files": files,
+    });
+    Ok((summary, report))
+}
+
+fn alignment_json(alignment: &Alignment, sep: &str) -> serde_json::Value {
+    serde_json::json!({
+        "rate": alignment.error_rate(),
+        "reference_len": alignment.reference_len(),
+        "hits": alignment.hits,
+        "substitutions": alignment.substitutions,
+        "deletions": alignment.deletions,
+        "insertions": alignment.insertions,
+        "diff": alignment.diff(sep),
+    })
+}
+
+fn print_table(summaries: &[ModelSummary]) {
+    let percent = |rate: Option<f64>| match rate {
+        Some(rate) => format!("{:.2}%", rate * 100.0),
+        None => "-".to_string(),
+    };
+    let width = summaries
+        .iter()
+        .map(|s| s.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(5);
+    println!(
+        "{:<width$}  {:>5}  {:>6}  {:>8}  {:>8}  {:>6}",
+        "Model", "Files", "Failed", "CER", "WER", "RTF"
+    );
+    for s in summaries {
+        println!(
+            "{:<width$}  {:>5}  {:>6}  {:>8}  {:>8}  {:>6.3}",
+            s.name,
+            s.files,
+            s.failed,
+            percent(s.cer),
+            percent(s.wer),
+            s.rtf
+        );
+    }
+}
@@ -0,0 +1,28 @@
+//! Subcommands of the `mofa-input` binary
+
+pub mod eval;
+pub mod transcribe;
+
+use std::path::PathBuf;
+
+use mofa_input::asr::WhisperModelSize;
+
+/// Pull the value following an option flag
+pub fn take_value<'a>(
+    flag: &str,
+    args: &mut impl Iterator<Item = &'a String>,
+) -> anyhow::Result<&'a str> {
+    args.next()
+        .map(String::as_str)
+        .ok_or_else(|| anyhow::anyhow!("Missing value for {}", flag))
+}
+
+/// Best downloaded Whisper model
+pub fn default_model() -> anyhow::Result<PathBuf> {
+    WhisperModelSize::all()
+        .iter()
+        .rev()
+        .map(WhisperModelSize::path)
+        .find(|path| path.exists())
+        .ok_or_else(|| anyhow::anyhow!("No ASR model found in ~/.mofa/models; pass --model"))
+}
@@ -0,0 +1,254 @@
+//! `mofa-input transcribe`: batch transcription of audio files
+
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use mofa_input::asr::{transcript, AsrSession, HallucinationFilter, Segment, WhisperParams};
+use mofa_input::llm::ChatSession;
+
+use super::{default_model, take_value};
+
+pub const USAGE: &str = "\
+Usage: mofa-input transcribe [options] <files...>
+
+Inputs may be WAV, FLAC, MP3, Ogg/Vorbis or M4A (AAC/ALAC).
+
+Options:
+  -m, --model <path>       ASR model (Whisper .bin or FunASR directory);
+                           defaults to the best model in ~/.mofa/models
+  -l, --language <code>    Force the spoken language (zh, en, ...); default auto
+  -f, --format <format>    txt | json | srt | vtt (default txt)
+  -o, --output-dir <dir>   Write <name>.<format> per input instead of stdout
+      --refine <gguf>      Polish each transcript with a local LLM
+                           (replaces txt output, added to json as \"refined\")
+      --beam <n>           Beam width; 1 is greedy (default 1)
+      --no-filter          Keep segments flagged as hallucinations";
+
+/// Output file format
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Txt,
+    Json,
+    Srt,
+    Vtt,
+}
+
+impl Format {
+    fn parse(value: &str) -> anyhow::Result<Self> {
+        match value {
+            "txt" | "text" => Ok(Self::Txt),
+            "json" => Ok(Self::Json),
+            "srt" => Ok(Self::Srt),
+            "vtt" => Ok(Self::Vtt),
+            other => Err(anyhow::anyhow!("Unknown format: {}", other)),
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Txt => "txt",
+            Self::Json => "json",
+            Self::Srt => "srt",
+            Self::Vtt => "vtt",
+        }
+    }
+}
+
+struct Options {
+    model: Option<PathBuf>,
+    params: WhisperParams,
+    format: Format,
+    output_dir: Option<PathBuf>,
+    refine_model: Option<PathBuf>,
+    filter: bool,
+    files: Vec<PathBuf>,
+}
+
+impl Options {
+    fn parse(args: &[String]) -> anyhow::Result<Self> {
+        let mut opts = Self {
+            model: None,
+            params: WhisperParams::default(),
+            format: Format::Txt,
+            output_dir: None,
+            refine_model: None,
+            filter: true,
+            files: Vec::new(),
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-m" | "--model" => opts.model = Some(take_value(arg, &mut args)?.into()),
+                "-l" | "--language" => {
+                    let lang = take_value(arg, &mut args)?;
+                    opts.params.language = (lang != "auto").then(|| lang.to_string());
+                }
+                "-f" | "--format" => opts.format = Format::parse(take_value(arg, &mut args)?)?,
+                "-o" | "--output-dir" => opts.output_dir = Some(take_value(arg, &mut args)?.into()),
+                "--refine" => opts.refine_model = Some(take_value(arg, &mut args)?.into()),
+                "--beam" => {
+                    let beam = take_value(arg, &mut args)?;
+                    opts.params.beam_size = beam
+                        .parse()
+                        .map_err(|_| anyhow::anyhow!("Invalid beam width: {}", beam))?;
+                }
+                "--no-filter" => opts.filter = false,
+                flag if flag.starts_with('-') => {
+                    return Err(anyhow::anyhow!("Unknown option: {}\n\n{}", flag, USAGE))
+                }
+                file => opts.files.push(file.into()),
+            }
+        }
+        if opts.files.is_empty() {
+            return Err(anyhow::anyhow!("No input files\n\n{}", USAGE));
+        }
+        let subtitles = matches!(opts.format, Format::Srt | Format::Vtt);
+        if subtitles && opts.output_dir.is_none() && opts.files.len() > 1 {
+            return Err(anyhow::anyhow!(
+                "Subtitles for several files need --output-dir"
+            ));
+        }
+        Ok(opts)
+    }
+}
+
+/// One transcribed file
+struct Transcript {
+    segments: Vec<Segment>,
+    text: String,
+    refined: Option<String>,
+    language: Option<mofa_input::asr::DetectedLanguage>,
+    duration_secs: f64,
+}
+
+pub fn run(args: &[String]) -> anyhow::Result<()> {
+    let opts = Options::parse(args)?;
+    let model = match &opts.model {
+        Some(path) => path.clone(),
+        None => default_model()?,
+    };
+
+    eprintln!("Loading ASR model {}...", model.display());
+    let session = AsrSession::with_params(&model, opts.params.clone())?;
+    if opts.filter {
+        session.set_filter(Some(HallucinationFilter::default()));
+    }
+    let chat = match &opts.refine_model {
+        Some(path) => {
+            eprintln!("Loading refine model {}...", path.display());
+            Some(ChatSession::new(path)?)
+        }
+        None => None,
+    };
+    if let Some(dir) = &opts.output_dir {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    let mut failed = 0;
+    for (i, path) in opts.files.iter().enumerate() {
+        eprintln!("[{}/{}] {}", i + 1, opts.files.len(), path.display());
+        let result = transcribe_file(&session, chat.as_ref(), path)
+            .and_then(|transcript| write_output(&opts, path, &transcript));
+        if let Err(e) = result {
+            eprintln!("  error: {}", e);
+            failed += 1;
+        }
+    }
+    if failed > 0 {
+        return Err(anyhow::anyhow!(
+            "{} of {} files failed",
+            failed,
+            opts.files.len()
+        ));
+    }
+    Ok(())
+}
+
+fn transcribe_file(
+    session: &AsrSession,
+    chat: Option<&ChatSession>,
+    path: &Path,
+) -> anyhow::Result<Transcript> {
+    let audio = mofa_input::audio::decode_file(path)?;
+    let duration_secs = audio.duration_secs();
+    let samples = audio.into_asr_input()?;
+
+    let start = Instant::now();
+    let result = session.transcribe_segments_with_progress(&samples, |_| {})?;
+    let text = result.text;
+    eprintln!(
+        "  {:.1}s audio in {:.1}s",
+        duration_secs,
+        start.elapsed().as_secs_f64()
+    );
+
+    let refined = match chat {
+        Some(chat) if !text.is_empty() => {
+            chat.clear();
+            Some(
+                chat.send(&refine_prompt(&text), 1024, 0.1)?
+                    .trim()
+                    .to_string(),
+            )
+        }
+        _ => None,
+    };
+
+    Ok(Transcript {
+        segments: result.segments,
+        text,
+        refined,
+        language: result.language,
+        duration_secs,
+    })
+}
+
+fn refine_prompt(raw_text: &str) -> String {
+    format!(
+        "将以下语音转写整理为通顺的书面文本：删除重复、卡顿和语气词，修复标点，\
+保留原意、专名、数字与原有语言，不新增信息。只输出整理后的文本。\n\n{}",
+        raw_text
+    )
+}
+
+fn render(format: Format, path: &Path, transcript: &Transcript) -> anyhow::Result<String> {
+    Ok(match format {
+        Format::Txt => {
+            let text = transcript.refined.as_ref().unwrap_or(&transcript.text);
+            format!("{}\n", text)
+        }
+        Format::Json => {
+            let value = serde_json::json!({
+                "file": path.display().to_string(),
+                "duration_secs": transcript.duration_secs,
+                "language": transcript.language,
+                "text": transcript.text,
+                "refined": transcript.refined,
+                "segments": transcript.segments,
+            });
+            format!("{}\n", serde_json::to_string_pretty(&value)?)
+        }
+        Format::Srt => transcript::to_srt(&transcript.segments),
+        Format::Vtt => transcript::to_vtt(&transcript.segments),
+    })
+}
+
+fn write_output(opts: &Options, path: &Path, transcript: &Transcript) -> anyhow::Result<()> {
+    let content = render(opts.format, path, transcript)?;
+    match &opts.output_dir {
+        Some(dir) => {
+            let stem = path
+                .file_stem()
+                .ok_or_else(|| anyhow::anyhow!("Invalid file name: {}", path.display()))?;
+            let name = format!("{}.{}", stem.to_string_lossy(), opts.format.extension());
+            let out = dir.join(name);
+            std::fs::write(&out, content)?;
+            eprintln!("  -> {}", out.display());
+        }
+        None if opts.files.len() > 1 && opts.format == Format::Txt => {
+            println!("== {} ==\n{}", path.display(), content);
+        }
+        None => print!("{}", content),
+    }
+    Ok(())
+}
@@ -2,3 +2,4 @@ pub mod llm;
 pub mod asr;
 pub mod audio;
 pub mod gui;
+pub mod text;
@@ -1,8 +1,11 @@
 pub mod ffi;
+pub mod worker;
 
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 
+pub use worker::{WorkerChatSession, WorkerConfig};
+
 /// Thread-safe wrapper for multi-turn conversations
 #[derive(Clone)]
 pub struct ChatSession {
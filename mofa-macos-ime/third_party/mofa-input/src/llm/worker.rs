@@ -0,0 +1,684 @@
+//! Out-of-process LLM worker
+//! llama.cpp aborts the whole process on malformed models and out-of-memory,
+//! so the host can run `ChatSession` in a child process and talk to it with
+//! line-delimited JSON over stdin/stdout. `WorkerChatSession` supervises the
+//! child and restarts it in the background after a crash.
+
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, TryRecvError};
+use std::sync::{Arc, Condvar, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use super::ChatSession;
+
+/// Request sent from the host to the worker, one JSON object per line
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum WorkerRequest {
+    Send {
+        message: String,
+        max_tokens: i32,
+        temperature: f32,
+    },
+    Clear,
+    TokenCount,
+}
+
+/// Message written by the worker, one JSON object per line
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WorkerResponse {
+    /// Model loaded, ready for requests
+    Ready,
+    Text { text: String },
+    Count { count: i32 },
+    Done,
+    Error { message: String },
+}
+
+/// Worker side: load the model and serve requests until stdin closes
+pub fn serve(model_path: &Path) -> anyhow::Result<()> {
+    let stdin = std::io::stdin();
+    let stdout = std::io::stdout();
+    serve_io(
+        ChatSession::new(model_path),
+        stdin.lock(),
+        &mut stdout.lock(),
+    )
+}
+
+/// What the worker needs from a loaded model
+trait WorkerModel {
+    fn send(&self, message: &str, max_tokens: i32, temperature: f32) -> anyhow::Result<String>;
+    fn clear(&self);
+    fn token_count(&self) -> i32;
+}
+
+impl WorkerModel for ChatSession {
+    fn send(&self, message: &str, max_tokens: i32, temperature: f32) -> anyhow::Result<String> {
+        ChatSession::send(self, message, max_tokens, temperature)
+    }
+
+    fn clear(&self) {
+        ChatSession::clear(self)
+    }
+
+    fn token_count(&self) -> i32 {
+        ChatSession::token_count(self)
+    }
+}
+
+/// Report the load result, then answer each request line until `input` ends
+fn serve_io<M: WorkerModel>(
+    model: anyhow::Result<M>,
+    input: impl BufRead,
+    out: &mut impl Write,
+) -> anyhow::Result<()> {
+    let model = match model {
+        Ok(m) => m,
+        Err(e) => {
+            write_message(
+                out,
+                &WorkerResponse::Error {
+                    message: e.to_string(),
+                },
+            )?;
+            return Err(e);
+        }
+    };
+    write_message(out, &WorkerResponse::Ready)?;
+
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<WorkerRequest>(&line) {
+            Ok(WorkerRequest::Send {
+                message,
+                max_tokens,
+                temperature,
+            }) => match model.send(&message, max_tokens, temperature) {
+                Ok(text) => WorkerResponse::Text { text },
+                Err(e) => WorkerResponse::Error {
+                    message: e.to_string(),
+                },
+            },
+            Ok(WorkerRequest::Clear) => {
+                model.clear();
+                WorkerResponse::Done
+            }
+            Ok(WorkerRequest::TokenCount) => WorkerResponse::Count {
+                count: model.token_count(),
+            },
+            Err(e) => WorkerResponse::Error {
+                message: format!("Invalid request: {e}"),
+            },
+        };
+        write_message(out, &response)?;
+    }
+
+    Ok(())
+}
+
+fn write_message<W: Write, T: Serialize>(out: &mut W, msg: &T) -> anyhow::Result<()> {
+    let line = serde_json::to_string(msg)?;
+    out.write_all(line.as_bytes())?;
+    out.write_all(b"\n")?;
+    out.flush()?;
+    Ok(())
+}
+
+/// How to launch and supervise the worker process
+#[derive(Clone, Debug)]
+pub struct WorkerConfig {
+    /// Path to the `mofa-llm-worker` executable
+    pub worker_exe: PathBuf,
+    /// GGUF model passed to the worker
+    pub model_path: PathBuf,
+    /// Max wait for the model to load
+    pub startup_timeout: Duration,
+    /// Max wait for a single request before the worker is considered hung
+    pub request_timeout: Duration,
+    /// First restart delay after a crash, doubled on each consecutive failure
+    pub restart_backoff: Duration,
+    /// Upper bound for the restart delay
+    pub max_restart_backoff: Duration,
+}
+
+impl WorkerConfig {
+    pub fn new(worker_exe: &Path, model_path: &Path) -> Self {
+        Self {
+            worker_exe: worker_exe.to_path_buf(),
+            model_path: model_path.to_path_buf(),
+            startup_timeout: Duration::from_secs(120),
+            request_timeout: Duration::from_secs(120),
+            restart_backoff: Duration::from_secs(1),
+            max_restart_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Thread-safe, crash-isolated counterpart of `ChatSession`
+///
+/// Requests fail fast while the worker is down or still loading, so callers
+/// can fall back to unrefined text instead of blocking.
+#[derive(Clone)]
+pub struct WorkerChatSession {
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    supervisor: Mutex<Supervisor>,
+    /// Signalled when a request hands the pipes back or the worker fails
+    pipes_returned: Condvar,
+}
+
+impl WorkerChatSession {
+    /// Launch the worker and wait until the model is loaded
+    pub fn spawn(config: WorkerConfig) -> anyhow::Result<Self> {
+        let mut process = WorkerProcess::launch(&config)?;
+        let ready = match process.pipes.as_mut() {
+            Some(pipes) => pipes.wait_ready(config.startup_timeout),
+            None => Err(anyhow::anyhow!("Worker pipes unavailable")),
+        };
+        if let Err(e) = ready {
+            process.kill();
+            return Err(e);
+        }
+
+        let shared = Arc::new(Shared {
+            supervisor: Mutex::new(Supervisor {
+                config,
+                process: Some(process),
+                generation: 0,
+                ready: true,
+                failures: 0,
+                last_failure: None,
+            }),
+            pipes_returned: Condvar::new(),
+        });
+        spawn_watchdog(Arc::downgrade(&shared));
+
+        Ok(Self { shared })
+    }
+
+    /// Send message and get complete response
+    pub fn send(&self, message: &str, max_tokens: i32, temperature: f32) -> anyhow::Result<String> {
+        let request = WorkerRequest::Send {
+            message: message.to_string(),
+            max_tokens,
+            temperature,
+        };
+        match self.request(&request)? {
+            WorkerResponse::Text { text } => Ok(text),
+            other => Err(anyhow::anyhow!("Unexpected worker response: {:?}", other)),
+        }
+    }
+
+    /// Clear conversation history
+    pub fn clear(&self) {
+        let _ = self.request(&WorkerRequest::Clear);
+    }
+
+    /// Get token count in KV cache
+    pub fn token_count(&self) -> i32 {
+        match self.request(&WorkerRequest::TokenCount) {
+            Ok(WorkerResponse::Count { count }) => count,
+            _ => 0,
+        }
+    }
+
+    /// Whether the worker is loaded and accepting requests
+    pub fn is_ready(&self) -> bool {
+        let mut sup = self.shared.supervisor.lock().unwrap();
+        sup.poll();
+        sup.ready
+    }
+
+    /// Round trip without the supervisor lock, so the watchdog keeps
+    /// checking the worker while a request is in flight
+    fn request(&self, request: &WorkerRequest) -> anyhow::Result<WorkerResponse> {
+        let (generation, mut pipes, timeout) = self.take_pipes()?;
+        let result = pipes.call(request, timeout);
+
+        let mut sup = self.shared.supervisor.lock().unwrap();
+        let result = match result {
+            Ok(WorkerResponse::Error { message }) => {
+                sup.return_pipes(generation, pipes);
+                Err(anyhow::anyhow!("LLM worker error: {message}"))
+            }
+            Ok(response) => {
+                if sup.return_pipes(generation, pipes) {
+                    sup.failures = 0;
+                }
+                Ok(response)
+            }
+            Err(e) => {
+                // The watchdog may already have replaced a crashed worker
+                if sup.generation == generation && sup.process.is_some() {
+                    sup.mark_failed(&e.to_string());
+                }
+                Err(e)
+            }
+        };
+        drop(sup);
+        self.shared.pipes_returned.notify_all();
+        result
+    }
+
+    /// Borrow the worker's pipes, waiting up to the request timeout for a
+    /// concurrent request to return them
+    fn take_pipes(&self) -> anyhow::Result<(u64, WorkerPipes, Duration)> {
+        let mut sup = self.shared.supervisor.lock().unwrap();
+        let timeout = sup.config.request_timeout;
+        let deadline = Instant::now() + timeout;
+        loop {
+            sup.poll();
+            if !sup.ready {
+                return Err(anyhow::anyhow!("LLM worker is restarting"));
+            }
+            let generation = sup.generation;
+            let Some(process) = sup.process.as_mut() else {
+                return Err(anyhow::anyhow!("LLM worker is not running"));
+            };
+            if let Some(pipes) = process.pipes.take() {
+                return Ok((generation, pipes, timeout));
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(anyhow::anyhow!("LLM worker is busy"));
+            }
+            sup = self
+                .shared
+                .pipes_returned
+                .wait_timeout(sup, deadline - now)
+                .unwrap()
+                .0;
+        }
+    }
+}
+
+struct Supervisor {
+    config: WorkerConfig,
+    process: Option<WorkerProcess>,
+    /// Bumped on every launch so pipes from a replaced worker are dropped
+    generation: u64,
+    ready: bool,
+    failures: u32,
+    last_failure: Option<Instant>,
+}
+
+impl Supervisor {
+    /// Detect exits, finish pending startups and relaunch after the backoff
+    fn poll(&mut self) {
+        if self.process.is_some() {
+            if let Err(reason) = self.check_process() {
+                self.mark_failed(&reason);
+            }
+            return;
+        }
+
+        let backoff = self.backoff();
+        let due = self
+            .last_failure
+            .map(|t| t.elapsed() >= backoff)
+            .unwrap_or(true);
+        if !due {
+            return;
+        }
+
+        match WorkerProcess::launch(&self.config) {
+            Ok(process) => {
+                self.process = Some(process);
+                self.generation += 1;
+                self.ready = false;
+            }
+            Err(e) => {
+                self.failures = self.failures.saturating_add(1);
+                self.last_failure = Some(Instant::now());
+                eprintln!("[mofa-llm] failed to relaunch worker: {e}");
+            }
+        }
+    }
+
+    fn check_process(&mut self) -> Result<(), String> {
+        let Some(process) = self.process.as_mut() else {
+            return Ok(());
+        };
+        if let Ok(Some(status)) = process.child.try_wait() {
+            return Err(format!("exited with {status}"));
+        }
+        if self.ready {
+            return Ok(());
+        }
+        // Not ready yet, so no request holds the pipes
+        let Some(pipes) = process.pipes.as_mut() else {
+            return Ok(());
+        };
+
+        match pipes.poll_ready() {
+            Ok(true) => {
+                self.ready = true;
+                eprintln!("[mofa-llm] worker restarted");
+                Ok(())
+            }
+            Ok(false) if process.started.elapsed() > self.config.startup_timeout => {
+                Err("startup timed out".to_string())
+            }
+            Ok(false) => Ok(()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    /// Give borrowed pipes back to the worker they came from; false if that
+    /// worker has been replaced or stopped in the meantime
+    fn return_pipes(&mut self, generation: u64, pipes: WorkerPipes) -> bool {
+        match self.process.as_mut() {
+            Some(process) if self.generation == generation => {
+                process.pipes = Some(pipes);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn mark_failed(&mut self, reason: &str) {
+        eprintln!("[mofa-llm] worker failed: {reason}");
+        if let Some(mut process) = self.process.take() {
+            process.kill();
+        }
+        self.ready = false;
+        self.failures = self.failures.saturating_add(1);
+        self.last_failure = Some(Instant::now());
+    }
+
+    fn backoff(&self) -> Duration {
+        let shift = self.failures.saturating_sub(1).min(16);
+        self.config
+            .restart_backoff
+            .saturating_mul(1u32 << shift)
+            .min(self.config.max_restart_backoff)
+    }
+}
+
+impl Drop for Supervisor {
+    fn drop(&mut self) {
+        if let Some(mut process) = self.process.take() {
+            process.kill();
+        }
+    }
+}
+
+struct WorkerProcess {
+    child: Child,
+    /// Taken by the request in flight
+    pipes: Option<WorkerPipes>,
+    started: Instant,
+}
+
+struct WorkerPipes {
+    stdin: ChildStdin,
+    responses: Receiver<WorkerResponse>,
+}
+
+impl WorkerProcess {
+    fn launch(config: &WorkerConfig) -> anyhow::Result<Self> {
+        let mut child = Command::new(&config.worker_exe)
+            .arg("--model")
+            .arg(&config.model_path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| anyhow::anyhow!("Failed to spawn {:?}: {e}", config.worker_exe))?;
+
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("Worker stdin unavailable"))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("Worker stdout unavailable"))?;
+
+        // The reader thread ends on EOF, which disconnects the channel and
+        // tells the supervisor that the worker died.
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                match serde_json::from_str::<WorkerResponse>(&line) {
+                    Ok(msg) => {
+                        if tx.send(msg).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => eprintln!("[mofa-llm] ignoring worker output {line:?}: {e}"),
+                }
+            }
+        });
+
+        Ok(Self {
+            child,
+            pipes: Some(WorkerPipes {
+                stdin,
+                responses: rx,
+            }),
+            started: Instant::now(),
+        })
+    }
+
+    fn kill(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+impl WorkerPipes {
+    fn wait_ready(&mut self, timeout: Duration) -> anyhow::Result<()> {
+        match self.responses.recv_timeout(timeout) {
+            Ok(WorkerResponse::Ready) => Ok(()),
+            Ok(WorkerResponse::Error { message }) => {
+                Err(anyhow::anyhow!("LLM worker failed to load model: {message}"))
+            }
+            Ok(other) => Err(anyhow::anyhow!("Unexpected worker response: {:?}", other)),
+            Err(RecvTimeoutError::Timeout) => Err(anyhow::anyhow!("LLM worker startup timed out")),
+            Err(RecvTimeoutError::Disconnected) => {
+                Err(anyhow::anyhow!("LLM worker exited during startup"))
+            }
+        }
+    }
+
+    fn poll_ready(&mut self) -> anyhow::Result<bool> {
+        match self.responses.try_recv() {
+            Ok(WorkerResponse::Ready) => Ok(true),
+            Ok(WorkerResponse::Error { message }) => {
+                Err(anyhow::anyhow!("LLM worker failed to load model: {message}"))
+            }
+            Ok(other) => Err(anyhow::anyhow!("Unexpected worker response: {:?}", other)),
+            Err(TryRecvError::Empty) => Ok(false),
+            Err(TryRecvError::Disconnected) => {
+                Err(anyhow::anyhow!("LLM worker exited during startup"))
+            }
+        }
+    }
+
+    fn call(&mut self, request: &WorkerRequest, timeout: Duration) -> anyhow::Result<WorkerResponse> {
+        write_message(&mut self.stdin, request)
+            .map_err(|e| anyhow::anyhow!("Failed to write to LLM worker: {e}"))?;
+
+        match self.responses.recv_timeout(timeout) {
+            Ok(response) => Ok(response),
+            Err(RecvTimeoutError::Timeout) => Err(anyhow::anyhow!("LLM worker timed out")),
+            Err(RecvTimeoutError::Disconnected) => Err(anyhow::anyhow!("LLM worker crashed")),
+        }
+    }
+}
+
+/// Relaunch the worker in the background so it is warm before the next request
+fn spawn_watchdog(shared: Weak<Shared>) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(Duration::from_millis(500));
+        let Some(shared) = shared.upgrade() else {
+            break;
+        };
+        shared.supervisor.lock().unwrap().poll();
+        // Waiting requests fail fast if the worker went down
+        shared.pipes_returned.notify_all();
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    /// Echoes messages back and counts one token per message
+    #[derive(Default)]
+    struct EchoModel {
+        history: Mutex<Vec<String>>,
+    }
+
+    impl WorkerModel for EchoModel {
+        fn send(
+            &self,
+            message: &str,
+            _max_tokens: i32,
+            _temperature: f32,
+        ) -> anyhow::Result<String> {
+            if message.is_empty() {
+                return Err(anyhow::anyhow!("empty message"));
+            }
+            self.history.lock().unwrap().push(message.to_string());
+            Ok(format!("echo: {message}"))
+        }
+
+        fn clear(&self) {
+            self.history.lock().unwrap().clear();
+        }
+
+        fn token_count(&self) -> i32 {
+            self.history.lock().unwrap().len() as i32
+        }
+    }
+
+    fn responses(out: &[u8]) -> Vec<WorkerResponse> {
+        String::from_utf8_lossy(out)
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn messages_round_trip_as_tagged_json() {
+        let requests = [
+            WorkerRequest::Send {
+                message: "你好".to_string(),
+                max_tokens: 64,
+                temperature: 0.5,
+            },
+            WorkerRequest::Clear,
+            WorkerRequest::TokenCount,
+        ];
+        for request in requests {
+            let line = serde_json::to_string(&request).unwrap();
+            assert!(!line.contains('\n'));
+            assert_eq!(
+                serde_json::from_str::<WorkerRequest>(&line).unwrap(),
+                request
+            );
+        }
+        assert_eq!(
+            serde_json::to_string(&WorkerRequest::TokenCount).unwrap(),
+            r#"{"op":"token_count"}"#
+        );
+
+        let responses = [
+            WorkerResponse::Ready,
+            WorkerResponse::Text {
+                text: "line one\nline two".to_string(),
+            },
+            WorkerResponse::Count { count: 7 },
+            WorkerResponse::Done,
+            WorkerResponse::Error {
+                message: "oops".to_string(),
+            },
+        ];
+        for response in responses {
+            let line = serde_json::to_string(&response).unwrap();
+            assert!(!line.contains('\n'));
+            assert_eq!(
+                serde_json::from_str::<WorkerResponse>(&line).unwrap(),
+                response
+            );
+        }
+        assert_eq!(
+            serde_json::to_string(&WorkerResponse::Count { count: 7 }).unwrap(),
+            r#"{"kind":"count","count":7}"#
+        );
+    }
+
+    #[test]
+    fn serve_answers_each_request_line() {
+        let input = [
+            r#"{"op":"send","message":"hi","max_tokens":8,"temperature":0.0}"#,
+            "",
+            r#"{"op":"token_count"}"#,
+            r#"{"op":"send","message":"","max_tokens":8,"temperature":0.0}"#,
+            "not json",
+            r#"{"op":"clear"}"#,
+            r#"{"op":"token_count"}"#,
+        ]
+        .join("\n");
+        let mut out = Vec::new();
+
+        serve_io(Ok(EchoModel::default()), Cursor::new(input), &mut out).unwrap();
+
+        let responses = responses(&out);
+        assert_eq!(responses.len(), 7);
+        assert_eq!(responses[0], WorkerResponse::Ready);
+        assert_eq!(
+            responses[1],
+            WorkerResponse::Text {
+                text: "echo: hi".to_string()
+            }
+        );
+        assert_eq!(responses[2], WorkerResponse::Count { count: 1 });
+        assert_eq!(
+            responses[3],
+            WorkerResponse::Error {
+                message: "empty message".to_string()
+            }
+        );
+        assert!(matches!(
+            &responses[4],
+            WorkerResponse::Error { message } if message.starts_with("Invalid request")
+        ));
+        assert_eq!(responses[5], WorkerResponse::Done);
+        assert_eq!(responses[6], WorkerResponse::Count { count: 0 });
+    }
+
+    #[test]
+    fn serve_reports_a_failed_load_and_stops() {
+        let mut out = Vec::new();
+        let input = Cursor::new(r#"{"op":"clear"}"#);
+
+        let result = serve_io::<EchoModel>(Err(anyhow::anyhow!("bad model")), input, &mut out);
+
+        assert!(result.is_err());
+        assert_eq!(
+            responses(&out),
+            [WorkerResponse::Error {
+                message: "bad model".to_string()
+            }]
+        );
+    }
+}
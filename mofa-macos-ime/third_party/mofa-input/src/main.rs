@@ -1,7 +1,25 @@
-fn main() -> anyhow::Result<()> {
-    println!("Mofa Input - Starting...");
+mod cli;
+
+const USAGE: &str = "\
+Usage: mofa-input <command> [options]
+
+Commands:
+  transcribe   Transcribe audio files to text, JSON or subtitles
+  eval         Score ASR models against reference transcripts (CER/WER)
 
-    // TODO: Initialize GUI with integrated ASR + LLM
+Run `mofa-input <command> --help` for command options.";
+
+fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let wants_help = args.iter().any(|a| a == "-h" || a == "--help");
 
+    match args.first().map(String::as_str) {
+        Some("transcribe") if wants_help => println!("{}", cli::transcribe::USAGE),
+        Some("transcribe") => cli::transcribe::run(&args[1..])?,
+        Some("eval") if wants_help => println!("{}", cli::eval::USAGE),
+        Some("eval") => cli::eval::run(&args[1..])?,
+        None | Some("-h" | "--help" | "help") => println!("{}", USAGE),
+        Some(other) => return Err(anyhow::anyhow!("Unknown command: {}\n\n{}", other, USAGE)),
+    }
     Ok(())
 }
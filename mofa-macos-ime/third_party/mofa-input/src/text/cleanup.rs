@@ -0,0 +1,511 @@
+//! Deterministic transcript cleanup for output without LLM refine
+//!
+//! Removes filler words and stuttered repeats, and restores sentence
+//! punctuation from the pauses between timed tokens. Nothing is ever appended
+//! after the last word, so a transcript without a final period stays that way.
+
+use std::path::Path;
+
+use crate::asr::Segment;
+
+/// Fillers removed when they stand alone (between punctuation or spaces)
+const DEFAULT_FILLERS: &[&str] = &[
+    "嗯",
+    "呃",
+    "额",
+    "啊",
+    "哦",
+    "那个",
+    "就是说",
+    "um",
+    "umm",
+    "uh",
+    "uhh",
+    "er",
+    "erm",
+    "ah",
+    "hmm",
+    "mm",
+];
+
+/// Interjections also removed inside running Chinese text ("我觉得嗯还行")
+const CN_INTERJECTIONS: &str = "嗯呃额";
+
+/// Words where 额 means "amount" or "forehead" and must stay
+const CN_INTERJECTION_WORDS: &[&str] = &[
+    "额外", "额度", "额头", "金额", "余额", "名额", "超额", "限额", "配额", "份额", "总额", "差额",
+    "定额", "巨额", "数额", "前额",
+];
+
+/// Characters commonly stuttered in Chinese speech ("我我觉得"); 是 and 有
+/// are left out since "是是是" and "有有" are said on purpose
+const CN_STUTTER: &str = "我你他她它这那就在要会把";
+
+/// English words commonly stuttered ("I I think"); other doubles such as
+/// "bye bye", "very very" or "had had" are kept
+const EN_STUTTER: &[&str] = &[
+    "i", "a", "an", "the", "and", "but", "to", "of", "in", "for", "with", "we", "you", "he", "she",
+    "it", "they", "my",
+];
+
+/// Rule-based cleanup settings
+#[derive(Clone, Debug)]
+pub struct Cleanup {
+    /// Gap between tokens that becomes a comma
+    pub comma_pause_ms: i64,
+    /// Gap between tokens that ends a sentence
+    pub period_pause_ms: i64,
+    /// Lowercase filler words, longest first
+    fillers: Vec<String>,
+}
+
+impl Default for Cleanup {
+    fn default() -> Self {
+        let mut cleanup = Self {
+            comma_pause_ms: 500,
+            period_pause_ms: 1000,
+            fillers: Vec::new(),
+        };
+        for filler in DEFAULT_FILLERS {
+            cleanup.add_filler(filler);
+        }
+        cleanup
+    }
+}
+
+impl Cleanup {
+    pub fn add_filler(&mut self, filler: &str) {
+        let filler = filler.trim().to_lowercase();
+        if !filler.is_empty() && !self.fillers.contains(&filler) {
+            self.fillers.push(filler);
+            self.fillers
+                .sort_by_key(|f| std::cmp::Reverse(f.chars().count()));
+        }
+    }
+
+    /// Stop treating `filler` as a filler word
+    pub fn remove_filler(&mut self, filler: &str) {
+        let filler = filler.trim().to_lowercase();
+        self.fillers.retain(|f| *f != filler);
+    }
+
+    /// Load fillers from a file, one per line; `#` starts a comment and a
+    /// leading `-` removes a default filler
+    pub fn load_fillers(&mut self, path: &Path) -> anyhow::Result<()> {
+        let content = std::fs::read_to_string(path)?;
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.strip_prefix('-') {
+                Some(filler) => self.remove_filler(filler),
+                None => self.add_filler(line),
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove fillers and repeated words, then tidy punctuation
+    pub fn clean(&self, text: &str) -> String {
+        let text = self.remove_fillers(text);
+        let text = dedupe_chinese(&text);
+        let text = dedupe_english(&text);
+        tidy_punctuation(&text)
+    }
+
+    /// Join segments, inserting punctuation at long pauses that have none
+    pub fn punctuate(&self, segments: &[Segment]) -> String {
+        let mut out = String::new();
+        let mut prev_end: Option<i64> = None;
+        for seg in segments {
+            let pieces: Vec<(&str, i64, i64)> = if seg.tokens.is_empty() {
+                vec![(seg.text.as_str(), seg.start_ms, seg.end_ms)]
+            } else {
+                seg.tokens
+                    .iter()
+                    .map(|t| (t.text.as_str(), t.t0, t.t1))
+                    .collect()
+            };
+            for (text, t0, t1) in pieces {
+                let gap = prev_end.map(|end| t0 - end).unwrap_or(0);
+                if gap >= self.comma_pause_ms {
+                    push_after_pause(&mut out, text, gap >= self.period_pause_ms);
+                } else {
+                    out.push_str(text);
+                }
+                prev_end = Some(t1);
+            }
+        }
+        out.trim().to_string()
+    }
+
+    fn remove_fillers(&self, text: &str) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        let mut out = String::with_capacity(text.len());
+        let mut i = 0;
+        'outer: while i < chars.len() {
+            let at_boundary = i == 0 || is_boundary(chars[i - 1]);
+            if at_boundary {
+                for filler in &self.fillers {
+                    let Some(len) = match_repeated(&chars[i..], filler) else {
+                        continue;
+                    };
+                    if chars.get(i + len).is_some_and(|&c| !is_boundary(c)) {
+                        continue;
+                    }
+                    // Drop the filler together with its trailing comma
+                    let mut end = i + len;
+                    while end < chars.len() && (chars[end].is_whitespace() || is_pause(chars[end]))
+                    {
+                        end += 1;
+                    }
+                    // "should, uh, go" → "should go"
+                    if filler.is_ascii() && out.trim_end().ends_with(',') {
+                        let cut = out.trim_end().len() - 1;
+                        out.truncate(cut);
+                        out.push(' ');
+                    }
+                    // "Um, so we" → "So we"
+                    let sentence_start =
+                        out.trim_end().is_empty() || out.trim_end().ends_with(is_sentence_end);
+                    let next = chars.get(end).copied();
+                    if sentence_start && chars[i].is_ascii_uppercase() {
+                        if let Some(c) = next.filter(char::is_ascii_lowercase) {
+                            out.push(c.to_ascii_uppercase());
+                            end += 1;
+                        }
+                    }
+                    i = end;
+                    continue 'outer;
+                }
+            }
+            if let Some(len) = self.interjection_at(&chars, i) {
+                i += len;
+                continue;
+            }
+            out.push(chars[i]);
+            i += 1;
+        }
+        out
+    }
+
+    /// Length of a run of one interjection at `i` that touches Chinese text
+    /// on either side, such as the 嗯 in "我觉得嗯还行"
+    fn interjection_at(&self, chars: &[char], i: usize) -> Option<usize> {
+        let c = chars[i];
+        if !CN_INTERJECTIONS.contains(c) || !self.fillers.iter().any(|f| f.chars().eq([c])) {
+            return None;
+        }
+        let len = match_repeated(&chars[i..], &c.to_string())?;
+        let cjk_before = i > 0 && is_cjk(chars[i - 1]);
+        let cjk_after = chars.get(i + len).is_some_and(|&n| is_cjk(n));
+        if !(cjk_before || cjk_after) || within_word(chars, i, CN_INTERJECTION_WORDS) {
+            return None;
+        }
+        Some(len)
+    }
+}
+
+/// Whether `chars[i]` is part of one of `words` in place
+fn within_word(chars: &[char], i: usize, words: &[&str]) -> bool {
+    words.iter().any(|word| {
+        let word: Vec<char> = word.chars().collect();
+        word.iter()
+            .enumerate()
+            .filter(|(_, &c)| c == chars[i])
+            .any(|(k, _)| i >= k && chars.get(i - k..i - k + word.len()) == Some(&word[..]))
+    })
+}
+
+/// Length in chars of one or more back-to-back copies of `word` at the start
+/// of `chars` (case-insensitive), if any
+fn match_repeated(chars: &[char], word: &str) -> Option<usize> {
+    let word: Vec<char> = word.chars().collect();
+    let matches_at = |pos: usize| {
+        chars.len() >= pos + word.len()
+            && chars[pos..pos + word.len()]
+                .iter()
+                .zip(&word)
+                .all(|(a, b)| a.to_lowercase().eq(b.to_lowercase()))
+    };
+    let mut len = 0;
+    while matches_at(len) {
+        len += word.len();
+    }
+    (len > 0).then_some(len)
+}
+
+/// Collapse "然后，然后" / "我我" style repeats
+fn dedupe_chinese(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    'outer: while i < chars.len() {
+        for len in (1..=4).rev() {
+            let Some(unit) = chars.get(i..i + len) else {
+                continue;
+            };
+            if !unit.iter().all(|&c| is_cjk(c)) {
+                continue;
+            }
+            let mut end = i + len;
+            let mut copies = 1;
+            let mut separated = false;
+            loop {
+                let sep = chars[end..]
+                    .iter()
+                    .take_while(|&&c| c == ' ' || is_pause(c))
+                    .count();
+                if chars.get(end + sep..end + sep + len) != Some(unit) {
+                    break;
+                }
+                separated |= sep > 0;
+                end += sep + len;
+                copies += 1;
+            }
+            // AB AB reduplication ("研究研究") is only a stutter when separated
+            // or repeated more than twice
+            let stutter = if len == 1 {
+                CN_STUTTER.contains(unit[0])
+            } else {
+                separated || copies > 2
+            };
+            if copies > 1 && stutter {
+                out.extend(unit);
+                i = end;
+                continue 'outer;
+            }
+        }
+        out.push(chars[i]);
+        i += 1;
+    }
+    out
+}
+
+/// Drop a word repeated right after itself ("I I think", "the, the")
+fn dedupe_english(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut prev_word = String::new();
+    let mut pending_sep = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if !c.is_ascii_alphabetic() {
+            pending_sep.push(c);
+            continue;
+        }
+        let mut word = c.to_string();
+        while let Some(&n) = chars.peek() {
+            if n.is_ascii_alphabetic() || n == '\'' {
+                word.push(n);
+                chars.next();
+            } else {
+                break;
+            }
+        }
+        let repeat = !prev_word.is_empty()
+            && word.eq_ignore_ascii_case(&prev_word)
+            && EN_STUTTER.contains(&word.to_ascii_lowercase().as_str())
+            && pending_sep.chars().all(|c| c == ' ' || c == ',');
+        if repeat {
+            pending_sep.clear();
+            continue;
+        }
+        out.push_str(&pending_sep);
+        out.push_str(&word);
+        pending_sep.clear();
+        prev_word = word;
+    }
+    out.push_str(&pending_sep);
+    out
+}
+
+/// Fix punctuation left behind by removals: leading or doubled commas and a
+/// comma directly before a sentence end
+fn tidy_punctuation(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !out.is_empty() && !out.ends_with(char::is_whitespace) {
+                out.push(' ');
+            }
+            continue;
+        }
+        if is_pause(c) || is_sentence_end(c) {
+            let trimmed = out.trim_end();
+            if trimmed.is_empty() {
+                continue;
+            }
+            if let Some(last) = trimmed.chars().last() {
+                if is_pause(last) || (is_sentence_end(last) && is_pause(c)) {
+                    if is_pause(c) {
+                        continue;
+                    }
+                    // "，。" → "。"
+                    let cut = trimmed.len() - last.len_utf8();
+                    out.truncate(cut);
+                } else {
+                    out.truncate(trimmed.len());
+                }
+            }
+        }
+        out.push(c);
+    }
+    let out = out.trim();
+    out.trim_end_matches(is_pause).trim_end().to_string()
+}
+
+/// Append `text` after a pause, adding a comma or full stop when neither
+/// side already has punctuation
+fn push_after_pause(out: &mut String, text: &str, full_stop: bool) {
+    let trimmed_len = out.trim_end().len();
+    let last = out[..trimmed_len].chars().last();
+    let next = text.trim_start().chars().next();
+    let (Some(last), Some(next)) = (last, next) else {
+        out.push_str(text);
+        return;
+    };
+    let cjk = is_cjk(last) || is_cjk(next);
+    // Latin sub-word pieces carry no leading space
+    let word_start = cjk || text.starts_with(char::is_whitespace);
+    if (is_boundary(last) && !last.is_whitespace()) || is_boundary(next) || !word_start {
+        out.push_str(text);
+        return;
+    }
+
+    out.truncate(trimmed_len);
+    let text = text.trim_start();
+    if cjk {
+        out.push(if full_stop { '。' } else { '，' });
+        out.push_str(text);
+    } else {
+        out.push_str(if full_stop { ". " } else { ", " });
+        if full_stop {
+            let mut rest = text.chars();
+            if let Some(first) = rest.next() {
+                out.push(first.to_ascii_uppercase());
+                out.push_str(rest.as_str());
+            }
+        } else {
+            out.push_str(text);
+        }
+    }
+}
+
+pub(super) fn is_cjk(c: char) -> bool {
+    matches!(c, '\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}' | '\u{F900}'..='\u{FAFF}')
+}
+
+/// Mid-sentence punctuation
+fn is_pause(c: char) -> bool {
+    matches!(c, ',' | '，' | '、' | ';' | '；' | ':' | '：')
+}
+
+fn is_sentence_end(c: char) -> bool {
+    matches!(c, '。' | '！' | '？' | '.' | '!' | '?' | '…')
+}
+
+fn is_boundary(c: char) -> bool {
+    c.is_whitespace()
+        || c.is_ascii_punctuation()
+        || is_pause(c)
+        || is_sentence_end(c)
+        || matches!(c, '“' | '”' | '‘' | '’' | '（' | '）' | '《' | '》' | '—')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asr::Token;
+
+    fn assert_clean(cases: &[(&str, &str)]) {
+        let cleanup = Cleanup::default();
+        for (raw, cleaned) in cases {
+            assert_eq!(cleanup.clean(raw), *cleaned, "input: {raw}");
+        }
+    }
+
+    #[test]
+    fn removes_standalone_fillers() {
+        assert_clean(&[
+            ("嗯，我们明天去吧", "我们明天去吧"),
+            ("我们，那个，明天去吧", "我们，明天去吧"),
+            ("Um, so we should, uh, go", "So we should go"),
+            ("I think umm that works", "I think that works"),
+        ]);
+    }
+
+    #[test]
+    fn removes_interjections_in_running_chinese() {
+        assert_clean(&[
+            ("嗯我们那个明天去吧", "我们那个明天去吧"),
+            ("呃我觉得", "我觉得"),
+            ("我觉得嗯还行", "我觉得还行"),
+            ("额我觉得嗯嗯还行", "我觉得还行"),
+        ]);
+    }
+
+    #[test]
+    fn keeps_words_containing_fillers() {
+        assert_clean(&[
+            ("这笔金额不小", "这笔金额不小"),
+            ("额外的费用", "额外的费用"),
+            ("那个人是谁", "那个人是谁"),
+            ("umbrella", "umbrella"),
+        ]);
+        let mut cleanup = Cleanup::default();
+        cleanup.remove_filler("嗯");
+        assert_eq!(cleanup.clean("我觉得嗯还行"), "我觉得嗯还行");
+    }
+
+    #[test]
+    fn collapses_repeats() {
+        assert_clean(&[
+            ("我我觉得可以", "我觉得可以"),
+            ("然后，然后我们走了", "然后我们走了"),
+            ("我们研究研究", "我们研究研究"),
+            ("I I think the the plan works", "I think the plan works"),
+            ("he had had enough", "he had had enough"),
+        ]);
+    }
+
+    #[test]
+    fn keeps_intentional_doubles() {
+        assert_clean(&[
+            ("是是是，我马上来", "是是是，我马上来"),
+            ("有有，在桌上", "有有，在桌上"),
+            ("好好学习", "好好学习"),
+            ("bye bye", "bye bye"),
+            ("it was very very good", "it was very very good"),
+            ("no no, not that one", "no no, not that one"),
+            ("it was so so", "it was so so"),
+            ("I know that that works", "I know that that works"),
+        ]);
+    }
+
+    fn token(text: &str, t0: i64, t1: i64) -> Token {
+        Token {
+            text: text.to_string(),
+            p: 1.0,
+            t0,
+            t1,
+        }
+    }
+
+    #[test]
+    fn punctuates_long_pauses() {
+        let segment = Segment {
+            start_ms: 0,
+            end_ms: 3000,
+            text: String::new(),
+            avg_logprob: 0.0,
+            no_speech_prob: 0.0,
+            tokens: vec![
+                token("我们", 0, 400),
+                token("明天", 1000, 1400),
+                token("去", 2600, 2800),
+            ],
+        };
+        assert_eq!(Cleanup::default().punctuate(&[segment]), "我们，明天。去");
+    }
+}
@@ -0,0 +1,767 @@
+//! Rule-based inverse text normalization (ITN)
+//!
+//! Rewrites spoken numbers in transcripts to their written form:
+//! "百分之五十" → "50%", "二零二六年十月" → "2026年10月",
+//! "three thirty pm" → "3:30 PM", "twenty five dollars" → "$25".
+//! Rules only fire in an unambiguous context (a unit, measure word, month,
+//! meridiem, ...), so idioms such as "十分" or "一下" are left alone.
+
+use super::cleanup::is_cjk;
+
+/// Apply Chinese and English ITN rules
+pub fn inverse_normalize(text: &str) -> String {
+    english(&chinese(text))
+}
+
+// ---------------------------------------------------------------------------
+// Chinese
+// ---------------------------------------------------------------------------
+
+/// Measure words after which a number ≥ 10 is written in digits
+const CN_MEASURES: &[&str] = &[
+    "个", "位", "人", "次", "岁", "天", "年", "周", "小时", "分钟", "秒", "公里", "千米", "米",
+    "厘米", "毫米", "公斤", "千克", "克", "斤", "吨", "页", "章", "件", "条", "本", "张", "台",
+    "辆", "家", "层", "楼", "号", "度", "倍", "多", "余", "块",
+];
+
+/// Currency units; numbers before them are always converted
+const CN_CURRENCY: &[&str] = &["元", "块钱", "美元", "美金", "欧元", "英镑", "日元"];
+
+fn cn_digit(c: char) -> Option<u64> {
+    Some(match c {
+        '零' | '〇' => 0,
+        '一' => 1,
+        '二' | '两' => 2,
+        '三' => 3,
+        '四' => 4,
+        '五' => 5,
+        '六' => 6,
+        '七' => 7,
+        '八' => 8,
+        '九' => 9,
+        _ => return None,
+    })
+}
+
+fn cn_unit(c: char) -> Option<u64> {
+    Some(match c {
+        '十' => 10,
+        '百' => 100,
+        '千' => 1_000,
+        '万' => 10_000,
+        '亿' => 100_000_000,
+        _ => return None,
+    })
+}
+
+fn is_cn_num(c: char) -> bool {
+    cn_digit(c).is_some() || cn_unit(c).is_some()
+}
+
+/// Value of a Chinese numeral such as "三千零五十" or colloquial "一万五";
+/// `None` if it is malformed or does not fit in a `u64`
+fn parse_cn_int(chars: &[char]) -> Option<u64> {
+    let mut total = 0u64;
+    let mut section = 0u64;
+    let mut number = 0u64;
+    for (i, &c) in chars.iter().enumerate() {
+        if let Some(d) = cn_digit(c) {
+            // Only 零 may precede another digit ("五六个" is a range, not 56)
+            if i > 0 && cn_digit(chars[i - 1]).is_some_and(|p| p != 0) {
+                return None;
+            }
+            number = d;
+        } else if let Some(unit) = cn_unit(c) {
+            match unit {
+                10 | 100 | 1_000 => {
+                    // "十五" starts with an implicit one
+                    let n = if number == 0 && unit == 10 { 1 } else { number };
+                    if n == 0 {
+                        return None;
+                    }
+                    section = section.checked_add(n * unit)?;
+                }
+                _ => {
+                    let value = section + number;
+                    if value == 0 && total == 0 {
+                        return None;
+                    }
+                    total = total.checked_add(value)?.checked_mul(unit)?;
+                    section = 0;
+                }
+            }
+            number = 0;
+        }
+    }
+    // "一万五" / "三百二" drop the trailing unit
+    if let [.., unit, last] = chars {
+        if let (Some(unit), Some(_)) = (cn_unit(*unit), cn_digit(*last)) {
+            if unit >= 100 {
+                number *= unit / 10;
+            }
+        }
+    }
+    total.checked_add(section)?.checked_add(number)
+}
+
+/// Digit-by-digit reading such as "二零二六"
+fn is_cn_digit_run(chars: &[char]) -> bool {
+    chars.len() > 1 && chars.iter().all(|&c| cn_digit(c).is_some() && c != '两')
+}
+
+fn cn_digits_str(chars: &[char]) -> Option<String> {
+    chars
+        .iter()
+        .map(|&c| match c {
+            '两' => None,
+            c => cn_digit(c).and_then(|d| char::from_digit(d as u32, 10)),
+        })
+        .collect()
+}
+
+fn starts_with_any<'a>(rest: &[char], words: &[&'a str]) -> Option<&'a str> {
+    words.iter().copied().find(|w| {
+        let n = w.chars().count();
+        rest.len() >= n && rest[..n].iter().copied().eq(w.chars())
+    })
+}
+
+fn chinese(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < chars.len() {
+        if !is_cn_num(chars[i]) {
+            out.push(chars[i]);
+            i += 1;
+            continue;
+        }
+        let mut j = i;
+        while j < chars.len() && is_cn_num(chars[j]) {
+            j += 1;
+        }
+        match convert_cn_number(&chars, i, j, &mut out) {
+            Some(end) => i = end,
+            None => {
+                out.extend(&chars[i..j]);
+                i = j;
+            }
+        }
+    }
+    out
+}
+
+/// Convert the numeral `chars[start..end]` given its context; returns the
+/// index after everything consumed, or `None` to leave it as spoken
+fn convert_cn_number(chars: &[char], start: usize, end: usize, out: &mut String) -> Option<usize> {
+    let span = &chars[start..end];
+    let rest = &chars[end..];
+
+    // Year read digit by digit: 二零二六年
+    if is_cn_digit_run(span) {
+        if rest.first() == Some(&'年') && (span.len() == 2 || span.len() == 4) {
+            out.push_str(&cn_digits_str(span)?);
+            return Some(end);
+        }
+        return None;
+    }
+
+    let int = parse_cn_int(span)?;
+
+    // Clock time: 三点半 / 十点十五分 / 八点一刻
+    if rest.first() == Some(&'点') && int <= 24 {
+        let after = &rest[1..];
+        if after.first() == Some(&'半') {
+            out.push_str(&format!("{int}:30"));
+            return Some(end + 2);
+        }
+        if after.len() >= 2 && after[1] == '刻' && matches!(after[0], '一' | '三') {
+            let minutes = if after[0] == '一' { 15 } else { 45 };
+            out.push_str(&format!("{int}:{minutes}"));
+            return Some(end + 3);
+        }
+        let m_len = after.iter().take_while(|&&c| is_cn_num(c)).count();
+        if m_len > 0 && after.get(m_len) == Some(&'分') {
+            let minutes = parse_cn_int(&after[..m_len])?;
+            let consumed = end + 1 + m_len + 1;
+            // "分钟" is a duration, not a clock time
+            if minutes < 60 && chars.get(consumed) != Some(&'钟') {
+                out.push_str(&format!("{int}:{minutes:02}"));
+                return Some(consumed);
+            }
+        }
+    }
+
+    // Decimal: 三点五
+    let mut value = int.to_string();
+    let mut has_unit = span.iter().any(|&c| cn_unit(c).is_some());
+    let mut next = end;
+    if rest.first() == Some(&'点') {
+        let frac_len = rest[1..]
+            .iter()
+            .take_while(|&&c| cn_digit(c).is_some() && c != '两')
+            .count();
+        if frac_len > 0 {
+            value = format!("{int}.{}", cn_digits_str(&rest[1..1 + frac_len])?);
+            next = end + 1 + frac_len;
+            has_unit = true;
+        }
+    }
+    let rest = &chars[next..];
+
+    // Percentage: 百分之五十
+    if out.ends_with("百分之") {
+        out.truncate(out.len() - "百分之".len());
+        out.push_str(&value);
+        out.push('%');
+        return Some(next);
+    }
+
+    // Month and day: 十月五日
+    if rest.first() == Some(&'月') && (1..=12).contains(&int) && next == end {
+        out.push_str(&value);
+        return Some(next);
+    }
+    if matches!(rest.first(), Some('日' | '号')) && (1..=31).contains(&int) && out.ends_with('月')
+    {
+        out.push_str(&value);
+        return Some(next);
+    }
+
+    // Currency: 五十块钱 / 三点五元
+    if starts_with_any(rest, CN_CURRENCY).is_some() {
+        out.push_str(&value);
+        return Some(next);
+    }
+
+    // Ordinals and measure words, from ten up
+    let counted = out.ends_with('第') || starts_with_any(rest, CN_MEASURES).is_some();
+    if int >= 10 && has_unit && counted {
+        out.push_str(&value);
+        return Some(next);
+    }
+
+    // Compound numerals on their own: 两千零五十, but not 三百六十行
+    let units = span.iter().filter(|&&c| cn_unit(c).is_some()).count();
+    if units >= 2 && next == end && !rest.first().is_some_and(|&c| is_cjk(c)) {
+        out.push_str(&value);
+        return Some(next);
+    }
+    None
+}
+
+// ---------------------------------------------------------------------------
+// English
+// ---------------------------------------------------------------------------
+
+const EN_UNITS: &[&str] = &[
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+const EN_TEENS: &[&str] = &[
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+const EN_TENS: &[&str] = &[
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+const EN_ORDINALS: &[&str] = &[
+    "",
+    "first",
+    "second",
+    "third",
+    "fourth",
+    "fifth",
+    "sixth",
+    "seventh",
+    "eighth",
+    "ninth",
+    "tenth",
+    "eleventh",
+    "twelfth",
+    "thirteenth",
+    "fourteenth",
+    "fifteenth",
+    "sixteenth",
+    "seventeenth",
+    "eighteenth",
+    "nineteenth",
+    "twentieth",
+];
+const EN_MONTHS: &[&str] = &[
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum WordKind {
+    Unit(u64),
+    Teen(u64),
+    Tens(u64),
+    Hundred,
+    Scale(u64),
+}
+
+fn word_kind(word: &str) -> Option<WordKind> {
+    if let Some(n) = EN_UNITS.iter().position(|&w| w == word) {
+        return Some(WordKind::Unit(n as u64));
+    }
+    if let Some(n) = EN_TEENS.iter().position(|&w| w == word) {
+        return Some(WordKind::Teen(10 + n as u64));
+    }
+    if let Some(n) = EN_TENS.iter().position(|&w| !w.is_empty() && w == word) {
+        return Some(WordKind::Tens(n as u64 * 10));
+    }
+    match word {
+        "hundred" => Some(WordKind::Hundred),
+        "thousand" => Some(WordKind::Scale(1_000)),
+        "million" => Some(WordKind::Scale(1_000_000)),
+        "billion" => Some(WordKind::Scale(1_000_000_000)),
+        _ => None,
+    }
+}
+
+struct Tok {
+    word: String,
+    lower: String,
+    /// Text between this word and the next one
+    sep: String,
+}
+
+/// A parsed cardinal number spanning `len` words
+struct Number {
+    int: u64,
+    frac: Option<String>,
+    len: usize,
+}
+
+impl Number {
+    fn written(&self) -> String {
+        match &self.frac {
+            Some(frac) => format!("{}.{frac}", self.int),
+            None => self.int.to_string(),
+        }
+    }
+}
+
+struct Words {
+    toks: Vec<Tok>,
+}
+
+impl Words {
+    fn lower(&self, i: usize) -> Option<&str> {
+        self.toks.get(i).map(|t| t.lower.as_str())
+    }
+
+    /// Word `i` is followed by word `i + 1` with only spaces or a hyphen between
+    fn linked(&self, i: usize) -> bool {
+        i + 1 < self.toks.len()
+            && !self.toks[i].sep.is_empty()
+            && self.toks[i].sep.chars().all(|c| c == ' ' || c == '-')
+    }
+
+    fn linked_word(&self, i: usize) -> Option<&str> {
+        if self.linked(i) {
+            self.lower(i + 1)
+        } else {
+            None
+        }
+    }
+
+    fn cardinal(&self, start: usize) -> Option<Number> {
+        let mut total = 0u64;
+        let mut current = 0u64;
+        let mut last: Option<WordKind> = None;
+        let mut last_scale = u64::MAX;
+        let mut len = 0;
+        let mut i = start;
+        loop {
+            if i > start && !self.linked(i - 1) {
+                break;
+            }
+            let Some(word) = self.lower(i) else { break };
+            if word == "and"
+                && matches!(last, Some(WordKind::Hundred | WordKind::Scale(_)))
+                && self.linked_word(i).and_then(word_kind).is_some_and(|k| {
+                    matches!(k, WordKind::Unit(_) | WordKind::Teen(_) | WordKind::Tens(_))
+                })
+            {
+                i += 1;
+                continue;
+            }
+            let Some(kind) = word_kind(word) else { break };
+            let small = matches!(
+                last,
+                Some(WordKind::Unit(_) | WordKind::Teen(_) | WordKind::Tens(_))
+            );
+            match kind {
+                WordKind::Unit(n) => {
+                    if (small && !matches!(last, Some(WordKind::Tens(_))))
+                        || (n == 0 && last.is_some())
+                    {
+                        break;
+                    }
+                    current += n;
+                }
+                WordKind::Teen(n) | WordKind::Tens(n) => {
+                    if small {
+                        break;
+                    }
+                    current += n;
+                }
+                WordKind::Hundred => {
+                    if current == 0 || current >= 100 {
+                        break;
+                    }
+                    current *= 100;
+                }
+                WordKind::Scale(scale) => {
+                    if current == 0 || scale >= last_scale {
+                        break;
+                    }
+                    total += current * scale;
+                    current = 0;
+                    last_scale = scale;
+                }
+            }
+            last = Some(kind);
+            i += 1;
+            len = i - start;
+        }
+        if len == 0 {
+            return None;
+        }
+
+        let mut number = Number {
+            int: total + current,
+            frac: None,
+            len,
+        };
+        // "three point one four"
+        let point = start + len;
+        if self.linked(point - 1) && self.lower(point) == Some("point") {
+            let mut frac = String::new();
+            let mut k = point + 1;
+            while self.linked(k - 1) {
+                match self.lower(k).and_then(word_kind) {
+                    Some(WordKind::Unit(d)) => frac.push_str(&d.to_string()),
+                    _ if self.lower(k) == Some("oh") => frac.push('0'),
+                    _ => break,
+                }
+                k += 1;
+            }
+            if !frac.is_empty() {
+                number.frac = Some(frac);
+                number.len = k - start;
+            }
+        }
+        Some(number)
+    }
+
+    /// "am"/"pm", "a m", "p.m." starting at `i`: (label, words consumed)
+    fn meridiem(&mut self, i: usize) -> Option<(&'static str, usize)> {
+        let label = match self.lower(i)? {
+            "am" | "a" => "AM",
+            "pm" | "p" => "PM",
+            _ => return None,
+        };
+        if matches!(self.lower(i)?, "am" | "pm") {
+            return Some((label, 1));
+        }
+        let sep = self.toks[i].sep.trim().to_string();
+        if (sep.is_empty() || sep == ".") && self.lower(i + 1) == Some("m") {
+            // Swallow the dot of "p.m."
+            if sep == "." && self.toks[i + 1].sep.starts_with('.') {
+                self.toks[i + 1].sep.remove(0);
+            }
+            return Some((label, 2));
+        }
+        None
+    }
+
+    fn ordinal(&self, i: usize) -> Option<(u64, usize)> {
+        let word = self.lower(i)?;
+        if let Some(n) = EN_ORDINALS.iter().position(|&w| !w.is_empty() && w == word) {
+            return Some((n as u64, 1));
+        }
+        if word == "thirtieth" {
+            return Some((30, 1));
+        }
+        if let Some(WordKind::Tens(tens @ (20 | 30))) = word_kind(word) {
+            let unit = self.linked_word(i)?;
+            let n = EN_ORDINALS[1..10].iter().position(|&w| w == unit)? as u64 + 1;
+            return Some((tens + n, 2));
+        }
+        None
+    }
+
+    /// Rewrite starting at word `i`: (replacement, words consumed)
+    fn rewrite(&mut self, i: usize) -> Option<(String, usize)> {
+        let word = self.lower(i)?.to_string();
+
+        // Month followed by a day: "october fifth" → "October 5"
+        if EN_MONTHS.contains(&word.as_str()) && self.linked(i) {
+            let day = self
+                .ordinal(i + 1)
+                .or_else(|| self.cardinal(i + 1).map(|n| (n.int, n.len)))
+                .filter(|(d, _)| (1..=31).contains(d));
+            if let Some((day, len)) = day {
+                let mut month = self.toks[i].word.clone();
+                month[..1].make_ascii_uppercase();
+                return Some((format!("{month} {day}"), 1 + len));
+            }
+        }
+
+        // Number words that do not combine ("nine eleven") are read out
+        if i > 0 && self.linked(i - 1) && self.lower(i - 1).and_then(word_kind).is_some() {
+            return None;
+        }
+        let number = self.cardinal(i)?;
+        let after = i + number.len;
+        let next = if self.linked(after - 1) {
+            self.lower(after).map(str::to_string)
+        } else {
+            None
+        };
+
+        // Clock time: "three thirty pm", "ten oh five a.m.", "seven o'clock"
+        if number.frac.is_none() && (1..=12).contains(&number.int) {
+            if next.as_deref() == Some("o'clock") {
+                return Some((format!("{}:00", number.int), number.len + 1));
+            }
+            let mut minutes = (0u64, 0usize);
+            if next.as_deref() == Some("oh") && self.linked(after) {
+                if let Some(WordKind::Unit(d)) = self.lower(after + 1).and_then(word_kind) {
+                    minutes = (d, 2);
+                }
+            } else if next.is_some() {
+                if let Some(m) = self.cardinal(after) {
+                    if m.frac.is_none() && (10..=59).contains(&m.int) {
+                        minutes = (m.int, m.len);
+                    }
+                }
+            }
+            let m_end = after + minutes.1;
+            if self.linked(m_end - 1) {
+                if let Some((label, len)) = self.meridiem(m_end) {
+                    let time = if minutes.1 == 0 {
+                        format!("{} {label}", number.int)
+                    } else {
+                        format!("{}:{:02} {label}", number.int, minutes.0)
+                    };
+                    return Some((time, number.len + minutes.1 + len));
+                }
+            }
+        }
+
+        // Year read in pairs: "twenty twenty six", "nineteen oh five"
+        if number.len == 1 && (19..=20).contains(&number.int) && next.is_some() {
+            let low = match next.as_deref() {
+                Some("hundred") => Some((0, 1)),
+                Some("oh") if self.linked(after) => match self.lower(after + 1).and_then(word_kind)
+                {
+                    Some(WordKind::Unit(d)) if d > 0 => Some((d, 2)),
+                    _ => None,
+                },
+                _ => self
+                    .cardinal(after)
+                    .filter(|n| n.frac.is_none() && (10..=99).contains(&n.int))
+                    .map(|n| (n.int, n.len)),
+            };
+            if let Some((low, len)) = low {
+                return Some(((number.int * 100 + low).to_string(), 1 + len));
+            }
+        }
+
+        match next.as_deref() {
+            Some("percent") => return Some((format!("{}%", number.written()), number.len + 1)),
+            Some("dollar" | "dollars" | "bucks") => {
+                let mut text = format!("${}", number.written());
+                let mut len = number.len + 1;
+                // "five dollars and twenty cents"
+                if number.frac.is_none()
+                    && self.linked(after)
+                    && self.lower(after + 1) == Some("and")
+                    && self.linked(after + 1)
+                {
+                    if let Some(cents) = self.cardinal(after + 2) {
+                        let c_end = after + 2 + cents.len;
+                        if cents.int < 100
+                            && cents.frac.is_none()
+                            && self.linked(c_end - 1)
+                            && matches!(self.lower(c_end), Some("cent" | "cents"))
+                        {
+                            text = format!("${}.{:02}", number.int, cents.int);
+                            len = c_end + 1 - i;
+                        }
+                    }
+                }
+                return Some((text, len));
+            }
+            Some("euro" | "euros") => {
+                return Some((format!("€{}", number.written()), number.len + 1))
+            }
+            Some("pound" | "pounds") => {
+                return Some((format!("£{}", number.written()), number.len + 1))
+            }
+            _ => {}
+        }
+
+        // Small integers stay as words ("one of them", "two people")
+        let dangling = next.as_deref().and_then(word_kind).is_some();
+        if (number.int >= 10 || number.frac.is_some()) && !dangling {
+            return Some((number.written(), number.len));
+        }
+        None
+    }
+}
+
+fn english(text: &str) -> String {
+    let mut prefix = String::new();
+    let mut toks: Vec<Tok> = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_ascii_alphabetic() {
+            let mut word = c.to_string();
+            while let Some(&n) = chars.peek() {
+                if n.is_ascii_alphabetic() || n == '\'' {
+                    word.push(n);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            toks.push(Tok {
+                lower: word.to_ascii_lowercase(),
+                word,
+                sep: String::new(),
+            });
+        } else if let Some(last) = toks.last_mut() {
+            last.sep.push(c);
+        } else {
+            prefix.push(c);
+        }
+    }
+
+    let mut words = Words { toks };
+    let mut out = prefix;
+    let mut i = 0;
+    while i < words.toks.len() {
+        match words.rewrite(i) {
+            Some((replacement, len)) => {
+                out.push_str(&replacement);
+                out.push_str(&words.toks[i + len - 1].sep);
+                i += len;
+            }
+            None => {
+                out.push_str(&words.toks[i].word);
+                out.push_str(&words.toks[i].sep);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_itn(cases: &[(&str, &str)]) {
+        for (spoken, written) in cases {
+            assert_eq!(inverse_normalize(spoken), *written, "input: {spoken}");
+        }
+    }
+
+    #[test]
+    fn chinese_numbers_in_context() {
+        assert_itn(&[
+            ("百分之五十", "50%"),
+            ("二零二六年十月五日", "2026年10月5日"),
+            ("三点半出发", "3:30出发"),
+            ("五十块钱", "50块钱"),
+            ("三点五元", "3.5元"),
+            ("第二十五章", "第25章"),
+            ("一万五千个", "15000个"),
+        ]);
+    }
+
+    #[test]
+    fn chinese_idioms_stay() {
+        assert_itn(&[
+            ("十分好", "十分好"),
+            ("看一下", "看一下"),
+            ("五六个", "五六个"),
+            ("三百六十行", "三百六十行"),
+            ("五块蛋糕", "五块蛋糕"),
+        ]);
+    }
+
+    #[test]
+    fn chinese_compound_and_colloquial() {
+        assert_itn(&[
+            ("两千零五十", "2050"),
+            ("一共两千零五十，对吧", "一共2050，对吧"),
+            ("五十块", "50块"),
+            ("花了五十块", "花了50块"),
+        ]);
+    }
+
+    #[test]
+    fn chinese_overflow_is_left_unconverted() {
+        assert_itn(&[
+            ("一亿亿亿个", "一亿亿亿个"),
+            ("五亿亿亿亿", "五亿亿亿亿"),
+            ("九千九百亿亿亿元", "九千九百亿亿亿元"),
+        ]);
+        assert_eq!(
+            parse_cn_int(&"一亿亿".chars().collect::<Vec<_>>()),
+            Some(10u64.pow(16))
+        );
+    }
+
+    #[test]
+    fn english_numbers_in_context() {
+        assert_itn(&[
+            ("three thirty pm", "3:30 PM"),
+            ("ten oh five a.m.", "10:05 AM"),
+            ("twenty five dollars", "$25"),
+            ("five dollars and twenty cents", "$5.20"),
+            ("twenty twenty six", "2026"),
+            ("october fifth", "October 5"),
+            ("fifty percent", "50%"),
+            ("three point one four", "3.14"),
+            ("one of them", "one of them"),
+        ]);
+    }
+
+    #[test]
+    fn english_number_words_that_do_not_combine_stay() {
+        assert_itn(&[
+            ("nine eleven", "nine eleven"),
+            ("remember nine eleven.", "remember nine eleven."),
+            (
+                "one billion billion billion dollars",
+                "one billion billion billion dollars",
+            ),
+            ("one billion dollars", "$1000000000"),
+        ]);
+    }
+}
@@ -0,0 +1,7 @@
+//! Transcript text post-processing
+
+pub mod cleanup;
+pub mod itn;
+pub mod script;
+
+pub use script::Script;
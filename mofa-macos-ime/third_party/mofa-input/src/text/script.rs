@@ -0,0 +1,385 @@
+//! Traditional ↔ Simplified Chinese conversion
+//!
+//! Phrase tables resolve the ambiguous cases (发 → 發/髮, 干 → 乾/幹, ...) by
+//! longest match; everything else goes through a one-to-one character table.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Target Chinese script
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Script {
+    Simplified,
+    Traditional,
+}
+
+/// Convert `text` to the given script; non-Chinese text passes through
+pub fn convert(text: &str, script: Script) -> String {
+    let tables = tables();
+    match script {
+        Script::Simplified => tables.t2s.convert(text),
+        Script::Traditional => tables.s2t.convert(text),
+    }
+}
+
+pub fn to_simplified(text: &str) -> String {
+    convert(text, Script::Simplified)
+}
+
+pub fn to_traditional(text: &str) -> String {
+    convert(text, Script::Traditional)
+}
+
+/// Traditional/Simplified character pairs; for simplified characters with
+/// several traditional forms the first pair is the default reverse mapping,
+/// using the Taiwan form where variants differ (里 → 裡)
+const CHAR_PAIRS: &str = "\
+    萬万 與与 醜丑 專专 業业 叢丛 東东 絲丝 丟丢 兩两 嚴严 喪丧 個个 豐丰 臨临 為为 \
+    麗丽 舉举 麼么 義义 烏乌 樂乐 喬乔 習习 鄉乡 書书 買买 亂乱 爭争 於于 虧亏 雲云 \
+    亞亚 產产 畝亩 親亲 褻亵 億亿 僅仅 從从 侖仑 倉仓 儀仪 們们 價价 眾众 優优 會会 \
+    傘伞 偉伟 傳传 傷伤 倫伦 偽伪 體体 餘余 傭佣 僉佥 俠侠 侶侣 僥侥 偵侦 側侧 僑侨 \
+    儈侩 儕侪 儂侬 儔俦 儼俨 倆俩 儷俪 儉俭 債债 傾倾 僂偻 僨偾 償偿 儻傥 儐傧 儲储 \
+    儺傩 兒儿 兌兑 兗兖 黨党 蘭兰 關关 興兴 茲兹 養养 獸兽 內内 岡冈 冊册 寫写 軍军 \
+    農农 馮冯 衝冲 決决 況况 凍冻 淨净 涼凉 減减 湊凑 凜凛 幾几 鳳凤 憑凭 凱凯 擊击 \
+    鑿凿 劃划 劉刘 則则 剛刚 創创 刪删 別别 劊刽 劑剂 剮剐 劍剑 剝剥 劇剧 勸劝 辦办 \
+    務务 動动 勵励 勁劲 勞劳 勢势 勳勋 勻匀 匱匮 區区 醫医 華华 協协 單单 賣卖 盧卢 \
+    滷卤 衛卫 卻却 廠厂 廳厅 歷历 曆历 厲厉 壓压 厭厌 廁厕 廂厢 廈厦 廚厨 廄厩 廝厮 \
+    縣县 參参 雙双 變变 敘叙 疊叠 葉叶 號号 嘆叹 嘰叽 籲吁 後后 嚇吓 呂吕 嗎吗 噸吨 \
+    聽听 啟启 吳吴 吶呐 囈呓 嘔呕 唄呗 員员 嗆呛 嗚呜 詠咏 嚨咙 嚀咛 響响 啞哑 噠哒 \
+    嘩哗 噥哝 喲哟 嘮唠 嗩唢 喚唤 嘖啧 嗇啬 囀啭 嚙啮 嘯啸 噴喷 嘍喽 囁嗫 噯嗳 噓嘘 \
+    嚶嘤 囑嘱 嚕噜 團团 園园 圍围 圇囵 國国 圖图 圓圆 聖圣 場场 壞坏 塊块 堅坚 壇坛 \
+    壩坝 塢坞 墳坟 墜坠 壟垄 壘垒 墾垦 墊垫 塹堑 墮堕 牆墙 壯壮 聲声 殼壳 壺壶 處处 \
+    備备 復复 複复 夠够 頭头 誇夸 夾夹 奪夺 奮奋 獎奖 奧奥 妝妆 婦妇 媽妈 嫵妩 嫗妪 \
+    姍姗 婁娄 婭娅 嬈娆 嬌娇 娛娱 媧娲 嫻娴 嬰婴 嬋婵 嬸婶 嬡嫒 嬪嫔 嬤嬷 孫孙 學学 \
+    孿孪 寧宁 寶宝 實实 寵宠 審审 憲宪 宮宫 寬宽 賓宾 寢寝 對对 尋寻 導导 壽寿 將将 \
+    爾尔 塵尘 嘗尝 堯尧 尷尴 屍尸 盡尽 儘尽 層层 屜屉 屆届 屬属 屢屡 嶼屿 歲岁 豈岂 \
+    嶇岖 崗岗 嵐岚 島岛 嶺岭 嶽岳 峽峡 崢峥 巒峦 嶄崭 嶸嵘 巔巅 鞏巩 幣币 帥帅 師师 \
+    帳帐 簾帘 幟帜 帶带 幀帧 幫帮 幗帼 冪幂 莊庄 慶庆 廬庐 庫库 應应 廟庙 龐庞 廢废 \
+    開开 異异 棄弃 張张 彌弥 彎弯 彈弹 強强 歸归 當当 錄录 匯汇 彙汇 彥彦 徹彻 徑径 \
+    憶忆 懺忏 憂忧 懷怀 態态 慫怂 憮怃 慪怄 悵怅 愴怆 憐怜 總总 戀恋 懇恳 惡恶 慟恸 \
+    愷恺 惻恻 惱恼 悅悦 懸悬 憫悯 驚惊 懼惧 慘惨 懲惩 憊惫 愜惬 慚惭 憚惮 慣惯 慍愠 \
+    憤愤 願愿 懾慑 懶懒 戲戏 戰战 戶户 紮扎 撲扑 執执 擴扩 掃扫 揚扬 擾扰 撫抚 摳抠 \
+    掄抡 搶抢 護护 報报 擔担 擬拟 攏拢 揀拣 擁拥 攔拦 擰拧 撥拨 擇择 掛挂 摯挚 攣挛 \
+    撻挞 挾挟 撓挠 擋挡 掙挣 擠挤 揮挥 撈捞 損损 撿捡 換换 搗捣 據据 擄掳 摑掴 擲掷 \
+    撣掸 摻掺 攬揽 攙搀 擱搁 摟搂 攪搅 攜携 攝摄 擺摆 搖摇 擯摈 攤摊 撐撑 攆撵 擷撷 \
+    擼撸 攛撺 擻擞 攢攒 敵敌 斂敛 數数 齋斋 鬥斗 斬斩 斷断 無无 舊旧 時时 曠旷 曇昙 \
+    晝昼 顯显 晉晋 曬晒 曉晓 曄晔 暈晕 暉晖 暫暂 曖暧 術术 樸朴 機机 殺杀 雜杂 權权 \
+    條条 來来 楊杨 傑杰 極极 構构 樞枢 棗枣 槍枪 楓枫 梟枭 櫃柜 檸柠 柵栅 標标 棧栈 \
+    棟栋 欄栏 樹树 棲栖 樣样 檔档 橋桥 樺桦 檜桧 槳桨 樁桩 夢梦 檢检 槓杠 橢椭 樓楼 \
+    欖榄 櫚榈 櫸榉 檻槛 檳槟 橫横 櫻樱 櫥橱 櫓橹 歡欢 歐欧 殲歼 殘残 殞殒 殮殓 殯殡 \
+    毆殴 毀毁 畢毕 斃毙 氈毡 氣气 氫氢 漢汉 湯汤 溝沟 沒没 淪沦 滄沧 滬沪 濘泞 淚泪 \
+    瀉泻 潑泼 澤泽 潔洁 灑洒 窪洼 淺浅 漿浆 澆浇 濁浊 測测 濟济 瀏浏 渾浑 濃浓 濤涛 \
+    澇涝 漣涟 渦涡 渙涣 滌涤 潤润 澗涧 漲涨 澀涩 淵渊 漬渍 漸渐 漁渔 瀋沈 滲渗 溫温 \
+    遊游 灣湾 濕湿 潰溃 濺溅 滯滞 滿满 濾滤 濫滥 濱滨 灘滩 瀟潇 瀲潋 濰潍 潛潜 瀾澜 \
+    瀨濑 瀕濒 滅灭 燈灯 靈灵 災灾 燦灿 爐炉 燉炖 點点 煉炼 熾炽 爍烁 爛烂 燭烛 煙烟 \
+    煩烦 燒烧 燴烩 燙烫 燼烬 熱热 煥焕 燜焖 愛爱 爺爷 牘牍 犛牦 牽牵 犧牺 犢犊 狀状 \
+    猶犹 狽狈 獰狞 獨独 狹狭 獅狮 猙狰 獄狱 獵猎 獼猕 豬猪 貓猫 獻献 獺獭 瑪玛 環环 \
+    現现 璽玺 瓏珑 璉琏 瑣琐 瓊琼 瑤瑶 甌瓯 電电 畫画 暢畅 療疗 瘧疟 瘍疡 瘡疮 瘋疯 \
+    癢痒 癆痨 瘓痪 癇痫 癡痴 瘻瘘 癟瘪 癱瘫 癮瘾 癩癞 癬癣 癲癫 皚皑 皺皱 盞盏 鹽盐 \
+    監监 蓋盖 盜盗 盤盘 睜睁 瞼睑 瞞瞒 矚瞩 矯矫 磯矶 礦矿 碼码 磚砖 硯砚 礪砺 礫砾 \
+    礎础 碩硕 確确 鹼碱 礙碍 禮礼 禎祯 禱祷 禍祸 稟禀 祿禄 禪禅 離离 禿秃 稈秆 種种 \
+    積积 稱称 穢秽 稅税 穩稳 窮穷 竊窃 竅窍 窯窑 竄窜 窩窝 窺窥 竇窦 豎竖 競竞 篤笃 \
+    筍笋 筆笔 箋笺 籠笼 築筑 篩筛 箏筝 籌筹 簽签 簡简 簍篓 籃篮 籬篱 籟籁 類类 糞粪 \
+    糧粮 緊紧 糾纠 紅红 纖纤 約约 級级 紀纪 緯纬 純纯 紗纱 綱纲 納纳 縱纵 綸纶 紛纷 \
+    紙纸 紋纹 紡纺 紐纽 線线 練练 組组 紳绅 細细 織织 終终 絆绊 紹绍 繹绎 經经 綁绑 \
+    絨绒 結结 繞绕 給给 絢绚 絡络 絕绝 絞绞 統统 絹绢 繡绣 繼继 績绩 緒绪 續续 綺绮 \
+    緋绯 綽绰 繩绳 維维 綿绵 繃绷 綢绸 綜综 綻绽 綠绿 綴缀 緘缄 緬缅 纜缆 緝缉 緞缎 \
+    緩缓 締缔 縷缕 編编 緣缘 縛缚 縫缝 纏缠 縮缩 繆缪 繚缭 繕缮 罌罂 網网 羅罗 罰罚 \
+    罷罢 羈羁 翹翘 聳耸 恥耻 聶聂 聾聋 職职 聯联 聰聪 肅肃 腸肠 膚肤 腎肾 腫肿 脹胀 \
+    脅胁 膽胆 勝胜 朧胧 脛胫 膠胶 脈脉 髒脏 臍脐 腦脑 膿脓 腳脚 脫脱 臉脸 臘腊 醃腌 \
+    膩腻 騰腾 輿舆 艦舰 艙舱 艱艰 豔艳 藝艺 節节 蘆芦 葦苇 蒼苍 蘋苹 莖茎 繭茧 荊荆 \
+    薦荐 莢荚 蕎荞 薈荟 蕩荡 榮荣 葷荤 熒荧 蔭荫 藥药 萊莱 蓮莲 獲获 穫获 瑩莹 鶯莺 \
+    蘿萝 螢萤 營营 縈萦 蕭萧 薩萨 蔥葱 蔣蒋 藍蓝 薔蔷 藹蔼 蘊蕴 蘚藓 虜虏 慮虑 虛虚 \
+    蟲虫 雖虽 蝦虾 蝕蚀 蟻蚁 螞蚂 蠶蚕 蠔蚝 蠱蛊 蠻蛮 蟄蛰 蛻蜕 蝸蜗 蠟蜡 蠅蝇 蟬蝉 \
+    蠍蝎 釁衅 銜衔 補补 襯衬 襖袄 襪袜 襲袭 裝装 褲裤 見见 觀观 規规 覓觅 視视 覽览 \
+    覺觉 覬觊 覷觑 觸触 訂订 計计 訊讯 討讨 訓训 託托 記记 訝讶 訟讼 訣诀 設设 訪访 \
+    證证 評评 詛诅 識识 詐诈 訴诉 診诊 詞词 譯译 試试 詩诗 誠诚 話话 誕诞 詮诠 詭诡 \
+    詢询 該该 詳详 詫诧 誡诫 誣诬 語语 誤误 誘诱 誨诲 說说 誦诵 請请 諸诸 諾诺 讀读 \
+    誹诽 課课 誰谁 調调 諒谅 談谈 誼谊 謀谋 諜谍 謊谎 諧谐 謂谓 諭谕 諮谘 諺谚 諦谛 \
+    謎谜 謝谢 謠谣 謙谦 謹谨 謬谬 譜谱 譴谴 貝贝 貞贞 負负 貢贡 財财 責责 賢贤 敗败 \
+    賬账 貨货 質质 販贩 貪贪 貧贫 貶贬 購购 貯贮 貫贯 貳贰 賤贱 貼贴 貴贵 貸贷 貿贸 \
+    費费 賀贺 賊贼 賈贾 賄贿 賃赁 賂赂 贓赃 資资 賑赈 賦赋 賭赌 贖赎 賞赏 賜赐 賠赔 \
+    賴赖 贅赘 賺赚 賽赛 贈赠 贊赞 贏赢 趙赵 趕赶 趨趋 躍跃 蹌跄 跡迹 蹟迹 踐践 蹺跷 \
+    踴踊 躊踌 蹤踪 躡蹑 軀躯 車车 軋轧 軌轨 軒轩 轉转 輪轮 軟软 轟轰 軸轴 輕轻 載载 \
+    轎轿 較较 輔辅 輛辆 輩辈 輝辉 輯辑 輸输 轄辖 轍辙 辭辞 辯辩 邊边 遼辽 達达 遷迁 \
+    過过 邁迈 運运 還还 這这 進进 遠远 違违 連连 遲迟 適适 選选 遜逊 遞递 邏逻 遺遗 \
+    遙遥 鄧邓 郵邮 鄒邹 鄰邻 鬱郁 鄭郑 醞酝 醬酱 釀酿 釋释 裡里 裏里 鑒鉴 針针 釘钉 \
+    釣钓 鈣钙 鈍钝 鈔钞 鐘钟 鍾钟 鋼钢 鑰钥 欽钦 鉤钩 鈕钮 錢钱 鉗钳 鑽钻 鈴铃 鉛铅 \
+    鐵铁 銅铜 鋁铝 銘铭 鏟铲 銀银 鑄铸 鋪铺 鏈链 鏗铿 銷销 鎖锁 鋤锄 鍋锅 銹锈 鏽锈 \
+    鋒锋 鋅锌 銳锐 錯错 錨锚 錫锡 錦锦 鍵键 鋸锯 錘锤 錐锥 鍛锻 鎬镐 鎮镇 鏡镜 鏢镖 \
+    鐮镰 鑲镶 鑼锣 長长 門门 閃闪 閉闭 問问 闖闯 閑闲 間间 悶闷 閘闸 鬧闹 閨闺 聞闻 \
+    閥阀 閣阁 閱阅 闊阔 闡阐 闢辟 隊队 陽阳 陰阴 陣阵 階阶 際际 陸陆 隴陇 陳陈 陝陕 \
+    隕陨 險险 隨随 隱隐 隸隶 難难 雛雏 靂雳 霧雾 黴霉 靄霭 靜静 韃鞑 韋韦 韌韧 韓韩 \
+    韻韵 頁页 頂顶 頃顷 項项 順顺 須须 頑顽 顧顾 頓顿 頒颁 頌颂 預预 顱颅 領领 頗颇 \
+    頸颈 頰颊 頻频 頹颓 穎颖 顆颗 題题 顏颜 額额 顛颠 顫颤 風风 颯飒 颱台 飄飘 飛飞 \
+    飢饥 飯饭 飲饮 飾饰 飽饱 飼饲 餌饵 饒饶 餃饺 餅饼 餓饿 餡馅 館馆 饋馈 饞馋 饅馒 \
+    馬马 馭驭 馳驰 馴驯 驢驴 駁驳 駐驻 駝驼 駕驾 駛驶 駭骇 駱骆 驕骄 驗验 駿骏 騎骑 \
+    騙骗 騷骚 驅驱 驛驿 驟骤 骯肮 髏髅 鬢鬓 魚鱼 魯鲁 鮑鲍 鮮鲜 鯉鲤 鯨鲸 鰻鳗 鱗鳞 \
+    鳥鸟 雞鸡 鴨鸭 鴛鸳 鴦鸯 鴿鸽 鵝鹅 鵬鹏 鵲鹊 鶴鹤 鷹鹰 鷺鹭 鸚鹦 麥麦 黃黄 齊齐 \
+    齒齿 齡龄 齣出 龍龙 龔龚 龜龟 讓让 發发 髮发 麵面 係系 繫系 幹干 乾干 鬆松 範范 \
+    準准 隻只 穀谷 盪荡 鹹咸 製制 嚮向 彆别 鬍胡 捲卷 樑梁 薑姜 擡抬 摺折 檯台 臺台 \
+    囉啰 喫吃 纔才 餵喂 著着 衊蔑 廣广 瀰弥 蘇苏 囌苏 餚肴 戇戆 僕仆 傢家 闆板 噁恶 \
+    噹当 嚐尝 兇凶 硃朱 誌志 佈布 週周 鑑鉴 徵征 迴回 捨舍 祕秘 閒闲 議议 論论 認认 \
+    許许 講讲";
+
+/// Simplified characters that are also standard traditional characters; they
+/// are only converted by a phrase rule
+const S2T_KEEP: &str =
+    "台面系松谷范准只丑冲制向胡卷托梁姜折吃才咸划出辟沈干家板凶志布周征回舍秘朱蔑肴别喂抬郁游朴斗";
+
+/// Traditional → Simplified phrases the character table gets wrong
+const T2S_PHRASES: &[(&str, &str)] = &[
+    ("乾坤", "乾坤"),
+    ("乾隆", "乾隆"),
+    ("著名", "著名"),
+    ("著作", "著作"),
+    ("著者", "著者"),
+    ("名著", "名著"),
+    ("巨著", "巨著"),
+    ("顯著", "显著"),
+    ("甚麼", "什么"),
+];
+
+/// Simplified → Traditional phrases for one-to-many characters
+const S2T_PHRASES: &[(&str, &str)] = &[
+    ("头发", "頭髮"),
+    ("理发", "理髮"),
+    ("发型", "髮型"),
+    ("白发", "白髮"),
+    ("毛发", "毛髮"),
+    ("干净", "乾淨"),
+    ("干燥", "乾燥"),
+    ("饼干", "餅乾"),
+    ("干杯", "乾杯"),
+    ("干脆", "乾脆"),
+    ("干部", "幹部"),
+    ("干活", "幹活"),
+    ("干嘛", "幹嘛"),
+    ("干什么", "幹什麼"),
+    ("能干", "能幹"),
+    ("才干", "才幹"),
+    ("主干", "主幹"),
+    ("树干", "樹幹"),
+    ("这里", "這裡"),
+    ("那里", "那裡"),
+    ("哪里", "哪裡"),
+    ("里面", "裡面"),
+    ("公里", "公里"),
+    ("皇后", "皇后"),
+    ("王后", "王后"),
+    ("太后", "太后"),
+    ("面条", "麵條"),
+    ("面包", "麵包"),
+    ("面粉", "麵粉"),
+    ("方便面", "方便麵"),
+    ("关系", "關係"),
+    ("联系", "聯繫"),
+    ("一只", "一隻"),
+    ("两只", "兩隻"),
+    ("几只", "幾隻"),
+    ("准备", "準備"),
+    ("标准", "標準"),
+    ("准确", "準確"),
+    ("准时", "準時"),
+    ("水准", "水準"),
+    ("范围", "範圍"),
+    ("模范", "模範"),
+    ("规范", "規範"),
+    ("示范", "示範"),
+    ("制造", "製造"),
+    ("制作", "製作"),
+    ("复制", "複製"),
+    ("印制", "印製"),
+    ("复杂", "複雜"),
+    ("重复", "重複"),
+    ("复印", "複印"),
+    ("复数", "複數"),
+    ("复合", "複合"),
+    ("放松", "放鬆"),
+    ("轻松", "輕鬆"),
+    ("松开", "鬆開"),
+    ("台湾", "臺灣"),
+    ("台北", "臺北"),
+    ("台风", "颱風"),
+    ("舞台", "舞臺"),
+    ("柜台", "櫃檯"),
+    ("日历", "日曆"),
+    ("农历", "農曆"),
+    ("历法", "曆法"),
+    ("词汇", "詞彙"),
+    ("汇总", "彙總"),
+    ("收获", "收穫"),
+    ("冲突", "衝突"),
+    ("冲动", "衝動"),
+    ("胡子", "鬍子"),
+    ("胡须", "鬍鬚"),
+    ("委托", "委託"),
+    ("拜托", "拜託"),
+    ("生姜", "生薑"),
+    ("咸鱼", "鹹魚"),
+    ("咸味", "鹹味"),
+    ("开辟", "開闢"),
+    ("沈阳", "瀋陽"),
+    ("忧郁", "憂鬱"),
+    ("旅游", "旅遊"),
+    ("游戏", "遊戲"),
+    ("朴素", "樸素"),
+    ("斗争", "鬥爭"),
+    ("战斗", "戰鬥"),
+    ("奋斗", "奮鬥"),
+    ("特征", "特徵"),
+    ("象征", "象徵"),
+    ("家具", "傢具"),
+    ("老板", "老闆"),
+    ("恶心", "噁心"),
+    ("周末", "週末"),
+    ("一周", "一週"),
+    ("上周", "上週"),
+    ("下周", "下週"),
+    ("回复", "回覆"),
+];
+
+struct Tables {
+    t2s: Converter,
+    s2t: Converter,
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let mut t2s_chars = HashMap::new();
+        let mut s2t_chars = HashMap::new();
+        for pair in CHAR_PAIRS.split_whitespace() {
+            let mut chars = pair.chars();
+            let (Some(t), Some(s)) = (chars.next(), chars.next()) else {
+                continue;
+            };
+            t2s_chars.insert(t, s);
+            if !S2T_KEEP.contains(s) {
+                s2t_chars.entry(s).or_insert(t);
+            }
+        }
+        Tables {
+            t2s: Converter::new(t2s_chars, T2S_PHRASES),
+            s2t: Converter::new(s2t_chars, S2T_PHRASES),
+        }
+    })
+}
+
+/// Longest-match phrase lookup followed by per-character mapping
+struct Converter {
+    chars: HashMap<char, char>,
+    phrases: HashMap<&'static str, &'static str>,
+    max_phrase_len: usize,
+}
+
+impl Converter {
+    fn new(chars: HashMap<char, char>, phrases: &[(&'static str, &'static str)]) -> Self {
+        let max_phrase_len = phrases
+            .iter()
+            .map(|(from, _)| from.chars().count())
+            .max()
+            .unwrap_or(0);
+        Self {
+            chars,
+            phrases: phrases.iter().copied().collect(),
+            max_phrase_len,
+        }
+    }
+
+    fn convert(&self, text: &str) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        let mut out = String::with_capacity(text.len());
+        let mut i = 0;
+        let mut key = String::new();
+        'outer: while i < chars.len() {
+            let longest = self.max_phrase_len.min(chars.len() - i);
+            for len in (2..=longest).rev() {
+                key.clear();
+                key.extend(&chars[i..i + len]);
+                if let Some(to) = self.phrases.get(key.as_str()) {
+                    out.push_str(to);
+                    i += len;
+                    continue 'outer;
+                }
+            }
+            let c = chars[i];
+            out.push(*self.chars.get(&c).unwrap_or(&c));
+            i += 1;
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_both_ways() {
+        let cases = [
+            ("我们的头发很干净", "我們的頭髮很乾淨"),
+            (
+                "这个问题很复杂，需要重复检查",
+                "這個問題很複雜，需要重複檢查",
+            ),
+            ("下周去台北旅游", "下週去臺北旅遊"),
+            ("他在干部会议上发言", "他在幹部會議上發言"),
+            ("我认为这个讨论值得讲一讲", "我認為這個討論值得講一講"),
+        ];
+        for (simplified, traditional) in cases {
+            assert_eq!(to_traditional(simplified), traditional);
+            assert_eq!(to_simplified(traditional), simplified);
+            assert_eq!(to_simplified(&to_traditional(simplified)), simplified);
+            assert_eq!(to_traditional(&to_simplified(traditional)), traditional);
+        }
+    }
+
+    #[test]
+    fn uses_one_form_of_li() {
+        assert_eq!(to_traditional("里"), "裡");
+        assert_eq!(to_traditional("这里的里面，心里"), "這裡的裡面，心裡");
+        assert_eq!(to_traditional("五公里"), "五公里");
+        assert_eq!(to_simplified("這裡和那裏"), "这里和那里");
+    }
+
+    #[test]
+    fn longest_phrase_wins() {
+        let converter = Converter::new(
+            HashMap::from([('a', 'A'), ('b', 'B'), ('c', 'C')]),
+            &[("ab", "xy"), ("abc", "XYZ"), ("bc", "yz")],
+        );
+        assert_eq!(converter.convert("abcab"), "XYZxy");
+        assert_eq!(converter.convert("cabc"), "CXYZ");
+        assert_eq!(converter.convert("abab"), "xyxy");
+
+        assert_eq!(to_traditional("你干什么"), "你幹什麼");
+        assert_eq!(to_traditional("吃方便面"), "吃方便麵");
+        assert_eq!(to_simplified("甚麼"), "什么");
+    }
+
+    #[test]
+    fn keeps_shared_characters_outside_phrases() {
+        // Standard in both scripts, so only phrase rules change them
+        assert_eq!(to_traditional("面对"), "面對");
+        assert_eq!(to_traditional("只有"), "只有");
+        assert_eq!(to_traditional("一只猫"), "一隻貓");
+        assert_eq!(to_traditional("台上"), "台上");
+        assert_eq!(to_traditional("干"), "干");
+        assert_eq!(to_traditional("松树"), "松樹");
+        assert_eq!(to_traditional("放松"), "放鬆");
+    }
+
+    #[test]
+    fn mixed_input_passes_through() {
+        assert_eq!(
+            to_traditional("Hello 世界, 这里有 123 个 apple!"),
+            "Hello 世界, 這裡有 123 個 apple!"
+        );
+        // Already mixed scripts end up in one
+        assert_eq!(to_simplified("我們这里發现了问題"), "我们这里发现了问题");
+        assert_eq!(to_traditional("我們这里發现了问題"), "我們這裡發現了問題");
+        assert_eq!(convert("", Script::Traditional), "");
+        assert_eq!(convert("こんにちは", Script::Simplified), "こんにちは");
+    }
+}